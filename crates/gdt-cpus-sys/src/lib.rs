@@ -11,8 +11,12 @@
 //! - Functions return `i32` error codes (`GdtCpusErrorCode`); `0` = success.
 //! - Results are written through out-pointers; `NULL` out-pointers return
 //!   `InvalidParameter`.
-//! - Detection runs once on first use and is cached for the process lifetime;
-//!   returned strings (`vendor_name`, `model_name`) stay valid forever after.
+//! - After a failing call, `gdt_cpus_last_error_message()` returns the
+//!   detailed message behind the code (errno-style, per thread).
+//! - Detection runs on first use and its result is cached for the process
+//!   lifetime; returned strings (`vendor_name`, `model_name`) stay valid
+//!   forever after. A failed detection is not cached - the next call detects
+//!   again, so a transient failure does not break every later call.
 //! - Affinity masks cross the FFI as arrays of OS logical-processor ids.
 //!
 //! # Usage Example (Conceptual C Code)
@@ -30,6 +34,7 @@
 
 #![deny(missing_docs)]
 
+use std::cell::RefCell;
 use std::ffi::CString;
 use std::os::raw::c_char;
 use std::sync::OnceLock;
@@ -48,37 +53,80 @@ struct CpuInfoContainer {
     vendor_name_storage: CString,
 }
 
-static CPU_INFO_CONTAINER: OnceLock<Result<CpuInfoContainer, gdt_cpus::Error>> = OnceLock::new();
-
-fn container() -> Result<&'static CpuInfoContainer, GdtCpusErrorCode> {
-    let result = CPU_INFO_CONTAINER.get_or_init(|| {
-        let info = gdt_cpus::CpuInfo::detect()?;
-        let model_name_storage = CString::new(info.model_name.clone()).unwrap_or_default();
-        let vendor_name_storage = CString::new(info.vendor.to_string()).unwrap_or_default();
-        Ok(CpuInfoContainer {
-            info,
-            model_name_storage,
-            vendor_name_storage,
-        })
-    });
-    match result {
-        Ok(c) => Ok(c),
-        Err(e) => Err(GdtCpusErrorCode::from(e)),
+static CPU_INFO_CONTAINER: OnceLock<CpuInfoContainer> = OnceLock::new();
+
+fn container() -> Result<&'static CpuInfoContainer, i32> {
+    container_in(&CPU_INFO_CONTAINER, gdt_cpus::CpuInfo::detect)
+}
+
+/// The container in `cell`, or one built from `detect` and stored there if
+/// detection succeeds. A failure is reported and NOT stored - the next call
+/// runs `detect` again. Racing first calls may each detect; one value wins.
+fn container_in(
+    cell: &'static OnceLock<CpuInfoContainer>,
+    detect: impl FnOnce() -> gdt_cpus::Result<gdt_cpus::CpuInfo>,
+) -> Result<&'static CpuInfoContainer, i32> {
+    if let Some(c) = cell.get() {
+        return Ok(c);
     }
+
+    let info = detect().map_err(|e| fail_with(&e))?;
+    let model_name_storage = CString::new(info.model_name.clone()).unwrap_or_default();
+    let vendor_name_storage = CString::new(info.vendor.to_string()).unwrap_or_default();
+    Ok(cell.get_or_init(|| CpuInfoContainer {
+        info,
+        model_name_storage,
+        vendor_name_storage,
+    }))
 }
 
 macro_rules! get_info_validate_out_or_err {
     ($out:ident) => {{
         if $out.is_null() {
-            return GdtCpusErrorCode::InvalidParameter as i32;
+            return fail(
+                GdtCpusErrorCode::InvalidParameter,
+                concat!("`", stringify!($out), "` is NULL"),
+            );
         }
         match container() {
             Ok(c) => c,
-            Err(code) => return code as i32,
+            Err(code) => return code,
         }
     }};
 }
 
+thread_local! {
+    /// Detail behind this thread's most recent failing call, for
+    /// [`gdt_cpus_last_error_message`]. Cleared by every successful call.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Records `message` as this thread's last error and returns `code`.
+fn fail(code: GdtCpusErrorCode, message: impl std::fmt::Display) -> i32 {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    code as i32
+}
+
+/// [`fail`] with a library error's code and message.
+fn fail_with(e: &gdt_cpus::Error) -> i32 {
+    fail(GdtCpusErrorCode::from(e), e)
+}
+
+/// `OutOfBounds` for `index` into a table of `len` `what`s.
+fn out_of_bounds(what: &str, index: u64, len: usize) -> i32 {
+    fail(
+        GdtCpusErrorCode::OutOfBounds,
+        format_args!("{} index {} out of bounds (count {})", what, index, len),
+    )
+}
+
+/// Clears this thread's last error and returns `Success`.
+fn succeed() -> i32 {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    GdtCpusErrorCode::Success as i32
+}
+
 /// C-compatible enumeration for error codes returned by FFI functions.
 ///
 /// `Success` (0) indicates no error. Negative values indicate errors.
@@ -111,15 +159,18 @@ pub enum GdtCpusErrorCode {
 
 impl From<&gdt_cpus::Error> for GdtCpusErrorCode {
     fn from(e: &gdt_cpus::Error) -> Self {
-        match e {
-            gdt_cpus::Error::Detection(_) => GdtCpusErrorCode::Detection,
-            gdt_cpus::Error::InvalidCoreId(_) => GdtCpusErrorCode::InvalidCoreId,
-            gdt_cpus::Error::Affinity(_) => GdtCpusErrorCode::Affinity,
-            gdt_cpus::Error::Unsupported(_) => GdtCpusErrorCode::Unsupported,
-            gdt_cpus::Error::PermissionDenied(_) => GdtCpusErrorCode::PermissionDenied,
-            gdt_cpus::Error::SystemCall(_) => GdtCpusErrorCode::SystemCall,
-            gdt_cpus::Error::NotFound(_) => GdtCpusErrorCode::NotFound,
-            gdt_cpus::Error::InvalidParameter(_) => GdtCpusErrorCode::InvalidParameter,
+        match e.kind() {
+            gdt_cpus::ErrorKind::Detection => GdtCpusErrorCode::Detection,
+            gdt_cpus::ErrorKind::InvalidCoreId => GdtCpusErrorCode::InvalidCoreId,
+            gdt_cpus::ErrorKind::Affinity => GdtCpusErrorCode::Affinity,
+            gdt_cpus::ErrorKind::Unsupported => GdtCpusErrorCode::Unsupported,
+            gdt_cpus::ErrorKind::PermissionDenied => GdtCpusErrorCode::PermissionDenied,
+            gdt_cpus::ErrorKind::SystemCall => GdtCpusErrorCode::SystemCall,
+            gdt_cpus::ErrorKind::NotFound => GdtCpusErrorCode::NotFound,
+            gdt_cpus::ErrorKind::InvalidParameter => GdtCpusErrorCode::InvalidParameter,
+            // ErrorKind is non-exhaustive: kinds added later map to Unknown
+            // until they get a code of their own.
+            _ => GdtCpusErrorCode::Unknown,
         }
     }
 }
@@ -148,6 +199,8 @@ pub enum GdtCpusVendor {
     Nvidia = 8,
     /// Marvell / Cavium.
     Marvell = 9,
+    /// Ampere Computing.
+    Ampere = 10,
 }
 
 impl From<gdt_cpus::Vendor> for GdtCpusVendor {
@@ -161,6 +214,7 @@ impl From<gdt_cpus::Vendor> for GdtCpusVendor {
             gdt_cpus::Vendor::Broadcom => GdtCpusVendor::Broadcom,
             gdt_cpus::Vendor::Nvidia => GdtCpusVendor::Nvidia,
             gdt_cpus::Vendor::Marvell => GdtCpusVendor::Marvell,
+            gdt_cpus::Vendor::Ampere => GdtCpusVendor::Ampere,
             gdt_cpus::Vendor::Other => GdtCpusVendor::Other,
             gdt_cpus::Vendor::Unknown => GdtCpusVendor::Unknown,
         }
@@ -240,7 +294,7 @@ ffi_enum_from_i32!(
     vendor_from_i32,
     GdtCpusVendor,
     [
-        Intel, Amd, Arm, Apple, Unknown, Other, Qualcomm, Broadcom, Nvidia, Marvell
+        Intel, Amd, Arm, Apple, Unknown, Other, Qualcomm, Broadcom, Nvidia, Marvell, Ampere
     ]
 );
 ffi_enum_from_i32!(
@@ -339,6 +393,10 @@ pub enum GdtCpusCpuFeatures {
     BMI2 = 0x00100000,
     /// F16C (half-precision float conversion) support.
     F16C = 0x00200000,
+    /// RTM (transactional memory) reported by CPUID; microcode may disable it.
+    RTM = 0x00400000,
+    /// HLE (hardware lock elision) reported by CPUID; microcode may disable it.
+    HLE = 0x00800000,
 }
 
 /// C-compatible enumeration for CPU features on aarch64 architecture (bitmask).
@@ -568,6 +626,10 @@ pub enum GdtCpusMechanismPolicy {
     Qos = 3,
     /// Windows `SetThreadPriority` - `value` is the THREAD_PRIORITY constant (-15..15).
     WinPriority = 4,
+    /// Linux `SCHED_DEADLINE` - `value` is unused (0).
+    SchedDeadline = 5,
+    /// macOS `THREAD_TIME_CONSTRAINT_POLICY` - `value` is unused (0).
+    TimeConstraint = 6,
 }
 
 impl From<gdt_cpus::MechanismPolicy> for GdtCpusMechanismPolicy {
@@ -578,6 +640,8 @@ impl From<gdt_cpus::MechanismPolicy> for GdtCpusMechanismPolicy {
             gdt_cpus::MechanismPolicy::SchedOther => GdtCpusMechanismPolicy::SchedOther,
             gdt_cpus::MechanismPolicy::Qos => GdtCpusMechanismPolicy::Qos,
             gdt_cpus::MechanismPolicy::WinPriority => GdtCpusMechanismPolicy::WinPriority,
+            gdt_cpus::MechanismPolicy::SchedDeadline => GdtCpusMechanismPolicy::SchedDeadline,
+            gdt_cpus::MechanismPolicy::TimeConstraint => GdtCpusMechanismPolicy::TimeConstraint,
         }
     }
 }
@@ -805,6 +869,23 @@ pub extern "C" fn gdt_cpus_error_code_description(error_code: i32) -> *const c_c
     s.as_ptr() as *const c_char
 }
 
+/// Returns the detailed message behind the most recent failing call on this
+/// thread (which sysfs path was missing, which index was out of range), or an
+/// empty string when the last call succeeded. Never NULL.
+///
+/// Like `errno`, the message is per thread and replaced by the next call that
+/// returns an error code; every successful call clears it. The pointer stays
+/// valid until the next such call on the same thread - copy the text if it
+/// must outlive that. [`gdt_cpus_error_code_description`] gives the generic
+/// text for a code.
+#[unsafe(no_mangle)]
+pub extern "C" fn gdt_cpus_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(message) => message.as_ptr(),
+        None => c"".as_ptr(),
+    })
+}
+
 /// Returns a static, null-terminated description of a vendor.
 #[unsafe(no_mangle)]
 pub extern "C" fn gdt_cpus_vendor_description(vendor: i32) -> *const c_char {
@@ -820,6 +901,7 @@ pub extern "C" fn gdt_cpus_vendor_description(vendor: i32) -> *const c_char {
         GdtCpusVendor::Broadcom => b"Broadcom\0",
         GdtCpusVendor::Nvidia => b"NVIDIA\0",
         GdtCpusVendor::Marvell => b"Marvell\0",
+        GdtCpusVendor::Ampere => b"Ampere\0",
         GdtCpusVendor::Other => b"Other\0",
         GdtCpusVendor::Unknown => b"Unknown\0",
     };
@@ -878,7 +960,7 @@ pub unsafe extern "C" fn gdt_cpus_cpu_info(out_info: *mut GdtCpusCpuInfo) -> i32
             vendor_name: c.vendor_name_storage.as_ptr(),
             model_name: c.model_name_storage.as_ptr(),
             features: info.features.bits(),
-            lp_count: info.lps.len() as u64,
+            lp_count: info.lps().len() as u64,
             core_count: info.core_count as u64,
             socket_count: info.socket_count as u64,
             numa_node_count: info.numa_node_count as u64,
@@ -889,7 +971,7 @@ pub unsafe extern "C" fn gdt_cpus_cpu_info(out_info: *mut GdtCpusCpuInfo) -> i32
             lp_efficiency_cores: info.num_lp_efficiency_cores() as u64,
         };
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Writes `true` if more than one core kind is present.
@@ -900,7 +982,7 @@ pub unsafe extern "C" fn gdt_cpus_cpu_info(out_info: *mut GdtCpusCpuInfo) -> i32
 pub unsafe extern "C" fn gdt_cpus_is_hybrid(out_is_hybrid: *mut bool) -> i32 {
     let c = get_info_validate_out_or_err!(out_is_hybrid);
     unsafe { *out_is_hybrid = c.info.is_hybrid() };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_lp` with the logical-processor record at `index`
@@ -911,8 +993,8 @@ pub unsafe extern "C" fn gdt_cpus_is_hybrid(out_is_hybrid: *mut bool) -> i32 {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn gdt_cpus_get_lp(index: u64, out_lp: *mut GdtCpusLp) -> i32 {
     let c = get_info_validate_out_or_err!(out_lp);
-    let Some(lp) = c.info.lps.get(index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+    let Some(lp) = c.info.lps().get(index as usize) else {
+        return out_of_bounds("LP", index, c.info.lps().len());
     };
     unsafe {
         *out_lp = GdtCpusLp {
@@ -936,7 +1018,7 @@ pub unsafe extern "C" fn gdt_cpus_get_lp(index: u64, out_lp: *mut GdtCpusLp) ->
             cpu_part: lp.cpu_part as u32,
         };
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_domain` with the L3 domain at `index` (`0..l3_domain_count`).
@@ -950,7 +1032,7 @@ pub unsafe extern "C" fn gdt_cpus_get_l3_domain(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_domain);
     let Some(d) = c.info.l3_domains.get(index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L3 domain", index, c.info.l3_domains.len());
     };
     unsafe {
         *out_domain = GdtCpusL3Domain {
@@ -959,7 +1041,7 @@ pub unsafe extern "C" fn gdt_cpus_get_l3_domain(
             lp_count: d.mask.count() as u32,
         };
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Writes the OS id of the `lp_index`-th logical processor (ascending) of L3
@@ -976,13 +1058,13 @@ pub unsafe extern "C" fn gdt_cpus_get_l3_domain_lp(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_os_id);
     let Some(d) = c.info.l3_domains.get(domain_index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L3 domain", domain_index, c.info.l3_domains.len());
     };
     let Some(os_id) = d.mask.iter().nth(lp_index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L3 domain LP", lp_index, d.mask.count());
     };
     unsafe { *out_os_id = os_id as u32 };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_domain` with the L2 domain at `index` (`0..l2_domain_count`).
@@ -996,7 +1078,7 @@ pub unsafe extern "C" fn gdt_cpus_get_l2_domain(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_domain);
     let Some(d) = c.info.l2_domains.get(index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L2 domain", index, c.info.l2_domains.len());
     };
     unsafe {
         *out_domain = GdtCpusL2Domain {
@@ -1010,7 +1092,7 @@ pub unsafe extern "C" fn gdt_cpus_get_l2_domain(
             },
         };
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Writes the OS id of the `lp_index`-th logical processor (ascending) of L2
@@ -1027,13 +1109,13 @@ pub unsafe extern "C" fn gdt_cpus_get_l2_domain_lp(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_os_id);
     let Some(d) = c.info.l2_domains.get(domain_index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L2 domain", domain_index, c.info.l2_domains.len());
     };
     let Some(os_id) = d.mask.iter().nth(lp_index as usize) else {
-        return GdtCpusErrorCode::OutOfBounds as i32;
+        return out_of_bounds("L2 domain LP", lp_index, d.mask.count());
     };
     unsafe { *out_os_id = os_id as u32 };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_cache` with the L1 data cache of the given core kind
@@ -1048,10 +1130,13 @@ pub unsafe extern "C" fn gdt_cpus_get_l1d_cache(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_cache);
     let Some(kind) = core_kind_from_i32(kind) else {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(
+            GdtCpusErrorCode::InvalidParameter,
+            format_args!("invalid core kind {}", kind),
+        );
     };
     unsafe { *out_cache = (&c.info.l1d[kind.index()]).into() };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_cache` with the L1 instruction cache of the given core kind.
@@ -1065,10 +1150,13 @@ pub unsafe extern "C" fn gdt_cpus_get_l1i_cache(
 ) -> i32 {
     let c = get_info_validate_out_or_err!(out_cache);
     let Some(kind) = core_kind_from_i32(kind) else {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(
+            GdtCpusErrorCode::InvalidParameter,
+            format_args!("invalid core kind {}", kind),
+        );
     };
     unsafe { *out_cache = (&c.info.l1i[kind.index()]).into() };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Fills `out_cache` with the L2 cache of the given core kind.
@@ -1079,10 +1167,13 @@ pub unsafe extern "C" fn gdt_cpus_get_l1i_cache(
 pub unsafe extern "C" fn gdt_cpus_get_l2_cache(kind: i32, out_cache: *mut GdtCpusCacheInfo) -> i32 {
     let c = get_info_validate_out_or_err!(out_cache);
     let Some(kind) = core_kind_from_i32(kind) else {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(
+            GdtCpusErrorCode::InvalidParameter,
+            format_args!("invalid core kind {}", kind),
+        );
     };
     unsafe { *out_cache = (&c.info.l2[kind.index()]).into() };
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Writes the number of physical cores of the given kind.
@@ -1093,21 +1184,99 @@ pub unsafe extern "C" fn gdt_cpus_get_l2_cache(kind: i32, out_cache: *mut GdtCpu
 pub unsafe extern "C" fn gdt_cpus_num_cores_of_kind(kind: i32, out_count: *mut u64) -> i32 {
     let c = get_info_validate_out_or_err!(out_count);
     let Some(kind) = core_kind_from_i32(kind) else {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(
+            GdtCpusErrorCode::InvalidParameter,
+            format_args!("invalid core kind {}", kind),
+        );
     };
     unsafe {
         *out_count = c.info.kind_core_counts[kind.index()] as u64;
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
+}
+
+/// The LP count every core shares, or `None` when cores differ.
+fn uniform_lps_per_core(info: &gdt_cpus::CpuInfo) -> Option<u64> {
+    let mut per_core = vec![0u64; info.core_count as usize];
+    for lp in info.lps() {
+        *per_core.get_mut(lp.core as usize)? += 1;
+    }
+
+    let (&first, rest) = per_core.split_first()?;
+    rest.iter().all(|&n| n == first).then_some(first)
+}
+
+/// Writes the number of logical processors per physical core (1 without SMT,
+/// 2 with Hyper-Threading).
+///
+/// Returns `Unsupported` when cores differ - hybrid Intel parts run SMT-2
+/// P-cores next to SMT-1 E-cores, so `lp_count / core_count` is no core's
+/// count there. Walk the LPs with [`gdt_cpus_get_lp`] and group them by
+/// `core` instead; `out_count` is left untouched in that case.
+///
+/// # Safety
+/// `out_count` must point to a valid `u64`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gdt_cpus_logical_processors_per_core(out_count: *mut u64) -> i32 {
+    let c = get_info_validate_out_or_err!(out_count);
+    let Some(count) = uniform_lps_per_core(&c.info) else {
+        return fail(
+            GdtCpusErrorCode::Unsupported,
+            "cores have different logical processor counts",
+        );
+    };
+    unsafe {
+        *out_count = count;
+    }
+    succeed()
+}
+
+/// Writes `value` through `out`, or returns `NotFound` (naming `what`) when
+/// it is `None`.
+fn write_optional_u32(value: Option<u32>, out: *mut u32, what: &str) -> i32 {
+    let Some(value) = value else {
+        return fail(
+            GdtCpusErrorCode::NotFound,
+            format_args!("{} not reported on this platform", what),
+        );
+    };
+    unsafe { *out = value };
+    succeed()
+}
+
+/// Writes the base (nominal, non-boost) clock in MHz. Returns `NotFound`
+/// when the platform does not report one (Apple Silicon, most ARM boards,
+/// many VMs); `out_mhz` is left untouched in that case.
+///
+/// # Safety
+/// `out_mhz` must point to a valid `u32`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gdt_cpus_base_frequency_mhz(out_mhz: *mut u32) -> i32 {
+    let c = get_info_validate_out_or_err!(out_mhz);
+    write_optional_u32(c.info.base_frequency_mhz, out_mhz, "base frequency")
+}
+
+/// Writes the maximum (boost) clock in MHz. Returns `NotFound` when the
+/// platform does not report one; `out_mhz` is left untouched in that case.
+///
+/// # Safety
+/// `out_mhz` must point to a valid `u32`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gdt_cpus_max_frequency_mhz(out_mhz: *mut u32) -> i32 {
+    let c = get_info_validate_out_or_err!(out_mhz);
+    write_optional_u32(c.info.max_frequency_mhz, out_mhz, "max frequency")
 }
 
 // ---------------------------------------------------------------------------
 // Thread control
 // ---------------------------------------------------------------------------
 
-fn mask_from_ffi(lp_ids: *const u32, count: u64) -> Result<AffinityMask, GdtCpusErrorCode> {
+fn mask_from_ffi(lp_ids: *const u32, count: u64) -> Result<AffinityMask, i32> {
     if lp_ids.is_null() || count == 0 {
-        return Err(GdtCpusErrorCode::InvalidParameter);
+        return Err(fail(
+            GdtCpusErrorCode::InvalidParameter,
+            "`lp_ids` is NULL or `count` is 0",
+        ));
     }
     let ids = unsafe { std::slice::from_raw_parts(lp_ids, count as usize) };
     let mut mask = AffinityMask::empty();
@@ -1116,7 +1285,14 @@ fn mask_from_ffi(lp_ids: *const u32, count: u64) -> Result<AffinityMask, GdtCpus
         // MAX_LP_COUNT, so a bogus id is a clean InvalidParameter rather than a
         // silently dropped core (and never an allocation - the mask is fixed).
         if id as usize >= AffinityMask::MAX_LP_COUNT {
-            return Err(GdtCpusErrorCode::InvalidParameter);
+            return Err(fail(
+                GdtCpusErrorCode::InvalidParameter,
+                format_args!(
+                    "LP id {} exceeds the supported maximum of {}",
+                    id,
+                    AffinityMask::MAX_LP_COUNT - 1
+                ),
+            ));
         }
         mask.add(id as usize);
     }
@@ -1130,8 +1306,8 @@ fn mask_from_ffi(lp_ids: *const u32, count: u64) -> Result<AffinityMask, GdtCpus
 #[unsafe(no_mangle)]
 pub extern "C" fn gdt_cpus_pin_thread_to_core(logical_core_id: u64) -> i32 {
     match gdt_cpus::pin_thread_to_core(logical_core_id as usize) {
-        Ok(_) => GdtCpusErrorCode::Success as i32,
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Ok(_) => succeed(),
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1146,11 +1322,11 @@ pub extern "C" fn gdt_cpus_pin_thread_to_core(logical_core_id: u64) -> i32 {
 pub unsafe extern "C" fn gdt_cpus_set_thread_affinity(lp_ids: *const u32, count: u64) -> i32 {
     let mask = match mask_from_ffi(lp_ids, count) {
         Ok(m) => m,
-        Err(code) => return code as i32,
+        Err(code) => return code,
     };
     match gdt_cpus::set_thread_affinity(&mask) {
-        Ok(_) => GdtCpusErrorCode::Success as i32,
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Ok(_) => succeed(),
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1164,11 +1340,11 @@ pub unsafe extern "C" fn gdt_cpus_set_thread_affinity(lp_ids: *const u32, count:
 pub unsafe extern "C" fn gdt_cpus_set_thread_soft_affinity(lp_ids: *const u32, count: u64) -> i32 {
     let mask = match mask_from_ffi(lp_ids, count) {
         Ok(m) => m,
-        Err(code) => return code as i32,
+        Err(code) => return code,
     };
     match gdt_cpus::set_thread_soft_affinity(&mask) {
-        Ok(_) => GdtCpusErrorCode::Success as i32,
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Ok(_) => succeed(),
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1188,16 +1364,19 @@ pub unsafe extern "C" fn gdt_cpus_set_thread_priority(
     out_applied: *mut GdtCpusAppliedPriority,
 ) -> i32 {
     let Some(priority) = thread_priority_from_i32(priority) else {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(
+            GdtCpusErrorCode::InvalidParameter,
+            format_args!("invalid thread priority {}", priority),
+        );
     };
     match gdt_cpus::set_thread_priority(priority) {
         Ok(applied) => {
             if !out_applied.is_null() {
                 unsafe { *out_applied = GdtCpusAppliedPriority::from(&applied) };
             }
-            GdtCpusErrorCode::Success as i32
+            succeed()
         }
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1221,9 +1400,9 @@ pub unsafe extern "C" fn gdt_cpus_promote_thread_to_realtime(
             if !out_applied.is_null() {
                 unsafe { *out_applied = GdtCpusAppliedPriority::from(&applied) };
             }
-            GdtCpusErrorCode::Success as i32
+            succeed()
         }
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1231,8 +1410,8 @@ pub unsafe extern "C" fn gdt_cpus_promote_thread_to_realtime(
 #[unsafe(no_mangle)]
 pub extern "C" fn gdt_cpus_demote_thread_from_realtime() -> i32 {
     match gdt_cpus::demote_thread_from_realtime() {
-        Ok(()) => GdtCpusErrorCode::Success as i32,
-        Err(e) => GdtCpusErrorCode::from(&e) as i32,
+        Ok(()) => succeed(),
+        Err(e) => fail_with(&e),
     }
 }
 
@@ -1245,7 +1424,7 @@ pub extern "C" fn gdt_cpus_demote_thread_from_realtime() -> i32 {
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn gdt_cpus_priority_capabilities(out_caps: *mut GdtCpusPriorityCaps) -> i32 {
     if out_caps.is_null() {
-        return GdtCpusErrorCode::InvalidParameter as i32;
+        return fail(GdtCpusErrorCode::InvalidParameter, "`out_caps` is NULL");
     }
     let caps = gdt_cpus::priority_capabilities();
     unsafe {
@@ -1254,7 +1433,7 @@ pub unsafe extern "C" fn gdt_cpus_priority_capabilities(out_caps: *mut GdtCpusPr
             distinct_levels: caps.distinct_levels(),
         };
     }
-    GdtCpusErrorCode::Success as i32
+    succeed()
 }
 
 /// Returns a static, null-terminated name for a grant tier.
@@ -1308,6 +1487,31 @@ pub extern "C" fn gdt_cpus_broker_error_description(broker_error: i32) -> *const
 mod tests {
     use super::*;
 
+    // A transient detection failure is reported, not cached: the next call
+    // detects again, and only the success is kept.
+    #[test]
+    fn failed_detection_is_retried_not_cached() {
+        static CELL: OnceLock<CpuInfoContainer> = OnceLock::new();
+        let mut calls = 0;
+        let mut flaky = || {
+            calls += 1;
+            if calls == 1 {
+                Err(gdt_cpus::Error::Detection("sysfs read interrupted".into()))
+            } else {
+                gdt_cpus::CpuInfo::detect()
+            }
+        };
+
+        assert_eq!(
+            container_in(&CELL, &mut flaky).err(),
+            Some(GdtCpusErrorCode::Detection as i32)
+        );
+        assert!(container_in(&CELL, &mut flaky).is_ok());
+        // Cached now: the detector is not run again.
+        assert!(container_in(&CELL, &mut flaky).is_ok());
+        assert_eq!(calls, 2);
+    }
+
     #[test]
     fn ffi_rejects_null_out_pointers() {
         assert_eq!(
@@ -1324,6 +1528,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ffi_last_error_explains_failure_until_next_success() {
+        let mut lp: GdtCpusLp = unsafe { std::mem::zeroed() };
+        let mut info: GdtCpusCpuInfo = unsafe { std::mem::zeroed() };
+        let message = || {
+            unsafe { std::ffi::CStr::from_ptr(gdt_cpus_last_error_message()) }
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        assert_eq!(
+            unsafe { gdt_cpus_get_lp(u64::MAX, &mut lp) },
+            GdtCpusErrorCode::OutOfBounds as i32
+        );
+        let detail = message();
+        assert!(
+            detail.starts_with("LP index 18446744073709551615"),
+            "{detail}"
+        );
+
+        assert_eq!(
+            unsafe { gdt_cpus_cpu_info(&mut info) },
+            GdtCpusErrorCode::Success as i32
+        );
+        assert_eq!(message(), "");
+
+        unsafe { gdt_cpus_get_lp(0, std::ptr::null_mut()) };
+        assert_eq!(message(), "`out_lp` is NULL");
+    }
+
     #[test]
     fn ffi_rejects_invalid_priority_value() {
         assert_eq!(
@@ -1344,6 +1578,67 @@ mod tests {
         assert!(!info.model_name.is_null());
     }
 
+    // Hybrid Intel: SMT-2 P-cores next to SMT-1 E-cores have no single
+    // per-core count, and averaging one up would be wrong for every core.
+    #[test]
+    fn lps_per_core_is_unsupported_on_asymmetric_smt() {
+        use gdt_cpus::{CoreKind, CpuInfoBuilder};
+
+        let hybrid = CpuInfoBuilder::new()
+            .cores(8, CoreKind::Performance, 2)
+            .cores(16, CoreKind::Efficiency, 1)
+            .build();
+        assert_eq!(uniform_lps_per_core(&hybrid), None);
+
+        let smt = CpuInfoBuilder::new()
+            .cores(8, CoreKind::Performance, 2)
+            .build();
+        assert_eq!(uniform_lps_per_core(&smt), Some(2));
+
+        let mut count = 0u64;
+        let code = unsafe { gdt_cpus_logical_processors_per_core(&mut count) };
+        let detected = gdt_cpus::CpuInfo::detect().unwrap();
+        match uniform_lps_per_core(&detected) {
+            Some(n) => {
+                assert_eq!(code, GdtCpusErrorCode::Success as i32);
+                assert_eq!(count, n);
+            }
+            None => assert_eq!(code, GdtCpusErrorCode::Unsupported as i32),
+        }
+    }
+
+    // Frequencies are optional per platform: an absent value must surface as
+    // NotFound (and leave the out-param alone), a present one as Success
+    // carrying exactly what detection found.
+    #[test]
+    fn ffi_frequency_reports_not_found_when_absent() {
+        let mut mhz = 7u32;
+        assert_eq!(
+            write_optional_u32(None, &mut mhz, "base frequency"),
+            GdtCpusErrorCode::NotFound as i32
+        );
+        assert_eq!(mhz, 7);
+
+        let info = gdt_cpus::CpuInfo::detect().unwrap();
+        for (ffi, expected) in [
+            (
+                gdt_cpus_base_frequency_mhz as unsafe extern "C" fn(*mut u32) -> i32,
+                info.base_frequency_mhz,
+            ),
+            (gdt_cpus_max_frequency_mhz, info.max_frequency_mhz),
+        ] {
+            let mut mhz = 0u32;
+            let code = unsafe { ffi(&mut mhz) };
+            match expected {
+                Some(v) => {
+                    assert_eq!(code, GdtCpusErrorCode::Success as i32);
+                    assert_eq!(mhz, v);
+                }
+                None => assert_eq!(code, GdtCpusErrorCode::NotFound as i32),
+            }
+        }
+    }
+
     #[test]
     fn applied_priority_conversion_preserves_structured_fields() {
         let applied = gdt_cpus::AppliedPriority::from_parts(
@@ -282,7 +282,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // One primary thread (smt_index == 0) per Performance core, by OS LP id.
     let p_lps: Vec<usize> = info
-        .lps
+        .lps()
         .iter()
         .filter(|lp| lp.smt_index == 0 && lp.kind == CoreKind::Performance)
         .map(|lp| lp.os_id as usize)
@@ -360,7 +360,7 @@ fn run_median_point(
 
 fn collect_candidate_lps(info: &gdt_cpus::CpuInfo) -> Vec<CandidateLp> {
     let mut candidates = info
-        .lps
+        .lps()
         .iter()
         .filter(|lp| lp.smt_index == 0 && lp.kind == CoreKind::Performance)
         .map(|lp| CandidateLp {
@@ -372,7 +372,7 @@ fn collect_candidate_lps(info: &gdt_cpus::CpuInfo) -> Vec<CandidateLp> {
         return candidates;
     }
     candidates = info
-        .lps
+        .lps()
         .iter()
         .filter(|lp| lp.smt_index == 0)
         .map(|lp| CandidateLp {
@@ -22,6 +22,9 @@ fn main() {
     println!("Efficiency cores: {}", info.num_efficiency_cores());
     println!("LP-Efficiency cores: {}", info.num_lp_efficiency_cores());
     println!("NUMA nodes: {}", info.numa_node_count);
+    let mhz = |f: Option<u32>| f.map_or("unknown".to_string(), |v| format!("{} MHz", v));
+    println!("Base clock: {}", mhz(info.base_frequency_mhz));
+    println!("Max clock: {}", mhz(info.max_frequency_mhz));
     println!(
         "Hybrid architecture: {}",
         if info.is_hybrid() { "Yes" } else { "No" }
@@ -87,7 +90,7 @@ fn main() {
     }
 
     println!("\nLogical processors:");
-    for lp in &info.lps {
+    for lp in info.lps() {
         println!(
             "  lp {:>3}: core {:>3} smt {} socket {} l3-domain {:>lw$} l2-domain {:>w$} numa {} perf {:>4} kind {}",
             lp.os_id,
@@ -175,9 +175,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         info.num_logical_cores()
     );
 
-    let all_lps: Vec<usize> = info.lps.iter().map(|lp| lp.os_id as usize).collect();
+    let all_lps: Vec<usize> = info.lps().iter().map(|lp| lp.os_id as usize).collect();
     let phys_lps: Vec<usize> = info
-        .lps
+        .lps()
         .iter()
         .filter(|lp| lp.smt_index == 0)
         .map(|lp| lp.os_id as usize)
@@ -89,7 +89,7 @@ fn primary_cores_of_l2(info: &CpuInfo, idx: usize) -> Vec<usize> {
         .mask
         .iter()
         .filter(|&os_id| {
-            info.lps
+            info.lps()
                 .iter()
                 .any(|lp| lp.os_id as usize == os_id && lp.smt_index == 0)
         })
@@ -129,7 +129,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "CPU: {} - {} cores / {} threads, {} L3 / {} L2 domain(s)",
         info.model_name,
         info.core_count,
-        info.lps.len(),
+        info.lps().len(),
         info.l3_domains.len(),
         info.l2_domains.len(),
     );
@@ -226,7 +226,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .find(|c| !c.is_empty() && c[0] != tight.1 && c[0] != tight.2)
             .map(|c| (label, tight.1, c[0]))
     } else {
-        info.lps
+        info.lps()
             .iter()
             .find(|lp| lp.smt_index == 0 && lp.l3_domain != l3 && lp.l3_domain != Lp::NO_L3)
             .map(|lp| ("next out, CROSS L3 domain", tight.1, lp.os_id as usize))
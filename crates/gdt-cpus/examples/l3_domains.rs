@@ -75,7 +75,7 @@ fn ping_pong(lp_a: usize, lp_b: usize) -> f64 {
 
 /// First primary thread (smt_index == 0) of each physical core in a domain.
 fn primary_lps_of_domain(info: &CpuInfo, domain: u8) -> Vec<&Lp> {
-    info.lps
+    info.lps()
         .iter()
         .filter(|lp| lp.smt_index == 0 && lp.l3_domain == domain)
         .collect()
@@ -88,7 +88,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "CPU: {} - {} cores / {} threads, {} L3 domain(s):",
         info.model_name,
         info.core_count,
-        info.lps.len(),
+        info.lps().len(),
         info.l3_domains.len()
     );
     for (i, d) in info.l3_domains.iter().enumerate() {
@@ -103,14 +103,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n{} round trips per configuration...\n", ROUND_TRIPS);
 
     // 1. SMT siblings of one core (if SMT exists): the communication floor.
-    let smt_pair = info.lps.iter().find(|lp| lp.smt_index == 1).map(|sibling| {
-        let primary = info
-            .lps
-            .iter()
-            .find(|lp| lp.core == sibling.core && lp.smt_index == 0)
-            .expect("SMT sibling without a primary thread");
-        (primary.os_id as usize, sibling.os_id as usize)
-    });
+    let smt_pair = info
+        .lps()
+        .iter()
+        .find(|lp| lp.smt_index == 1)
+        .map(|sibling| {
+            let primary = info
+                .lps()
+                .iter()
+                .find(|lp| lp.core == sibling.core && lp.smt_index == 0)
+                .expect("SMT sibling without a primary thread");
+            (primary.os_id as usize, sibling.os_id as usize)
+        });
     if let Some((a, b)) = smt_pair {
         let ns = ping_pong(a, b);
         println!(
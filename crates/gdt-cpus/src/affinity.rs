@@ -4,9 +4,16 @@
 //! (Linux, Windows; macOS returns [`crate::Error::Unsupported`] - Apple
 //! Silicon ignores affinity, QoS via [`set_thread_priority`] is the only
 //! placement tool there). [`set_thread_priority`] maps the 7 portable
-//! [`ThreadPriority`] levels onto each OS scheduler.
+//! [`ThreadPriority`] levels onto each OS scheduler. [`set_process_affinity`]
+//! is the one process-wide exception.
 
-use crate::{AffinityMask, AppliedPriority, ThreadPriority, error::Result};
+use std::sync::RwLock;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{
+    AffinityMask, AppliedPriority, CacheSet, CpuInfo, Error, ThreadPriority, error::Result,
+};
 
 /// Pins the current thread to a single logical core (OS LP id).
 ///
@@ -32,6 +39,26 @@ pub fn pin_thread_to_core(logical_core_id: usize) -> Result<()> {
     set_thread_affinity(&AffinityMask::single(logical_core_id))
 }
 
+/// Pins the current thread to the logical processors of NUMA node `node_id`
+/// (OS node id) - to run next to memory that node owns.
+///
+/// Detects the topology and applies [`CpuInfo::numa_node_mask`] through
+/// [`set_thread_affinity`]; hold a [`CpuInfo`] and call those two directly
+/// when pinning many threads.
+///
+/// # Errors
+///
+/// Whatever [`CpuInfo::detect()`] or [`set_thread_affinity`] return (macOS:
+/// [`crate::Error::Unsupported`]), or [`crate::Error::NotFound`] if no online
+/// LP is on node `node_id`.
+pub fn pin_current_thread_to_numa_node(node_id: usize) -> Result<()> {
+    let mask = crate::numa_node_mask(node_id).ok_or_else(|| {
+        Error::NotFound(format!("NUMA node {} has no online logical processors", node_id).into())
+    })?;
+
+    set_thread_affinity(&mask)
+}
+
 /// Sets the current thread's hard CPU affinity to `mask` (OS LP ids).
 ///
 /// Linux: `sched_setaffinity`. Windows: `SetThreadGroupAffinity` - a thread's
@@ -51,7 +78,137 @@ pub fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
     {
         let _ = mask;
         Err(crate::Error::Unsupported(
-            "Thread affinity is not supported on this platform.".to_string(),
+            "Thread affinity is not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// Like [`set_thread_affinity`], but drops the ids it cannot use instead of
+/// failing - for masks from config files or an earlier run, where a core may
+/// since have gone offline or never existed on this machine.
+///
+/// The mask is first narrowed to the online LPs of a fresh
+/// [`CpuInfo::detect()`] (one detection per call - keep it off hot paths). On
+/// Windows, whose hard affinity is single-group, it is further narrowed to the
+/// processor group holding most of the remaining LPs.
+///
+/// # Returns
+///
+/// The number of LPs the thread may now run on - read back from the OS where
+/// [`current_affinity`] works, since cpusets can narrow it further.
+///
+/// # Errors
+///
+/// [`crate::Error::InvalidParameter`] if no id in `mask` is usable, otherwise
+/// whatever [`CpuInfo::detect()`] or [`set_thread_affinity`] return.
+pub fn set_thread_affinity_best_effort(mask: &AffinityMask) -> Result<usize> {
+    let info = CpuInfo::detect()?;
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut usable = mask.intersection(&info.all_cores_mask());
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut per_group = [0usize; AffinityMask::MAX_LP_COUNT / 64];
+        for lp in usable.iter() {
+            per_group[lp / 64] += 1;
+        }
+        let best = (0..per_group.len())
+            .max_by_key(|&g| per_group[g])
+            .unwrap_or(0);
+        usable = usable.iter().filter(|lp| lp / 64 == best).collect();
+    }
+
+    if usable.is_empty() {
+        return Err(Error::InvalidParameter(
+            format!("No usable logical processor in affinity mask {}", mask).into(),
+        ));
+    }
+
+    set_thread_affinity(&usable)?;
+
+    Ok(current_affinity().map_or(usable.count(), |applied| applied.count()))
+}
+
+/// Mask [`spawn`] applies to new threads; see [`set_default_thread_affinity`].
+static DEFAULT_THREAD_AFFINITY: RwLock<Option<AffinityMask>> = RwLock::new(None);
+
+/// Sets the hard affinity (OS LP ids) that every thread started through
+/// [`spawn`] applies to itself before running its closure; `None` lets new
+/// threads inherit the spawner's affinity, the OS default.
+///
+/// `std::thread::spawn` offers no global hook, so this does NOT reach threads
+/// started any other way - by `std::thread`, rayon, tokio or C libraries.
+/// Route your own spawns through [`spawn`], or use [`set_process_affinity`]
+/// to constrain every thread of the process.
+pub fn set_default_thread_affinity(mask: Option<AffinityMask>) {
+    *DEFAULT_THREAD_AFFINITY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = mask;
+}
+
+/// The mask set by [`set_default_thread_affinity`], if any.
+pub fn default_thread_affinity() -> Option<AffinityMask> {
+    *DEFAULT_THREAD_AFFINITY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// [`std::thread::spawn`] that first pins the new thread to
+/// [`default_thread_affinity`], when one is set.
+///
+/// The mask is read at spawn time, so a later
+/// [`set_default_thread_affinity`] affects only threads spawned after it.
+/// Pinning is best effort: where [`set_thread_affinity`] fails (macOS, a
+/// mask outside the process's cpuset) the thread runs unpinned, logging a
+/// warning with feature `log`.
+///
+/// # Panics
+///
+/// Like [`std::thread::spawn`], if the OS fails to create the thread.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let mask = default_thread_affinity();
+    std::thread::spawn(move || {
+        if let Some(mask) = mask {
+            #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+            if let Err(e) = set_thread_affinity(&mask) {
+                #[cfg(feature = "log")]
+                log::warn!("Default thread affinity {} not applied: {}", mask, e);
+            }
+        }
+        f()
+    })
+}
+
+/// Sets the hard CPU affinity of the WHOLE PROCESS to `mask` (OS LP ids) -
+/// every current thread, and every thread spawned afterwards (they inherit
+/// it), including threads owned by other libraries.
+///
+/// For launchers and background helpers that should stay off the cores the
+/// foreground app needs. Any per-thread pin set earlier is overwritten.
+///
+/// Linux: `sched_setaffinity` on each thread in `/proc/self/task` (the pid
+/// alone addresses only the main thread). Windows: `SetProcessAffinityMask` -
+/// the mask must lie in the process's own 64-LP processor group, otherwise
+/// [`crate::Error::InvalidParameter`]. macOS and other platforms:
+/// [`crate::Error::Unsupported`].
+pub fn set_process_affinity(mask: &AffinityMask) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::set_process_affinity(mask)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::affinity::set_process_affinity(mask)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = mask;
+        Err(crate::Error::Unsupported(
+            "Process affinity is not supported on this platform.".into(),
         ))
     }
 }
@@ -90,11 +247,200 @@ pub fn current_affinity() -> Result<AffinityMask> {
     #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
         Err(crate::Error::Unsupported(
-            "Reading thread affinity is not supported on this platform.".to_string(),
+            "Reading thread affinity is not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// The hard-affinity mask of the thread with native id `native_id` (as
+/// [`current_thread_native_id`] returns it) - for a supervisor auditing
+/// where its workers are pinned.
+///
+/// Linux: `sched_getaffinity(tid)`; any thread the caller may inspect, this
+/// process or not. Windows: `OpenThread` + `GetThreadGroupAffinity`, so a
+/// single-group mask like [`current_affinity`]'s. macOS and other platforms:
+/// [`crate::Error::Unsupported`] (no thread affinity to read).
+///
+/// # Errors
+///
+/// - [`crate::Error::NotFound`] if no thread has that id (it exited - ids are
+///   reused, so read while the thread is known to be alive) or it cannot be
+///   opened.
+/// - [`crate::Error::InvalidParameter`] if `native_id` is out of the OS id
+///   range.
+/// - [`crate::Error::Affinity`] if the query itself fails.
+pub fn get_thread_affinity_for(native_id: u64) -> Result<AffinityMask> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::affinity_of_thread_id(native_id)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // tid 0 would mean "the caller" to the kernel: never a real id.
+        let tid = libc::pid_t::try_from(native_id)
+            .ok()
+            .filter(|&tid| tid > 0)
+            .ok_or_else(|| {
+                Error::InvalidParameter(format!("{native_id} is not a Linux tid").into())
+            })?;
+        crate::platform::linux::affinity::affinity_of(tid)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = native_id;
+        Err(crate::Error::Unsupported(
+            "Reading thread affinity is not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// The OS LP id the calling thread is executing on at this instant.
+///
+/// Linux: `sched_getcpu`. Windows: `GetCurrentProcessorNumberEx`
+/// (`group * 64 + number`). macOS and other platforms:
+/// [`crate::Error::Unsupported`] (no public API reports the current CPU).
+///
+/// The answer can be stale by the time it is returned - an unpinned thread
+/// may migrate at any point. It is exact only for a thread pinned to one LP.
+pub fn current_logical_processor() -> Result<usize> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::current_logical_processor()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::affinity::current_logical_processor()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err(crate::Error::Unsupported(
+            "Querying the current logical processor is not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// The calling thread's id as the OS knows it - for handing to profilers,
+/// tracers and schedulers that address threads natively.
+///
+/// Linux: `gettid()`, the kernel TID (`/proc/self/task/<tid>`,
+/// `perf --tid`). macOS: `pthread_threadid_np`, the 64-bit id Instruments
+/// and `spindump` show. Windows: `GetCurrentThreadId`. Other platforms:
+/// [`crate::Error::Unsupported`]. Ids are unique among live threads only -
+/// the OS reuses them after a thread exits.
+pub fn current_thread_native_id() -> Result<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        Ok(crate::platform::windows::affinity::current_thread_id())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::affinity::current_tid().map(u64::from)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::affinity::current_thread_id()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    {
+        Err(crate::Error::Unsupported(
+            "Native thread ids are not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// CPU time the calling thread has consumed so far (user + kernel) - to
+/// check how much work pinned workers actually did, independent of how long
+/// they were preempted.
+///
+/// Linux and macOS: `clock_gettime(CLOCK_THREAD_CPUTIME_ID)`, nanosecond
+/// resolution. Windows: `GetThreadTimes`, which advances in clock-tick steps
+/// (15.6 ms by default). Other platforms: [`crate::Error::Unsupported`].
+/// Subtract two reads to time a section; the value never decreases.
+pub fn current_thread_cpu_time() -> Result<Duration> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::current_thread_cpu_time()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::affinity::current_thread_cpu_time()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::affinity::current_thread_cpu_time()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    {
+        Err(crate::Error::Unsupported(
+            "Thread CPU time is not supported on this platform.".into(),
         ))
     }
 }
 
+/// The `(socket, core, lp)` the calling thread is executing on: the current
+/// logical processor (see [`current_logical_processor`]) mapped through a fresh
+/// [`CpuInfo::detect()`] to its [`crate::Lp::socket`] and [`crate::Lp::core`].
+///
+/// Only meaningful for a pinned thread. An unpinned thread can migrate between
+/// the read and the return - even to another socket - so treat the result as a
+/// sample, not an identity. Pin first ([`pin_thread_to_core`], or a mask
+/// covering one core's SMT siblings if only `core` must be stable).
+///
+/// # Errors
+///
+/// Whatever [`current_logical_processor`] or [`CpuInfo::detect()`] return, or
+/// [`crate::Error::NotFound`] if the LP is missing from the detected topology
+/// (hot-plugged after detection).
+pub fn current_core() -> Result<(usize, usize, usize)> {
+    let info = CpuInfo::detect()?;
+    let os_id = current_logical_processor()?;
+
+    info.lps
+        .iter()
+        .find(|lp| lp.os_id as usize == os_id)
+        .map(|lp| (lp.socket as usize, lp.core as usize, os_id))
+        .ok_or_else(|| {
+            Error::NotFound(
+                format!(
+                    "Logical processor {} is not in the detected topology",
+                    os_id
+                )
+                .into(),
+            )
+        })
+}
+
+/// The caches of the core the calling thread is executing on - its L1i, L1d
+/// and L2 and the L3 above them - for self-tuning code that sizes blocks to
+/// where it runs: the current logical processor (see
+/// [`current_logical_processor`]) looked up with
+/// [`CpuInfo::caches_for_logical_processor`] on a fresh [`CpuInfo::detect()`].
+///
+/// MOMENTARY for an unpinned thread: it may migrate to a core with other
+/// caches (a different L3 domain, an E-core cluster) right after the read.
+/// Pin first ([`pin_thread_to_core`], or [`set_thread_affinity`] to one
+/// core's siblings) when the sizes must stay valid.
+///
+/// # Errors
+///
+/// Whatever [`current_logical_processor`] or [`CpuInfo::detect()`] return, or
+/// [`crate::Error::NotFound`] if the LP is missing from the detected topology
+/// (hot-plugged after detection).
+pub fn current_core_caches() -> Result<CacheSet> {
+    let info = CpuInfo::detect()?;
+    let os_id = current_logical_processor()?;
+
+    info.caches_for_logical_processor(os_id).ok_or_else(|| {
+        Error::NotFound(
+            format!(
+                "Logical processor {} is not in the detected topology",
+                os_id
+            )
+            .into(),
+        )
+    })
+}
+
 /// Sets the current thread's SOFT affinity to `mask` (OS LP ids) - Windows only.
 ///
 /// Soft affinity (the CPU Sets API, `SetThreadSelectedCpuSets`) tells the
@@ -112,7 +458,111 @@ pub fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
     {
         let _ = mask;
         Err(crate::Error::Unsupported(
-            "Soft affinity (CPU Sets) is a Windows API; there is no equivalent here.".to_string(),
+            "Soft affinity (CPU Sets) is a Windows API; there is no equivalent here.".into(),
+        ))
+    }
+}
+
+/// Sets the current thread's IDEAL PROCESSOR to LP `lp_id` (OS id) -
+/// Windows only.
+///
+/// The lightest placement hint there is: the scheduler tries `lp_id` first
+/// when the thread wakes but runs it anywhere its affinity allows when that
+/// LP is busy, so there is none of the latency cost of a hard pin. Windows:
+/// `SetThreadIdealProcessorEx`. Every other platform:
+/// [`crate::Error::Unsupported`].
+///
+/// # Errors
+///
+/// [`crate::Error::InvalidCoreId`] if `lp_id` names no processor.
+pub fn set_thread_ideal_processor(lp_id: usize) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::set_thread_ideal_processor(lp_id)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = lp_id;
+        Err(crate::Error::Unsupported(
+            "The ideal processor hint is a Windows API; there is no equivalent here.".into(),
+        ))
+    }
+}
+
+/// Reads the current thread's ideal processor (OS LP id) - Windows only
+/// (`GetThreadIdealProcessorEx`); [`crate::Error::Unsupported`] elsewhere.
+///
+/// Pairs with [`set_thread_ideal_processor`] for save/restore. A thread
+/// that never set one still has an ideal processor - Windows assigns it
+/// round-robin at creation.
+pub fn current_ideal_processor() -> Result<usize> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::current_ideal_processor()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(crate::Error::Unsupported(
+            "The ideal processor hint is a Windows API; there is no equivalent here.".into(),
+        ))
+    }
+}
+
+/// Registration of the current thread with the Windows Multimedia Class
+/// Scheduler Service (MMCSS), returned by [`register_mmcss_task`].
+///
+/// Dropping it calls `AvRevertMmThreadCharacteristics`. Not `Send`: MMCSS
+/// registrations belong to the thread that made them and must be reverted
+/// there. `std::mem::forget` keeps the registration until the thread exits.
+#[derive(Debug)]
+pub struct MmcssHandle {
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    raw: *mut core::ffi::c_void,
+}
+
+impl Drop for MmcssHandle {
+    fn drop(&mut self) {
+        #[cfg(target_os = "windows")]
+        if let Err(_e) = crate::platform::windows::affinity::revert_mmcss_task(self.raw) {
+            #[cfg(feature = "log")]
+            log::warn!("Reverting MMCSS registration failed: {}", _e);
+        }
+    }
+}
+
+/// Registers the current thread with MMCSS under `task_name` - Windows only
+/// (`AvSetMmThreadCharacteristicsW`); [`crate::Error::Unsupported`] elsewhere.
+///
+/// MMCSS boosts registered threads into the real-time band for their share
+/// of each period and keeps network throttling off them. Task names are the
+/// subkeys of `HKLM\...\Multimedia\SystemProfile\Tasks`; the common ones
+/// are `"Games"`, `"Audio"` and `"Pro Audio"`. The registration lasts until
+/// the returned [`MmcssHandle`] is dropped.
+///
+/// # Errors
+///
+/// [`crate::Error::SystemCall`] when the task name is unknown or the MMCSS
+/// service is disabled.
+///
+/// # Example
+///
+/// ```no_run
+/// std::thread::spawn(|| {
+///     let _mmcss = gdt_cpus::register_mmcss_task("Games").ok();
+///     // ... frame loop; the registration is reverted when `_mmcss` drops ...
+/// });
+/// ```
+pub fn register_mmcss_task(task_name: &str) -> Result<MmcssHandle> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::register_mmcss_task(task_name)
+            .map(|raw| MmcssHandle { raw })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = task_name;
+        Err(crate::Error::Unsupported(
+            "MMCSS is a Windows service; there is no equivalent here.".into(),
         ))
     }
 }
@@ -163,7 +613,138 @@ pub fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority>
     {
         let _ = priority;
         Err(crate::Error::Unsupported(
-            "Setting thread priority is not supported on this platform.".to_string(),
+            "Setting thread priority is not supported on this platform.".into(),
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pin_to_numa_node_zero_applies_its_mask() {
+        std::thread::spawn(|| {
+            let node = crate::numa_node_mask(0).unwrap();
+            let allowed = current_affinity().unwrap();
+
+            pin_current_thread_to_numa_node(0).unwrap();
+            // A cpuset narrower than the node trims the mask the kernel keeps.
+            assert_eq!(current_affinity().unwrap(), node.intersection(&allowed));
+
+            assert!(matches!(
+                pin_current_thread_to_numa_node(usize::MAX),
+                Err(Error::NotFound(_))
+            ));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn spawned_threads_inherit_the_default_mask() {
+        // The default only reaches threads started through `spawn`, which no
+        // other test uses, so setting it here cannot leak into them.
+        let allowed = current_affinity().unwrap();
+        let first = AffinityMask::single(allowed.iter().next().unwrap());
+
+        set_default_thread_affinity(Some(first));
+        assert_eq!(default_thread_affinity(), Some(first));
+        let pinned = (0..2)
+            .map(|_| spawn(|| current_affinity().unwrap()))
+            .collect::<Vec<_>>();
+        for handle in pinned {
+            assert_eq!(handle.join().unwrap(), first);
+        }
+
+        set_default_thread_affinity(None);
+        assert_eq!(default_thread_affinity(), None);
+        assert_eq!(
+            spawn(|| current_affinity().unwrap()).join().unwrap(),
+            allowed
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn current_core_caches_match_the_pinned_core() {
+        std::thread::spawn(|| {
+            let info = CpuInfo::detect().unwrap();
+            let last = current_affinity().unwrap().highest_core().unwrap();
+
+            pin_thread_to_core(last).unwrap();
+            let caches = current_core_caches().unwrap();
+            let expected = info.caches_for_logical_processor(last).unwrap();
+            assert_eq!(
+                caches.l2.map(|l2| l2.info.size_bytes),
+                expected.l2.map(|l2| l2.info.size_bytes)
+            );
+            assert_eq!(caches.l3.map(|l3| l3.owner), expected.l3.map(|l3| l3.owner));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn affinity_is_readable_by_native_id() {
+        let lp = current_affinity().unwrap().lowest_core().unwrap();
+        let (tid_tx, tid_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+
+        let worker = std::thread::spawn(move || {
+            pin_thread_to_core(lp).unwrap();
+            tid_tx.send(current_thread_native_id().unwrap()).unwrap();
+            // Stay alive (and the tid valid) until the reader is done.
+            let _ = done_rx.recv();
+        });
+
+        let tid = tid_rx.recv().unwrap();
+        assert_eq!(
+            get_thread_affinity_for(tid).unwrap(),
+            AffinityMask::single(lp)
+        );
+        done_tx.send(()).unwrap();
+        worker.join().unwrap();
+
+        assert!(matches!(
+            get_thread_affinity_for(0),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            get_thread_affinity_for(u64::from(u32::MAX)),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    #[test]
+    fn native_thread_id_is_stable_per_thread() {
+        let id = current_thread_native_id().unwrap();
+        assert_ne!(id, 0);
+        assert_eq!(current_thread_native_id().unwrap(), id);
+
+        let other = std::thread::spawn(|| current_thread_native_id().unwrap())
+            .join()
+            .unwrap();
+        assert_ne!(other, id);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    #[test]
+    fn thread_cpu_time_grows_with_busy_work() {
+        std::thread::spawn(|| {
+            let before = current_thread_cpu_time().unwrap();
+            // Longer than Windows' 15.6 ms accounting tick.
+            crate::spin_for(Duration::from_millis(50));
+            let after = current_thread_cpu_time().unwrap();
+
+            assert!(after > Duration::ZERO);
+            assert!(after >= before, "{before:?} -> {after:?}");
+        })
+        .join()
+        .unwrap();
+    }
+}
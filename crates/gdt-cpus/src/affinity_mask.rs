@@ -4,6 +4,14 @@
 //! logical processor IDs that a thread may run on. Unlike pinning to a single
 //! core, affinity masks allow threads to migrate between a specified set of
 //! cores, reducing scheduling latency while still constraining execution.
+//!
+//! With feature `serde` the mask serializes, by default, as its fixed bitset:
+//! `{"bits": [w0, w1, ..., w15]}`, sixteen `u64` words with bit `b` of word
+//! `i` = LP `64·i + b`. The `hex` module is the compact alternative, a
+//! `taskset`-style hex string chosen per field with `#[serde(with = ...)]`.
+
+#[cfg(feature = "serde")]
+pub mod hex;
 
 /// Number of `u64` words in the fixed bitset. 16 words = 1024 logical
 /// processors, matching the Linux static `cpu_set_t` (`CPU_SETSIZE`).
@@ -50,6 +58,12 @@ const WORDS: usize = 16;
 ///
 /// - **Linux/Windows**: The mask is applied directly to constrain thread execution
 /// - **macOS**: Returns `Error::Unsupported`; use QoS classes instead
+///
+/// # Serialization
+///
+/// With feature `serde`, either the default sixteen-word bitset or a hex
+/// string via `gdt_cpus::affinity_mask::hex`; see the
+/// [module docs](crate::affinity_mask).
 #[must_use = "a mask is built to be applied or inspected; discarding it does nothing"]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -135,6 +149,32 @@ impl AffinityMask {
         mask
     }
 
+    /// Creates an affinity mask from [`Lp`](crate::Lp) records - each one's
+    /// `os_id`.
+    ///
+    /// Pairs with filtering [`CpuInfo::lps`](crate::CpuInfo::lps()) on any
+    /// field, for sets the canned mask helpers don't cover. A record stands for
+    /// one logical processor, not its whole core: keep the SMT siblings in the
+    /// filter (or use [`CpuInfo::kind_mask`](crate::CpuInfo::kind_mask)) when
+    /// the mask should span full cores.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::{AffinityMask, CoreKind, CpuInfo};
+    ///
+    /// if let Ok(info) = CpuInfo::detect() {
+    ///     let p_lps = info.lps().iter().filter(|lp| lp.kind == CoreKind::Performance);
+    ///     let mask = AffinityMask::from_lps(p_lps.clone());
+    ///
+    ///     assert_eq!(mask.count(), p_lps.count());
+    ///     assert_eq!(mask, info.performance_core_mask());
+    /// }
+    /// ```
+    pub fn from_lps<'a>(lps: impl IntoIterator<Item = &'a crate::Lp>) -> Self {
+        lps.into_iter().map(|lp| lp.os_id as usize).collect()
+    }
+
     /// Adds a logical core to the mask.
     ///
     /// If the core is already in the mask, this is a no-op. Ids at or above
@@ -194,6 +234,62 @@ impl AffinityMask {
         self.bits[word_idx] &= !(1u64 << bit_idx);
     }
 
+    /// Removes every logical core from the mask.
+    ///
+    /// The storage is a fixed inline array, so this only zeroes it - nothing
+    /// is freed or reallocated, and the mask can be refilled in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mut mask = AffinityMask::from_cores(&[0, 1, 200]);
+    /// mask.clear();
+    /// assert!(mask.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.bits = [0; WORDS];
+    }
+
+    /// Adds every logical core in `start..end` to the mask.
+    ///
+    /// The range is half-open. An empty range (`start >= end`) is a no-op, and
+    /// the part at or above [`MAX_LP_COUNT`](Self::MAX_LP_COUNT) is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - First logical processor ID to add
+    /// * `end` - One past the last logical processor ID to add
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mut mask = AffinityMask::empty();
+    /// mask.set_range(2, 5);
+    /// assert_eq!(mask.iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// ```
+    pub fn set_range(&mut self, start: usize, end: usize) {
+        let end = end.min(Self::MAX_LP_COUNT);
+        let mut id = start;
+
+        while id < end {
+            let word_idx = id / 64;
+            let bit_idx = id % 64;
+            let span = (64 - bit_idx).min(end - id);
+            let bits = if span == 64 {
+                u64::MAX
+            } else {
+                ((1u64 << span) - 1) << bit_idx
+            };
+
+            self.bits[word_idx] |= bits;
+            id += span;
+        }
+    }
+
     /// Checks if a logical core is in the mask.
     ///
     /// # Arguments
@@ -260,6 +356,60 @@ impl AffinityMask {
         self.bits.iter().all(|&w| w == 0)
     }
 
+    /// The highest core ID in the mask, or `None` if it is empty - the bound
+    /// to size an OS CPU set (`CPU_ALLOC_SIZE(highest + 1)`) by.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mask = AffinityMask::from_cores(&[3, 130]);
+    /// assert_eq!(mask.highest_core(), Some(130));
+    /// assert_eq!(AffinityMask::empty().highest_core(), None);
+    /// ```
+    #[must_use]
+    pub fn highest_core(&self) -> Option<usize> {
+        let word = self.bits.iter().rposition(|&w| w != 0)?;
+
+        Some(word * 64 + 63 - self.bits[word].leading_zeros() as usize)
+    }
+
+    /// The lowest core ID in the mask, or `None` if it is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mask = AffinityMask::from_cores(&[3, 130]);
+    /// assert_eq!(mask.lowest_core(), Some(3));
+    /// ```
+    #[must_use]
+    pub fn lowest_core(&self) -> Option<usize> {
+        let word = self.bits.iter().position(|&w| w != 0)?;
+
+        Some(word * 64 + self.bits[word].trailing_zeros() as usize)
+    }
+
+    /// Bits needed to hold the mask, in whole 64-bit words: `64 *`
+    /// [`used_words`](Self::used_words)`().len()`, 0 when empty. The
+    /// storage itself is always [`MAX_LP_COUNT`](Self::MAX_LP_COUNT) bits;
+    /// this is the size a `cpu_set_t` or processor-group walk actually needs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// assert_eq!(AffinityMask::single(63).bit_capacity(), 64);
+    /// assert_eq!(AffinityMask::single(64).bit_capacity(), 128);
+    /// ```
+    #[must_use]
+    pub fn bit_capacity(&self) -> usize {
+        self.used_words().len() * 64
+    }
+
     /// Returns an iterator over the core IDs in the mask.
     ///
     /// # Example
@@ -336,6 +486,28 @@ impl AffinityMask {
         AffinityMask { bits }
     }
 
+    /// Keeps only the cores that are also in `other` - [`intersection`](Self::intersection)
+    /// without the copy.
+    ///
+    /// Storage is fixed-width, so there is no capacity to get out of step:
+    /// the result compares, hashes and encodes exactly like a freshly built
+    /// mask with the same cores (an emptied mask equals [`AffinityMask::empty`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mut mask = AffinityMask::from_cores(&[0, 1, 130]);
+    /// mask.intersect_in_place(&AffinityMask::from_cores(&[1, 2]));
+    /// assert_eq!(mask, AffinityMask::single(1));
+    /// ```
+    pub fn intersect_in_place(&mut self, other: &AffinityMask) {
+        for (word, &keep) in self.bits.iter_mut().zip(&other.bits) {
+            *word &= keep;
+        }
+    }
+
     /// Returns the first 64 cores as a raw `u64` bitmask.
     ///
     /// This is useful for platform APIs that only support 64 cores.
@@ -362,6 +534,120 @@ impl AffinityMask {
     pub fn as_raw_bits(&self) -> &[u64] {
         &self.bits
     }
+
+    /// The raw words up to the highest non-zero one - empty for an empty
+    /// mask. Same layout as [`as_raw_bits`](Self::as_raw_bits), but
+    /// canonical: removing a mask's only high core shrinks it back, and equal
+    /// masks always yield equal slices. The backing store is fixed, so there
+    /// is nothing to `shrink_to_fit`; the trimming happens here, on every
+    /// call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mut mask = AffinityMask::from_cores(&[1, 130]);
+    /// assert_eq!(mask.used_words().len(), 3);
+    ///
+    /// mask.remove(130);
+    /// assert_eq!(mask.used_words(), &[0b10]);
+    /// ```
+    #[must_use]
+    pub fn used_words(&self) -> &[u64] {
+        let used = self.bits.iter().rposition(|&w| w != 0).map_or(0, |i| i + 1);
+
+        &self.bits[..used]
+    }
+
+    /// Serializes the mask to a host-independent byte string for IPC (a
+    /// launcher handing the game its cores over a pipe, a C tool writing a
+    /// config blob).
+    ///
+    /// # Wire format
+    ///
+    /// All integers little-endian, whatever the host byte order:
+    ///
+    /// | offset    | size    | field                                         |
+    /// |-----------|---------|-----------------------------------------------|
+    /// | 0         | 4       | `n`: number of 64-bit words that follow (u32) |
+    /// | 4 + 8·i   | 8       | word `i`: bit `b` set = LP `64·i + b` (u64)   |
+    ///
+    /// The encoder writes the [`used_words`](Self::used_words), so `n` is 0 for an empty mask and the output is canonical
+    /// (equal masks, equal bytes). In C:
+    ///
+    /// ```c
+    /// uint32_t n = p[0] | p[1] << 8 | p[2] << 16 | (uint32_t)p[3] << 24;
+    /// for (uint32_t i = 0; i < n; i++)
+    ///     for (int k = 0; k < 8; k++)
+    ///         words[i] |= (uint64_t)p[4 + 8 * i + k] << (8 * k);
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::AffinityMask;
+    ///
+    /// let mask = AffinityMask::from_cores(&[0, 9]);
+    /// assert_eq!(mask.to_bytes(), [1, 0, 0, 0, 0x01, 0x02, 0, 0, 0, 0, 0, 0]);
+    /// assert_eq!(AffinityMask::from_bytes(&mask.to_bytes()).unwrap(), mask);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let words = self.used_words();
+        let mut out = Vec::with_capacity(4 + words.len() * 8);
+
+        out.extend_from_slice(&(words.len() as u32).to_le_bytes());
+        for word in words {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    /// Parses the [`to_bytes`](Self::to_bytes) wire format.
+    ///
+    /// Producers need not trim: trailing zero words are accepted, so a C
+    /// side may always send a fixed-size buffer.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::Error::InvalidParameter`] if `bytes` is shorter than the
+    /// prefix, its length disagrees with the word count, or a bit at or
+    /// above [`MAX_LP_COUNT`](Self::MAX_LP_COUNT) is set.
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        let invalid = |msg: String| crate::Error::InvalidParameter(msg.into());
+
+        let (prefix, payload) = bytes.split_first_chunk::<4>().ok_or_else(|| {
+            invalid(format!(
+                "affinity mask blob of {} bytes has no length prefix",
+                bytes.len()
+            ))
+        })?;
+        let words = u32::from_le_bytes(*prefix) as usize;
+        if payload.len() != words.saturating_mul(8) {
+            return Err(invalid(format!(
+                "affinity mask blob announces {} words but carries {} payload bytes",
+                words,
+                payload.len()
+            )));
+        }
+
+        let mut mask = Self::empty();
+        for (i, chunk) in payload.chunks_exact(8).enumerate() {
+            let word = u64::from_le_bytes(chunk.try_into().expect("chunks_exact(8)"));
+            match mask.bits.get_mut(i) {
+                Some(slot) => *slot = word,
+                None if word == 0 => {}
+                None => {
+                    return Err(invalid(format!(
+                        "affinity mask blob sets LPs at or above {} (word {})",
+                        Self::MAX_LP_COUNT,
+                        i
+                    )));
+                }
+            }
+        }
+        Ok(mask)
+    }
 }
 
 impl std::fmt::Debug for AffinityMask {
@@ -485,6 +771,38 @@ mod tests {
         assert!(mask.contains(1));
     }
 
+    #[test]
+    fn test_clear_zeroes_in_place() {
+        let mut mask = AffinityMask::from_cores(&[0, 5, 64, 1023]);
+        let storage = mask.as_raw_bits().as_ptr();
+
+        mask.clear();
+        assert_eq!(mask.count(), 0);
+        assert_eq!(mask.as_raw_bits().as_ptr(), storage);
+
+        mask.add(3);
+        assert_eq!(mask, AffinityMask::single(3));
+    }
+
+    #[test]
+    fn test_set_range() {
+        let mut mask = AffinityMask::empty();
+        mask.set_range(2, 5);
+        assert_eq!(mask.iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        // Spans words, and an empty range is a no-op.
+        let mut wide = AffinityMask::empty();
+        wide.set_range(60, 200);
+        wide.set_range(7, 7);
+        assert_eq!(wide.count(), 140);
+        assert!(!wide.contains(59) && wide.contains(60));
+        assert!(wide.contains(199) && !wide.contains(200));
+
+        let mut top = AffinityMask::empty();
+        top.set_range(1020, usize::MAX);
+        assert_eq!(top.iter().collect::<Vec<_>>(), vec![1020, 1021, 1022, 1023]);
+    }
+
     #[test]
     fn test_high_core_ids() {
         let mut mask = AffinityMask::empty();
@@ -545,6 +863,102 @@ mod tests {
         assert_eq!(narrow.union(&AffinityMask::empty()), narrow);
     }
 
+    // A mask reaching the third word (LP 130) against one confined to the
+    // first: nothing in common, and the empty result is indistinguishable
+    // from a fresh one - whichever way round, copied or in place.
+    #[test]
+    fn test_intersection_of_disjoint_widths_is_canonical_empty() {
+        let high = AffinityMask::from_cores(&[128, 130]);
+        let low = AffinityMask::from_cores(&[0, 3]);
+        assert_eq!(high.used_words().len(), 3);
+        assert_eq!(low.used_words().len(), 1);
+
+        for result in [high.intersection(&low), low.intersection(&high)] {
+            assert!(result.is_empty());
+            assert_eq!(result, AffinityMask::empty());
+            assert!(result.used_words().is_empty());
+        }
+
+        let mut in_place = high;
+        in_place.intersect_in_place(&low);
+        assert_eq!(in_place, AffinityMask::empty());
+        assert_eq!(in_place.to_bytes(), [0, 0, 0, 0]);
+
+        // A later union sees only the other operand's cores.
+        assert_eq!(in_place.union(&low), low);
+    }
+
+    // The used words end at the highest non-zero word, so long-lived masks
+    // that once held a high core report the same words as a fresh one. The
+    // full raw view keeps its fixed length throughout.
+    #[test]
+    fn test_used_words_trim_after_remove() {
+        let mut mask = AffinityMask::from_cores(&[3, 700]);
+        assert_eq!(mask.used_words().len(), 11);
+
+        mask.remove(700);
+        assert_eq!(mask.used_words(), AffinityMask::single(3).used_words());
+        assert_eq!(mask.used_words().len(), 1);
+
+        mask.remove(3);
+        assert!(mask.used_words().is_empty());
+        assert_eq!(mask.as_raw_bits().len(), AffinityMask::MAX_LP_COUNT / 64);
+
+        let full = AffinityMask::single(AffinityMask::MAX_LP_COUNT - 1);
+        assert_eq!(full.used_words().len(), AffinityMask::MAX_LP_COUNT / 64);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        for cores in [&[][..], &[0], &[1, 63, 64], &[5, 700, 1023]] {
+            let mask = AffinityMask::from_cores(cores);
+            assert_eq!(AffinityMask::from_bytes(&mask.to_bytes()).unwrap(), mask);
+        }
+        assert_eq!(AffinityMask::empty().to_bytes(), [0, 0, 0, 0]);
+    }
+
+    // The layout is spelled out byte by byte with shifts rather than
+    // `to_le_bytes`, so it pins the wire format the way a big-endian peer (or
+    // C code) would assemble it, independent of the host's byte order.
+    #[test]
+    fn test_bytes_layout_is_little_endian() {
+        let mask = AffinityMask::from_cores(&[0, 8, 65, 127]);
+        let mut expected = vec![2, 0, 0, 0];
+        for word in [1u64 | 1 << 8, 1 << 1 | 1 << 63] {
+            expected.extend((0..8).map(|k| (word >> (8 * k)) as u8));
+        }
+        assert_eq!(mask.to_bytes(), expected);
+        assert_eq!(
+            &mask.to_bytes()[4..12],
+            [0x01, 0x01, 0, 0, 0, 0, 0, 0],
+            "LPs 0 and 8 land in the first two bytes"
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_malformed() {
+        assert!(matches!(
+            AffinityMask::from_bytes(&[1, 0]),
+            Err(crate::Error::InvalidParameter(_))
+        ));
+        // Announces 2 words, carries 1.
+        let mut short = vec![2, 0, 0, 0];
+        short.extend([0xff; 8]);
+        assert!(AffinityMask::from_bytes(&short).is_err());
+
+        // Untrimmed producers: zero words past the capacity are fine, a set
+        // bit there is not.
+        let mut padded = vec![17, 0, 0, 0];
+        padded.extend([0; 17 * 8]);
+        padded[4] = 0b1;
+        assert_eq!(
+            AffinityMask::from_bytes(&padded).unwrap(),
+            AffinityMask::single(0)
+        );
+        padded[4 + 16 * 8] = 0b1;
+        assert!(AffinityMask::from_bytes(&padded).is_err());
+    }
+
     // The capacity boundary: 1023 is the last valid core; MAX_LP_COUNT and
     // above are out of range and silently ignored (never an allocation/panic).
     #[test]
@@ -560,6 +974,24 @@ mod tests {
         assert_eq!(mask.count(), 1);
     }
 
+    #[test]
+    fn test_highest_and_lowest_core() {
+        let mask = AffinityMask::from_cores(&[5, 64, 130]);
+        assert_eq!(mask.highest_core(), Some(130));
+        assert_eq!(mask.lowest_core(), Some(5));
+        assert_eq!(mask.bit_capacity(), 192);
+
+        let top = AffinityMask::single(AffinityMask::MAX_LP_COUNT - 1);
+        assert_eq!(top.highest_core(), Some(1023));
+        assert_eq!(top.lowest_core(), Some(1023));
+        assert_eq!(top.bit_capacity(), AffinityMask::MAX_LP_COUNT);
+
+        let empty = AffinityMask::empty();
+        assert_eq!(empty.highest_core(), None);
+        assert_eq!(empty.lowest_core(), None);
+        assert_eq!(empty.bit_capacity(), 0);
+    }
+
     // Debug renders cores as a bracketed range list; the brackets keep an
     // empty mask from formatting as a blank field and keep multi-range output
     // from blurring into the trailing `count` field.
@@ -0,0 +1,159 @@
+//! Compact serde representation of [`AffinityMask`] as a hex bitstring -
+//! opt in per field with `#[serde(with = "gdt_cpus::affinity_mask::hex")]`.
+//!
+//! The string is the mask as one big hexadecimal number, most significant
+//! digit first: bit `n` set = LP `n`, so `"f"` is LPs 0-3 and `"100000003"`
+//! is LPs 0, 1 and 32. It is the format `taskset` prints and accepts. The
+//! encoder writes lowercase without leading zeros (`"0"` for an empty mask),
+//! so equal masks encode equally; a 256-LP server's full mask is 64
+//! characters instead of the default representation's sixteen-word array.
+//!
+//! The decoder also accepts an optional `0x` prefix, uppercase digits,
+//! leading zeros and `,` group separators - the Linux cpumask format of
+//! `/proc/<pid>/status` `Cpus_allowed` and sysfs `cpumap` files.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use gdt_cpus::AffinityMask;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct WorkerConfig {
+//!     #[serde(with = "gdt_cpus::affinity_mask::hex")]
+//!     cores: AffinityMask,
+//! }
+//!
+//! let config = WorkerConfig { cores: (0..8).collect() };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"cores":"ff"}"#);
+//! # }
+//! ```
+
+use super::{AffinityMask, WORDS};
+
+/// Writes `mask` as a hex bitstring.
+pub fn serialize<S: serde::Serializer>(
+    mask: &AffinityMask,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(mask))
+}
+
+/// Reads a mask from a hex bitstring.
+///
+/// Fails on an empty string, a character that is not a hex digit or `,`,
+/// and bits at or above [`AffinityMask::MAX_LP_COUNT`].
+pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AffinityMask, D::Error> {
+    let text = <String as serde::Deserialize>::deserialize(deserializer)?;
+    decode(&text).map_err(serde::de::Error::custom)
+}
+
+fn encode(mask: &AffinityMask) -> String {
+    let words = mask.used_words();
+    let Some((highest, rest)) = words.split_last() else {
+        return "0".to_string();
+    };
+
+    let mut out = format!("{highest:x}");
+    for word in rest.iter().rev() {
+        out.push_str(&format!("{word:016x}"));
+    }
+    out
+}
+
+fn decode(text: &str) -> Result<AffinityMask, String> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    if !digits.bytes().any(|b| b != b',') {
+        return Err(format!("affinity mask hex string {text:?} has no digits"));
+    }
+
+    let mut mask = AffinityMask::empty();
+    // Least significant digit first: nibble `i` covers LPs 4i..4i+4.
+    for (nibble, c) in digits.chars().rev().filter(|&c| c != ',').enumerate() {
+        let value = c
+            .to_digit(16)
+            .ok_or_else(|| format!("affinity mask hex string {text:?} has a non-hex {c:?}"))?;
+        if value == 0 {
+            continue;
+        }
+        if nibble >= WORDS * 16 {
+            return Err(format!(
+                "affinity mask hex string {text:?} sets LPs at or above {}",
+                AffinityMask::MAX_LP_COUNT
+            ));
+        }
+        mask.bits[nibble / 16] |= u64::from(value) << (4 * (nibble % 16));
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_strings_encode_the_bitset() {
+        assert_eq!(encode(&AffinityMask::empty()), "0");
+        assert_eq!(encode(&AffinityMask::from_cores(&[0, 1, 2, 3])), "f");
+        assert_eq!(encode(&AffinityMask::from_cores(&[0, 1, 32])), "100000003");
+        assert_eq!(
+            encode(&AffinityMask::from_cores(&[64])),
+            "10000000000000000"
+        );
+
+        assert_eq!(decode("0").unwrap(), AffinityMask::empty());
+        assert_eq!(
+            decode("0x0F").unwrap(),
+            AffinityMask::from_cores(&[0, 1, 2, 3])
+        );
+        // Linux cpumask: 32-bit groups, comma-separated.
+        assert_eq!(
+            decode("00000001,00000003").unwrap(),
+            AffinityMask::from_cores(&[0, 1, 32])
+        );
+        // Leading zeros beyond the capacity are harmless; set bits are not.
+        assert_eq!(
+            decode(&format!("{}1", "0".repeat(300))).unwrap(),
+            AffinityMask::single(0)
+        );
+        assert!(decode(&format!("1{}", "0".repeat(256))).is_err());
+        assert!(decode("").is_err());
+        assert!(decode("0x").is_err());
+        assert!(decode("fg").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn hex_round_trips_through_serde() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Config {
+            #[serde(with = "crate::affinity_mask::hex")]
+            cores: AffinityMask,
+        }
+
+        // 200 LPs: every even one, and the top of the range.
+        let mut cores: AffinityMask = (0..200).step_by(2).collect();
+        cores.add(AffinityMask::MAX_LP_COUNT - 1);
+        for mask in [AffinityMask::empty(), AffinityMask::single(63), cores] {
+            let config = Config { cores: mask };
+            let json = serde_json::to_string(&config).unwrap();
+            assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+        }
+
+        let wide = Config {
+            cores: (0..200).collect(),
+        };
+        let json = serde_json::to_string(&wide).unwrap();
+        assert_eq!(json, format!(r#"{{"cores":"{}"}}"#, "f".repeat(50)));
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), wide);
+
+        let err = serde_json::from_str::<Config>(r#"{"cores":"xyz"}"#).unwrap_err();
+        assert!(err.to_string().contains("non-hex"), "{err}");
+    }
+}
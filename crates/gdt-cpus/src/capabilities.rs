@@ -24,6 +24,7 @@ use crate::ThreadPriority;
 #[must_use = "priority_capabilities() has no side effect; its return value is the whole point -- \
               inspect distinct()/rank() to plan the threading strategy"]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PriorityCaps {
     /// Effective strength rank per level, indexed by `ThreadPriority as usize`
     /// (`Background` = 0 … `TimeCritical` = 6).
@@ -6,20 +6,33 @@
 //! each LP, not a container: per-socket hierarchies cannot represent chiplet
 //! CPUs, where one socket carries several L3 domains.
 
+#[cfg(feature = "test-util")]
+mod builder;
 mod cache_info;
+mod cache_scope;
 mod core_kind;
+mod detect_options;
+mod detection_phase;
 mod features;
 mod info;
 mod l2_domain;
 mod l3_domain;
 mod lp;
+mod memory_tier;
+mod microarch;
 mod vendor;
 
-pub use cache_info::CacheInfo;
+#[cfg(feature = "test-util")]
+pub use builder::CpuInfoBuilder;
+pub use cache_info::{CacheInfo, WritePolicy};
+pub use cache_scope::{CacheLevel, CacheOwner, CacheScope, CacheSet};
 pub use core_kind::CoreKind;
+pub use detect_options::DetectOptions;
+pub use detection_phase::DetectionPhase;
 pub use features::CpuFeatures;
 pub use info::CpuInfo;
 pub use l2_domain::L2Domain;
 pub use l3_domain::L3Domain;
 pub use lp::Lp;
+pub use memory_tier::MemoryTier;
 pub use vendor::Vendor;
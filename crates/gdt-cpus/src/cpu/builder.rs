@@ -0,0 +1,431 @@
+//! Synthetic topologies for downstream unit tests (feature `test-util`).
+//!
+//! Scheduling code that branches on hybrid / multi-socket / chiplet layouts
+//! cannot be exercised on a CI runner that has none of them. Hand-writing a
+//! [`CpuInfo`] literal means tracking every derived field (core and kind
+//! counts, domain tables, masks) and breaks each time a field is added;
+//! [`CpuInfoBuilder`] describes the machine core by core and derives the rest
+//! exactly like the detection backends do.
+
+use crate::{
+    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, DetectOptions, L2Domain, L3Domain, Lp,
+    Vendor,
+};
+
+/// How subsequently added cores get their L2.
+#[derive(Debug, Clone, Copy)]
+enum L2Mode {
+    None,
+    PerCore(u64),
+    Shared(u16),
+}
+
+/// Fluent builder for a synthetic [`CpuInfo`].
+///
+/// Cores are added in order; each gets the next dense core index and, unless
+/// [`core_with_ids`](Self::core_with_ids) names them, the next free OS LP ids.
+/// [`socket`](Self::socket), [`l3`](Self::l3), [`l2_per_core`](Self::l2_per_core),
+/// [`l2_shared`](Self::l2_shared) and [`numa_node`](Self::numa_node) set the
+/// placement of every core added AFTER them, so a layout reads top-down like
+/// an `lstopo` dump.
+///
+/// # Example
+///
+/// ```
+/// use gdt_cpus::{CoreKind, CpuInfoBuilder};
+///
+/// // 8 P-cores with SMT + 2 clusters of 4 E-cores, one shared L3.
+/// let info = CpuInfoBuilder::new()
+///     .l3(30 << 20)
+///     .l2_per_core(2 << 20)
+///     .cores(8, CoreKind::Performance, 2)
+///     .l2_shared(4 << 20)
+///     .cores(4, CoreKind::Efficiency, 1)
+///     .l2_shared(4 << 20)
+///     .cores(4, CoreKind::Efficiency, 1)
+///     .build();
+///
+/// assert!(info.is_hybrid());
+/// assert_eq!(info.num_logical_cores(), 24);
+/// assert_eq!(info.l2_domains.len(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CpuInfoBuilder {
+    lps: Vec<Lp>,
+    core_count: u16,
+    next_os_id: u16,
+    socket: u8,
+    numa_node: u8,
+    l3: Option<u8>,
+    l2: L2Mode,
+    l3_domains: Vec<L3Domain>,
+    l2_domains: Vec<L2Domain>,
+    l1d: [CacheInfo; CoreKind::COUNT],
+    l1i: [CacheInfo; CoreKind::COUNT],
+    l2_caches: [CacheInfo; CoreKind::COUNT],
+    vendor: Vendor,
+    model_name: String,
+    features: CpuFeatures,
+    base_frequency_mhz: Option<u32>,
+    max_frequency_mhz: Option<u32>,
+}
+
+impl Default for CpuInfoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuInfoBuilder {
+    /// An empty machine: socket 0, NUMA node 0, no caches, vendor
+    /// [`Vendor::Unknown`], model name `"synthetic cpu"`.
+    pub fn new() -> Self {
+        Self {
+            lps: Vec::new(),
+            core_count: 0,
+            next_os_id: 0,
+            socket: 0,
+            numa_node: 0,
+            l3: None,
+            l2: L2Mode::None,
+            l3_domains: Vec::new(),
+            l2_domains: Vec::new(),
+            l1d: Default::default(),
+            l1i: Default::default(),
+            l2_caches: Default::default(),
+            vendor: Vendor::Unknown,
+            model_name: "synthetic cpu".to_string(),
+            features: CpuFeatures::default(),
+            base_frequency_mhz: None,
+            max_frequency_mhz: None,
+        }
+    }
+
+    /// Starts the next socket. Cores added afterwards land on it, outside
+    /// any L3 / L2 domain until [`l3`](Self::l3) or an L2 call opens one.
+    /// The first socket is implicit - do not call this before the first core.
+    pub fn socket(mut self) -> Self {
+        self.socket += 1;
+        self.l3 = None;
+        self.l2 = L2Mode::None;
+        self
+    }
+
+    /// Places subsequently added cores on NUMA node `node`.
+    pub fn numa_node(mut self, node: u8) -> Self {
+        self.numa_node = node;
+        self
+    }
+
+    /// Opens a new L3 domain of `size_bytes` shared by every core added
+    /// until the next `l3` / [`socket`](Self::socket) call. Closes any open
+    /// L2 cluster, since an L2 never spans two L3s.
+    pub fn l3(mut self, size_bytes: u64) -> Self {
+        self.l3 = Some(self.l3_domains.len() as u8);
+        self.l3_domains.push(L3Domain {
+            size_bytes,
+            mask: AffinityMask::empty(),
+            core_count: 0,
+        });
+        self.l2 = L2Mode::None;
+        self
+    }
+
+    /// Gives each subsequently added core its own private L2 of `size_bytes`
+    /// (shared only by its SMT siblings).
+    pub fn l2_per_core(mut self, size_bytes: u64) -> Self {
+        self.l2 = L2Mode::PerCore(size_bytes);
+        self
+    }
+
+    /// Opens one L2 of `size_bytes` shared by every core added until the
+    /// next L2 / [`l3`](Self::l3) / [`socket`](Self::socket) call - an Intel
+    /// E-core cluster.
+    pub fn l2_shared(mut self, size_bytes: u64) -> Self {
+        self.l2 = L2Mode::Shared(self.push_l2(size_bytes));
+        self
+    }
+
+    /// Sets the per-kind L1d / L1i / L2 descriptions reported in
+    /// [`CpuInfo::l1d`] / [`CpuInfo::l1i`] / [`CpuInfo::l2`].
+    pub fn kind_caches(
+        mut self,
+        kind: CoreKind,
+        l1d: CacheInfo,
+        l1i: CacheInfo,
+        l2: CacheInfo,
+    ) -> Self {
+        self.l1d[kind.index()] = l1d;
+        self.l1i[kind.index()] = l1i;
+        self.l2_caches[kind.index()] = l2;
+        self
+    }
+
+    /// Adds one core of `kind` with `threads` SMT threads (at least 1), on
+    /// the next free OS LP ids.
+    pub fn core(self, kind: CoreKind, threads: u8) -> Self {
+        let ids: Vec<u16> = (0..threads.max(1) as u16)
+            .map(|t| self.next_os_id + t)
+            .collect();
+        self.core_with_ids(kind, &ids)
+    }
+
+    /// Adds `count` cores of `kind`, `threads` SMT threads each.
+    pub fn cores(mut self, count: usize, kind: CoreKind, threads: u8) -> Self {
+        for _ in 0..count {
+            self = self.core(kind, threads);
+        }
+        self
+    }
+
+    /// Adds one core of `kind` whose SMT threads carry exactly the OS LP ids
+    /// in `os_ids` (first id = SMT thread 0) - for sparse or interleaved
+    /// numbering such as Linux's `0-7` primaries + `8-15` siblings.
+    pub fn core_with_ids(mut self, kind: CoreKind, os_ids: &[u16]) -> Self {
+        let core = self.core_count;
+        self.core_count += 1;
+
+        let l2_domain = match self.l2 {
+            L2Mode::None => Lp::NO_L2,
+            L2Mode::PerCore(size) => self.push_l2(size),
+            L2Mode::Shared(domain) => domain,
+        };
+        let l3_domain = self.l3.unwrap_or(Lp::NO_L3);
+
+        if let Some(d) = self.l3_domains.get_mut(l3_domain as usize) {
+            d.core_count += 1;
+        }
+        if let Some(d) = self.l2_domains.get_mut(l2_domain as usize) {
+            d.core_count += 1;
+        }
+
+        for (smt_index, &os_id) in os_ids.iter().enumerate() {
+            if let Some(d) = self.l3_domains.get_mut(l3_domain as usize) {
+                d.mask.add(os_id as usize);
+            }
+            if let Some(d) = self.l2_domains.get_mut(l2_domain as usize) {
+                d.mask.add(os_id as usize);
+            }
+            self.lps.push(Lp {
+                os_id,
+                core,
+                socket: self.socket,
+                l3_domain,
+                l2_domain,
+                numa_node: self.numa_node,
+                kind,
+                smt_index: smt_index as u8,
+                perf_hint: 0,
+                cpu_part: 0,
+                native_core_id: None,
+                capacity: None,
+                freq_min_mhz: None,
+                freq_max_mhz: None,
+            });
+            self.next_os_id = self.next_os_id.max(os_id + 1);
+        }
+        self
+    }
+
+    /// Sets [`Lp::perf_hint`] on every LP of the most
+    /// recently added core - to model favored cores.
+    pub fn perf_hint(mut self, hint: u16) -> Self {
+        let core = self.core_count.wrapping_sub(1);
+        for lp in self.lps.iter_mut().filter(|lp| lp.core == core) {
+            lp.perf_hint = hint;
+        }
+        self
+    }
+
+    /// Sets [`Lp::capacity`] on every LP of the most recently added core.
+    pub fn capacity(mut self, capacity: u16) -> Self {
+        let core = self.core_count.wrapping_sub(1);
+        for lp in self.lps.iter_mut().filter(|lp| lp.core == core) {
+            lp.capacity = Some(capacity);
+        }
+        self
+    }
+
+    /// Sets [`CpuInfo::vendor`].
+    pub fn vendor(mut self, vendor: Vendor) -> Self {
+        self.vendor = vendor;
+        self
+    }
+
+    /// Sets [`CpuInfo::model_name`].
+    pub fn model_name(mut self, name: impl Into<String>) -> Self {
+        self.model_name = name.into();
+        self
+    }
+
+    /// Sets [`CpuInfo::features`].
+    pub fn features(mut self, features: CpuFeatures) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Sets [`CpuInfo::base_frequency_mhz`] / [`CpuInfo::max_frequency_mhz`].
+    pub fn frequencies_mhz(mut self, base: Option<u32>, max: Option<u32>) -> Self {
+        self.base_frequency_mhz = base;
+        self.max_frequency_mhz = max;
+        self
+    }
+
+    /// Derives the counts, SMT support and domain order, then runs the same
+    /// post-construction pass as detection.
+    ///
+    /// # Panics
+    ///
+    /// If the described machine fails [`CpuInfo::validate`] (no cores, an OS
+    /// id used twice, an empty socket) - a broken fixture, not a test result.
+    pub fn build(self) -> CpuInfo {
+        let mut kind_core_counts = [0u16; CoreKind::COUNT];
+        for lp in self.lps.iter().filter(|lp| lp.smt_index == 0) {
+            kind_core_counts[lp.kind.index()] += 1;
+        }
+
+        let mut info = CpuInfo {
+            core_count: self.core_count,
+            socket_count: self.socket + 1,
+            numa_node_count: self
+                .lps
+                .iter()
+                .map(|lp| lp.numa_node + 1)
+                .max()
+                .unwrap_or(1),
+            smt_supported: self.lps.iter().any(|lp| lp.smt_index > 0),
+            lps: self.lps,
+            kind_core_counts,
+            l3_domains: self.l3_domains,
+            l2_domains: self.l2_domains,
+            l1d: self.l1d,
+            l1i: self.l1i,
+            l2: self.l2_caches,
+            vendor: self.vendor,
+            model_name: self.model_name,
+            features: self.features,
+            base_frequency_mhz: self.base_frequency_mhz,
+            max_frequency_mhz: self.max_frequency_mhz,
+            clflush_line_size: None,
+            has_invariant_tsc: false,
+            tsc_frequency_hz: None,
+            microcode: None,
+            tdp_watts: None,
+            x86_signature: None,
+            avx512_downclock_risk: None,
+            schema_version: CpuInfo::SCHEMA_VERSION,
+            detection_warnings: Vec::new(),
+            logical_processor_ids: Default::default(),
+        };
+        info.finalize(&DetectOptions::FULL);
+
+        if let Err(e) = info.validate() {
+            panic!("CpuInfoBuilder produced an invalid topology: {}", e);
+        }
+        info
+    }
+
+    fn push_l2(&mut self, size_bytes: u64) -> u16 {
+        self.l2_domains.push(L2Domain {
+            size_bytes,
+            mask: AffinityMask::empty(),
+            core_count: 0,
+            l3_domain: self.l3.unwrap_or(Lp::NO_L3),
+        });
+        (self.l2_domains.len() - 1) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 8 SMT P-cores + 4 E-cores in one cluster, Alder Lake style.
+    fn hybrid_8p_4e() -> CpuInfo {
+        CpuInfoBuilder::new()
+            .vendor(Vendor::Intel)
+            .model_name("synthetic 8P+4E")
+            .l3(25 << 20)
+            .l2_per_core(1280 << 10)
+            .cores(8, CoreKind::Performance, 2)
+            .l2_shared(2 << 20)
+            .cores(4, CoreKind::Efficiency, 1)
+            .build()
+    }
+
+    #[test]
+    fn hybrid_accessors_match_the_description() {
+        let info = hybrid_8p_4e();
+
+        assert_eq!(info.num_physical_cores(), 12);
+        assert_eq!(info.num_logical_cores(), 20);
+        assert_eq!(info.num_performance_cores(), 8);
+        assert_eq!(info.num_efficiency_cores(), 4);
+        assert!(info.is_hybrid());
+        assert!(info.smt_supported);
+        assert_eq!(info.socket_count, 1);
+
+        assert_eq!(
+            info.performance_core_mask().iter().collect::<Vec<_>>(),
+            (0..16).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            info.efficiency_core_mask().iter().collect::<Vec<_>>(),
+            (16..20).collect::<Vec<_>>()
+        );
+        assert_eq!(info.primary_thread_mask().count(), 12);
+        assert_eq!(info.core_mask(0).iter().collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(info.core_mask(11).iter().collect::<Vec<_>>(), [19]);
+        assert!(info.core_mask(12).is_empty());
+
+        assert_eq!(info.l3_domains.len(), 1);
+        assert_eq!(info.l3_domains[0].core_count, 12);
+        assert_eq!(info.l2_domains.len(), 9);
+        assert_eq!(info.l2_domains[8].core_count, 4);
+        assert_eq!(info.l2_domain_mask(8).count(), 4);
+        assert!(info.detection_warnings.is_empty());
+    }
+
+    #[test]
+    fn sockets_numa_and_explicit_ids() {
+        let info = CpuInfoBuilder::new()
+            .l3(32 << 20)
+            .core_with_ids(CoreKind::Performance, &[0, 4])
+            .core_with_ids(CoreKind::Performance, &[1, 5])
+            .socket()
+            .numa_node(1)
+            .l3(32 << 20)
+            .core_with_ids(CoreKind::Performance, &[2, 6])
+            .core_with_ids(CoreKind::Performance, &[3, 7])
+            .perf_hint(10)
+            .build();
+
+        assert_eq!(info.socket_count, 2);
+        assert_eq!(info.numa_node_count, 2);
+        assert_eq!(info.socket_mask(1).iter().collect::<Vec<_>>(), [2, 3, 6, 7]);
+        assert_eq!(
+            info.numa_node_mask(0).iter().collect::<Vec<_>>(),
+            [0, 1, 4, 5]
+        );
+        assert_eq!(
+            info.l3_domain_mask(1).iter().collect::<Vec<_>>(),
+            [2, 3, 6, 7]
+        );
+        assert_eq!(
+            info.favored_cores()
+                .iter()
+                .map(|lp| lp.os_id)
+                .collect::<Vec<_>>(),
+            [3]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "listed twice")]
+    fn duplicate_ids_are_rejected() {
+        let _ = CpuInfoBuilder::new()
+            .core_with_ids(CoreKind::Performance, &[0])
+            .core_with_ids(CoreKind::Performance, &[0])
+            .build();
+    }
+}
@@ -3,8 +3,8 @@
 /// The library stores caches per CORE KIND (L1d/L1i/L2 are uniform within a
 /// kind on all shipping silicon) and per L3 DOMAIN - never per core, which
 /// only duplicates identical data, and never per socket, which cannot
-/// represent chiplet parts. Cache level/type enums remain internal parsing
-/// vocabulary for the platform detectors.
+/// represent chiplet parts. [`CpuInfo::caches`](crate::CpuInfo::caches)
+/// flattens both into one list of instances for display.
 #[derive(Debug, Clone, Copy, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CacheInfo {
@@ -15,4 +15,30 @@ pub struct CacheInfo {
     /// Number of LPs sharing ONE instance of this cache
     /// (2 = core-private with SMT; >2 = cluster-shared, e.g. Intel E-core L2).
     pub shared_by: u16,
+    /// How writes reach the next level. `None` = not reported (only Linux
+    /// sysfs exposes it, and mostly on ARM).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub write_policy: Option<WritePolicy>,
+    /// Whether this cache holds a copy of everything in the levels below it
+    /// (cpuid leaf 4 / 0x8000_001D on x86). `None` = not reported.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inclusive: Option<bool>,
+    /// Load-to-use latency in core cycles - an ESTIMATE from a built-in table
+    /// keyed by [`CpuInfo::microarchitecture`](crate::CpuInfo::microarchitecture)
+    /// and level, not a measurement. Real latency moves with clock domains,
+    /// die size and contention; use it to rank levels or size blocking, not
+    /// to predict timings. Filled for the L1d, L2 and L3 of listed core
+    /// designs; `None` for L1i and for anything the table lacks.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub estimated_latency_cycles: Option<u32>,
+}
+
+/// Write policy of a cache level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WritePolicy {
+    /// Writes stay in the cache until the line is evicted.
+    WriteBack,
+    /// Every write also goes to the next level.
+    WriteThrough,
 }
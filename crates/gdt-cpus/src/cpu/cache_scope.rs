@@ -0,0 +1,66 @@
+use super::CacheInfo;
+
+/// Which cache a [`CacheScope`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CacheLevel {
+    /// Level 1 data cache.
+    L1d,
+    /// Level 1 instruction cache.
+    L1i,
+    /// Level 2 (unified) cache.
+    L2,
+    /// Level 3 (unified, usually last-level) cache.
+    L3,
+}
+
+/// Who one cache instance belongs to.
+///
+/// Core ids are the dense [`Lp::core`](crate::Lp::core) indices, socket ids
+/// [`Lp::socket`](crate::Lp::socket).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CacheOwner {
+    /// Private to one physical core (shared only by its SMT siblings).
+    Core(usize),
+    /// Shared by every core of one socket.
+    Socket(usize),
+    /// Shared by these cores (ascending) - a subset of a socket: an Intel
+    /// E-core cluster's L2, an AMD CCD's L3.
+    CoreGroup(Vec<usize>),
+}
+
+/// One physical cache instance and its owner, as listed by
+/// [`CpuInfo::caches`](crate::CpuInfo::caches).
+///
+/// A flat view over the per-kind entries and the L2/L3 domain tables, which
+/// stay the source of truth - see [`CacheInfo`] for why the model itself does
+/// not store caches per instance.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheScope {
+    /// Size, line size and sharing degree of this instance.
+    pub info: CacheInfo,
+    /// Which cache this is.
+    pub level: CacheLevel,
+    /// Who shares it.
+    pub owner: CacheOwner,
+}
+
+/// The caches one logical processor runs through, one instance per level, as
+/// returned by [`CpuInfo::caches_for_logical_processor`](crate::CpuInfo::caches_for_logical_processor).
+///
+/// A level is `None` when detection found no such cache for the LP (no L3 on
+/// Apple Silicon, caches hidden in a container).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheSet {
+    /// The core's L1 instruction cache.
+    pub l1i: Option<CacheScope>,
+    /// The core's L1 data cache.
+    pub l1d: Option<CacheScope>,
+    /// The L2 instance - core-private or cluster-shared.
+    pub l2: Option<CacheScope>,
+    /// The L3 instance - the LP's L3 domain.
+    pub l3: Option<CacheScope>,
+}
@@ -0,0 +1,47 @@
+/// Which optional parts of detection [`CpuInfo::detect_with`](crate::CpuInfo::detect_with)
+/// runs. The default enables everything, which is what
+/// [`CpuInfo::detect`](crate::CpuInfo::detect) does.
+///
+/// Topology (LPs, cores, sockets, core kinds, NUMA nodes) and identity
+/// (vendor, model, features) are always detected. On Linux the skipped steps
+/// are never run - the per-LP `cache/index*` and `cpufreq` walks dominate
+/// detection time on many-core servers. Elsewhere caches and clocks come
+/// from the same single queries as the topology, so skipping only clears
+/// the fields, for results that look the same on every platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DetectOptions {
+    /// Enumerate caches. When `false`, every [`CacheInfo`](crate::CacheInfo)
+    /// stays zeroed, [`l3_domains`](crate::CpuInfo::l3_domains) and
+    /// [`l2_domains`](crate::CpuInfo::l2_domains) stay empty, every LP reports
+    /// [`Lp::NO_L3`](crate::Lp::NO_L3) / [`Lp::NO_L2`](crate::Lp::NO_L2), and
+    /// no "cache info unavailable" warning is recorded. Intel low-power
+    /// island E-cores are told apart by their missing L3, so without caches
+    /// they report as plain Efficiency cores.
+    pub caches: bool,
+    /// Read the base and maximum clock frequencies. When `false`,
+    /// [`base_frequency_mhz`](crate::CpuInfo::base_frequency_mhz),
+    /// [`max_frequency_mhz`](crate::CpuInfo::max_frequency_mhz) and every
+    /// LP's governor limits ([`Lp::freq_min_mhz`](crate::Lp::freq_min_mhz) /
+    /// [`Lp::freq_max_mhz`](crate::Lp::freq_max_mhz)) are `None`.
+    pub frequencies: bool,
+}
+
+impl DetectOptions {
+    /// Everything - the same as [`Default`].
+    pub const FULL: Self = Self {
+        caches: true,
+        frequencies: true,
+    };
+
+    /// Topology and identity only - the fastest detection.
+    pub const TOPOLOGY_ONLY: Self = Self {
+        caches: false,
+        frequencies: false,
+    };
+}
+
+impl Default for DetectOptions {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
@@ -0,0 +1,29 @@
+/// One step of CPU detection, as reported to the callback of
+/// [`CpuInfo::detect_with_progress`](crate::CpuInfo::detect_with_progress).
+///
+/// Backends run the steps in their own order - Linux: topology, caches,
+/// NUMA, identity; Windows and macOS: identity, topology - and only report
+/// the ones they run as a separate step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectionPhase {
+    /// Vendor, model name and ISA features.
+    Identity,
+    /// Logical processors, cores, sockets and core kinds.
+    Topology,
+    /// The per-LP cache walk - the step that dominates on many-core Linux
+    /// servers, and the only one reporting fractions below 1.0.
+    Caches,
+    /// NUMA node membership.
+    Numa,
+}
+
+impl std::fmt::Display for DetectionPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DetectionPhase::Identity => write!(f, "identity"),
+            DetectionPhase::Topology => write!(f, "topology"),
+            DetectionPhase::Caches => write!(f, "caches"),
+            DetectionPhase::Numa => write!(f, "numa"),
+        }
+    }
+}
@@ -11,13 +11,13 @@ bitflags! {
     ///
     /// The specific flags are derived from CPUID instruction results.
     ///
-    /// Each flag's bit VALUE is a stable serialization format: a stored or
-    /// transmitted feature set deserializes by bit. APPEND new flags at the end
-    /// (next free bit); NEVER insert mid-list or renumber -- that would break
+    /// With the `serde` feature a set serializes as a list of flag NAMES
+    /// (`["SSE2", "AVX2"]`), readable without knowing the bit layout. Bit
+    /// values still matter: blobs from releases before schema 3 stored the
+    /// raw bits in binary formats. APPEND new flags at the end (next free
+    /// bit); NEVER insert mid-list or renumber -- that would break
     /// deserialization of an existing set. The conformance test below pins it.
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct CpuFeatures: u32 {
         /// MMX (MultiMedia eXtensions) support.
         const MMX       = 0x00000001;
@@ -63,6 +63,15 @@ bitflags! {
         const BMI2      = 0x00100000;
         /// F16C (half-precision <-> single-precision float conversion) support.
         const F16C      = 0x00200000;
+        /// RTM (Restricted Transactional Memory: XBEGIN/XEND/XABORT) reported
+        /// by CPUID. Microcode updates (TAA / MDS mitigations) often disable
+        /// TSX while CPUID keeps reporting it - every XBEGIN then aborts, so
+        /// an elision path must still handle a 100% abort rate.
+        const RTM       = 0x00400000;
+        /// HLE (Hardware Lock Elision: XACQUIRE/XRELEASE prefixes) reported by
+        /// CPUID. Same microcode caveat as [`RTM`](Self::RTM); where disabled
+        /// the prefixes are ignored and locks are simply taken.
+        const HLE       = 0x00800000;
     }
 }
 
@@ -76,8 +85,6 @@ bitflags! {
     ///
     /// The specific flags are typically derived from system registers (e.g., ID_AA64ISAR0_EL1).
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct CpuFeatures: u32 {
         /// NEON (Advanced SIMD) support.
         const NEON      = 0x00000001;
@@ -143,13 +150,264 @@ bitflags! {
 bitflags! {
     /// Represents CPU features. On unsupported architectures, this will be empty.
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct CpuFeatures: u32 {
         // No features defined for this architecture by default.
     }
 }
 
+impl CpuFeatures {
+    /// Every flag this architecture defines, with its name, in bit order -
+    /// set or not. For checklist UIs ("AVX2: yes") that must list the flags
+    /// a machine LACKS too; [`iter_names`](Self::iter_names) only walks the
+    /// set ones. Names match the serialized form.
+    ///
+    /// ```
+    /// use gdt_cpus::CpuFeatures;
+    ///
+    /// let detected = CpuFeatures::empty();
+    /// for &(flag, name) in CpuFeatures::ALL {
+    ///     println!("{name}: {}", if detected.contains(flag) { "yes" } else { "no" });
+    /// }
+    /// ```
+    pub const ALL: &'static [(CpuFeatures, &'static str)] = &FLAG_TABLE;
+}
+
+impl CpuFeatures {
+    /// The flags this binary was COMPILED to assume (`-C target-cpu`,
+    /// `-C target-feature`), from `cfg!(target_feature)`. Flags rustc has no
+    /// target feature for (MMX, RTM, HLE; the SVE sub-extensions) are never
+    /// set.
+    pub(crate) const fn compiled() -> Self {
+        let mut features = Self::empty();
+        let mut i = 0;
+        while i < COMPILED_TABLE.len() {
+            if COMPILED_TABLE[i].1 {
+                features = features.union(COMPILED_TABLE[i].0);
+            }
+            i += 1;
+        }
+        features
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+const COMPILED_TABLE: &[(CpuFeatures, bool)] = &[
+    (CpuFeatures::SSE, cfg!(target_feature = "sse")),
+    (CpuFeatures::SSE2, cfg!(target_feature = "sse2")),
+    (CpuFeatures::SSE3, cfg!(target_feature = "sse3")),
+    (CpuFeatures::SSSE3, cfg!(target_feature = "ssse3")),
+    (CpuFeatures::SSE4_1, cfg!(target_feature = "sse4.1")),
+    (CpuFeatures::SSE4_2, cfg!(target_feature = "sse4.2")),
+    // The CRC32 instruction is part of SSE4.2.
+    (CpuFeatures::CRC32, cfg!(target_feature = "sse4.2")),
+    (CpuFeatures::FMA3, cfg!(target_feature = "fma")),
+    (CpuFeatures::AVX, cfg!(target_feature = "avx")),
+    (CpuFeatures::AVX2, cfg!(target_feature = "avx2")),
+    (CpuFeatures::AVX512F, cfg!(target_feature = "avx512f")),
+    (CpuFeatures::AVX512BW, cfg!(target_feature = "avx512bw")),
+    (CpuFeatures::AVX512CD, cfg!(target_feature = "avx512cd")),
+    (CpuFeatures::AVX512DQ, cfg!(target_feature = "avx512dq")),
+    (CpuFeatures::AVX512VL, cfg!(target_feature = "avx512vl")),
+    (CpuFeatures::AES, cfg!(target_feature = "aes")),
+    (CpuFeatures::SHA, cfg!(target_feature = "sha")),
+    (CpuFeatures::POPCNT, cfg!(target_feature = "popcnt")),
+    (CpuFeatures::BMI1, cfg!(target_feature = "bmi1")),
+    (CpuFeatures::BMI2, cfg!(target_feature = "bmi2")),
+    (CpuFeatures::F16C, cfg!(target_feature = "f16c")),
+];
+
+#[cfg(target_arch = "aarch64")]
+const COMPILED_TABLE: &[(CpuFeatures, bool)] = &[
+    (CpuFeatures::NEON, cfg!(target_feature = "neon")),
+    (CpuFeatures::SVE, cfg!(target_feature = "sve")),
+    (CpuFeatures::SVE2, cfg!(target_feature = "sve2")),
+    // rustc's `aes` also enables PMULL (both are FEAT_AES in the ARM ARM).
+    (CpuFeatures::AES, cfg!(target_feature = "aes")),
+    (CpuFeatures::PMULL, cfg!(target_feature = "aes")),
+    (CpuFeatures::SHA, cfg!(target_feature = "sha2")),
+    (CpuFeatures::CRC32, cfg!(target_feature = "crc")),
+    (CpuFeatures::FP16, cfg!(target_feature = "fp16")),
+    (CpuFeatures::DOTPROD, cfg!(target_feature = "dotprod")),
+    (CpuFeatures::I8MM, cfg!(target_feature = "i8mm")),
+    (CpuFeatures::BF16, cfg!(target_feature = "bf16")),
+    (CpuFeatures::LSE, cfg!(target_feature = "lse")),
+    (CpuFeatures::JSCVT, cfg!(target_feature = "jsconv")),
+    (CpuFeatures::LRCPC, cfg!(target_feature = "rcpc")),
+    (CpuFeatures::LRCPC2, cfg!(target_feature = "rcpc2")),
+    (CpuFeatures::RDM, cfg!(target_feature = "rdm")),
+    (CpuFeatures::FHM, cfg!(target_feature = "fhm")),
+    (CpuFeatures::FCMA, cfg!(target_feature = "fcma")),
+];
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const COMPILED_TABLE: &[(CpuFeatures, bool)] = &[];
+
+const FLAG_TABLE: [(CpuFeatures, &str); <CpuFeatures as bitflags::Flags>::FLAGS.len()] = {
+    let flags = <CpuFeatures as bitflags::Flags>::FLAGS;
+    let mut table = [(CpuFeatures::empty(), ""); <CpuFeatures as bitflags::Flags>::FLAGS.len()];
+
+    let mut i = 0;
+    while i < flags.len() {
+        table[i] = (*flags[i].value(), flags[i].name());
+        i += 1;
+    }
+
+    table
+};
+
+// Feature sets travel as name lists (`["SSE2", "AVX2"]`) so a dump from one
+// machine reads without the bit table of its architecture. Deserialization also
+// takes the two shapes older releases wrote through bitflags' own impl: the
+// `"SSE2 | AVX2"` string (human-readable formats) and the raw bits (binary
+// ones). Names this architecture does not define - an aarch64 dump loaded on
+// x86_64, or a flag from a newer release - are dropped with a warning.
+#[cfg(feature = "serde")]
+mod serde_names {
+    use super::CpuFeatures;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // Human-readable formats get the name list; binary formats (bincode,
+    // postcard), which cannot `deserialize_any`, keep the raw bits.
+    impl Serialize for CpuFeatures {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if !serializer.is_human_readable() {
+                return serializer.serialize_u32(self.bits());
+            }
+            let mut seq = serializer.serialize_seq(Some(self.iter_names().count()))?;
+            for (name, _) in self.iter_names() {
+                seq.serialize_element(name)?;
+            }
+            seq.end()
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CpuFeatures {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_any(FeaturesVisitor)
+            } else {
+                deserializer.deserialize_u32(FeaturesVisitor)
+            }
+        }
+    }
+
+    /// Adds the flag called `name`, or warns and skips it.
+    fn insert_named(features: &mut CpuFeatures, name: &str) {
+        let flag = CpuFeatures::from_name(name);
+
+        #[cfg(feature = "log")]
+        if flag.is_none() {
+            log::warn!("ignoring CPU feature {name:?}: not defined for this architecture");
+        }
+
+        features.insert(flag.unwrap_or_else(CpuFeatures::empty));
+    }
+
+    struct FeaturesVisitor;
+
+    impl<'de> Visitor<'de> for FeaturesVisitor {
+        type Value = CpuFeatures;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a list of CPU feature names")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut features = CpuFeatures::empty();
+            while let Some(name) = seq.next_element::<std::borrow::Cow<'de, str>>()? {
+                insert_named(&mut features, &name);
+            }
+            Ok(features)
+        }
+
+        // Pre-schema-3 human-readable shape: `"SSE2 | AVX2"` (`""` when empty).
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            let mut features = CpuFeatures::empty();
+            for name in v.split('|').map(str::trim).filter(|n| !n.is_empty()) {
+                insert_named(&mut features, name);
+            }
+            Ok(features)
+        }
+
+        // Binary formats, and the pre-schema-3 human-readable raw bits.
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            let bits = u32::try_from(v).map_err(|_| E::custom("CPU feature bits exceed u32"))?;
+            let features = CpuFeatures::from_bits_truncate(bits);
+            #[cfg(feature = "log")]
+            if features.bits() != bits {
+                log::warn!(
+                    "ignoring CPU feature bits {:#x}: not defined for this architecture",
+                    bits & !features.bits()
+                );
+            }
+            Ok(features)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json", target_arch = "x86_64"))]
+mod serde_tests {
+    use super::CpuFeatures;
+
+    #[test]
+    fn serializes_as_name_list_and_round_trips() {
+        let features = CpuFeatures::SSE2 | CpuFeatures::AVX2 | CpuFeatures::SSE4_1;
+
+        let json = serde_json::to_string(&features).unwrap();
+        assert_eq!(json, r#"["SSE2","SSE4_1","AVX2"]"#);
+
+        let back: CpuFeatures = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, features);
+
+        let empty: CpuFeatures = serde_json::from_str("[]").unwrap();
+        assert_eq!(empty, CpuFeatures::empty());
+    }
+
+    #[test]
+    fn foreign_names_are_dropped() {
+        // NEON is aarch64-only; LASX is nobody's flag in this crate.
+        let back: CpuFeatures = serde_json::from_str(r#"["AVX", "NEON", "LASX"]"#).unwrap();
+        assert_eq!(back, CpuFeatures::AVX);
+    }
+
+    #[test]
+    fn legacy_shapes_still_load() {
+        let text: CpuFeatures = serde_json::from_str(r#""SSE2 | AVX2""#).unwrap();
+        assert_eq!(text, CpuFeatures::SSE2 | CpuFeatures::AVX2);
+
+        let empty: CpuFeatures = serde_json::from_str(r#""""#).unwrap();
+        assert_eq!(empty, CpuFeatures::empty());
+
+        // Bit 31 is undefined on x86_64 and is truncated.
+        let bits: CpuFeatures = serde_json::from_str(&(0x8000_0204u32).to_string()).unwrap();
+        assert_eq!(bits, CpuFeatures::SSE2 | CpuFeatures::AVX2);
+    }
+}
+
+#[cfg(all(test, feature = "serde", target_arch = "x86_64"))]
+mod binary_serde_tests {
+    use super::CpuFeatures;
+
+    // Non-self-describing formats reject `deserialize_any`: they must see
+    // the raw bits both ways.
+    #[test]
+    fn binary_formats_round_trip_raw_bits() {
+        let features = CpuFeatures::SSE2 | CpuFeatures::AVX2 | CpuFeatures::SSE4_1;
+
+        let bytes = postcard::to_allocvec(&features).unwrap();
+        assert_eq!(bytes, postcard::to_allocvec(&features.bits()).unwrap());
+        let back: CpuFeatures = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(back, features);
+
+        // Bit 31 is undefined on x86_64 and is truncated.
+        let bytes = postcard::to_allocvec(&0x8000_0204u32).unwrap();
+        let back: CpuFeatures = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(back, CpuFeatures::SSE2 | CpuFeatures::AVX2);
+    }
+}
+
 // Bit-position stability: each flag's bit is a serialization format. Pinning the
 // exact bit per flag fails CI if anyone inserts a flag mid-list or renumbers,
 // which would break deserialization of a stored/transmitted feature set.
@@ -182,6 +440,8 @@ mod conformance_x86 {
             (CpuFeatures::BMI1, 19),
             (CpuFeatures::BMI2, 20),
             (CpuFeatures::F16C, 21),
+            (CpuFeatures::RTM, 22),
+            (CpuFeatures::HLE, 23),
         ];
         for &(flag, bit) in pins {
             assert_eq!(
@@ -237,3 +497,31 @@ mod conformance_arm {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CpuFeatures;
+
+    #[test]
+    fn all_lists_every_defined_flag_once() {
+        assert_eq!(
+            CpuFeatures::ALL.len(),
+            CpuFeatures::all().bits().count_ones() as usize
+        );
+
+        let mut seen = CpuFeatures::empty();
+        for (i, &(flag, name)) in CpuFeatures::ALL.iter().enumerate() {
+            assert_eq!(flag.bits().count_ones(), 1, "{name} is not a single bit");
+            assert!(!seen.contains(flag), "{name} listed twice");
+            assert!(
+                CpuFeatures::ALL[..i]
+                    .iter()
+                    .all(|&(_, other)| other != name),
+                "name {name} listed twice"
+            );
+            assert_eq!(CpuFeatures::from_name(name), Some(flag));
+            seen |= flag;
+        }
+        assert_eq!(seen, CpuFeatures::all());
+    }
+}
@@ -1,5 +1,8 @@
+use std::sync::OnceLock;
+
 use crate::{
-    AffinityMask, CacheInfo, CoreKind, CpuFeatures, L2Domain, L3Domain, Lp, Result, Vendor,
+    AffinityMask, CacheInfo, CacheLevel, CacheOwner, CacheScope, CacheSet, CoreKind, CpuFeatures,
+    DetectOptions, DetectionPhase, L2Domain, L3Domain, Lp, MemoryTier, Result, Vendor,
 };
 
 /// The system's CPU topology and identity - a flat, by-value description.
@@ -11,19 +14,41 @@ use crate::{
 /// Socket membership lives on each `Lp`; socket totals are derived counts.
 ///
 /// Obtain it with [`CpuInfo::detect()`] and store it wherever you want - the
-/// struct owns all its data and there is no global state in the library.
+/// struct owns all its data and detection keeps no global state.
+///
+/// # Serialized form
+///
+/// With the `serde` feature the struct round-trips through any serde format,
+/// and blobs written by older releases keep loading: every field added after
+/// schema 1 (the 0.2606.1 shape) carries `#[serde(default)]`, and
+/// [`schema_version`](Self::schema_version) records which shape a blob was
+/// written with. Prefer `CpuInfo::from_json` (`json` feature) for JSON - it
+/// also back-fills defaults that can be derived from older data.
 #[must_use]
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuInfo {
-    /// One record per online logical processor.
-    pub lps: Vec<Lp>,
+    /// Serialized-shape version this value was produced with
+    /// ([`CpuInfo::SCHEMA_VERSION`] for freshly detected values; `1` for blobs
+    /// that predate the field).
+    #[cfg_attr(feature = "serde", serde(default = "schema_v1"))]
+    pub schema_version: u32,
+    /// One record per online logical processor, sorted by ascending
+    /// [`Lp::os_id`] on every platform. Read through [`CpuInfo::lps`].
+    pub(crate) lps: Vec<Lp>,
     /// Physical core count (SMT siblings counted once).
     pub core_count: u16,
     /// Socket count (derived; sockets are not containers in this model).
     pub socket_count: u8,
     /// NUMA node count (1 on single-node systems and macOS).
     pub numa_node_count: u8,
+    /// `true` when the hardware supports SMT (Hyper-Threading), whether or not
+    /// it is currently enabled - see [`CpuInfo::is_smt_enabled`] for the live
+    /// state. Only Linux can tell "SMT disabled" apart from "no SMT hardware"
+    /// (via `devices/system/cpu/smt/control`); elsewhere this equals
+    /// `is_smt_enabled()`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub smt_supported: bool,
     /// Physical cores per [`CoreKind`], indexed by [`CoreKind::index()`].
     pub kind_core_counts: [u16; CoreKind::COUNT],
 
@@ -49,32 +74,196 @@ pub struct CpuInfo {
     pub model_name: String,
     /// Runtime-detected ISA feature flags.
     pub features: CpuFeatures,
+    /// Base (nominal, non-boost) clock in MHz, or `None` when the platform
+    /// does not report one (Apple Silicon, most ARM boards, many VMs).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub base_frequency_mhz: Option<u32>,
+    /// Maximum (boost) clock in MHz, or `None` when the platform does not
+    /// report one. On hybrid parts this is the fastest core's ceiling.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_frequency_mhz: Option<u32>,
+    /// x86 `CLFLUSH` line size in bytes (cpuid leaf 1, EBX bits 8-15) - the
+    /// granularity of `clflush`/`clflushopt` and of software prefetch. Usually
+    /// equal to the data-cache [`CacheInfo::line_bytes`] but reported
+    /// separately by the hardware. `None` on non-x86_64 targets.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub clflush_line_size: Option<u16>,
+    /// `true` when the x86 TSC is invariant - it ticks at a constant rate
+    /// through P-, C- and T-state changes, so `rdtsc` deltas measure wall
+    /// time (cpuid leaf 0x8000_0007 EDX bit 8). `false` on other
+    /// architectures; the aarch64 generic timer is constant-rate by
+    /// definition, see [`tsc_frequency_hz`](Self::tsc_frequency_hz).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub has_invariant_tsc: bool,
+    /// Tick rate of the CPU's free-running counter in Hz: on x86_64 the TSC
+    /// from cpuid leaf 0x15 (crystal clock x ratio; `None` where the leaf is
+    /// absent or leaves the crystal unreported, as on AMD and pre-Ice-Lake
+    /// client Intel), on aarch64 the generic timer (`CNTFRQ_EL0`, the rate of
+    /// `CNTVCT_EL0`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tsc_frequency_hz: Option<u64>,
+    /// Loaded microcode revision as the OS prints it (`"0xf4"`), for pinning
+    /// down microcode-dependent performance changes. Linux: sysfs
+    /// `microcode/version`, else the `/proc/cpuinfo` `microcode` field;
+    /// Windows: the registry `Update Revision`. `None` where the platform
+    /// does not report one (macOS, ARM, most VMs).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub microcode: Option<String>,
+    /// Package power limit in watts: the sustained (PL1) budget firmware
+    /// programs from the SKU's TDP, for power-aware tuning heuristics. Linux:
+    /// RAPL powercap `intel-rapl:0` long-term constraint (package 0; Intel and
+    /// AMD Zen). `None` on Windows and macOS (no documented user-mode source),
+    /// on ARM, in most VMs, and wherever RAPL is not exposed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tdp_watts: Option<u32>,
+    /// x86 display `(family, model)` from cpuid leaf 1, extended fields folded
+    /// in (`(6, 0x97)` for Alder Lake-S, `(0x19, 0x61)` for Raphael) - the
+    /// key of the family/model heuristics such as
+    /// [`microarchitecture`](Self::microarchitecture). `None` on non-x86
+    /// targets.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub x86_signature: Option<(u8, u8)>,
+    /// Whether sustained AVX-512 code is likely to lower the core clock
+    /// (Intel's AVX-512 "license" levels) - a HEURISTIC from the x86
+    /// family/model, for kernels choosing between 512- and 256-bit paths.
+    /// `Some(true)` on Skylake-SP/X, Cascade Lake, Cooper Lake and Cannon
+    /// Lake; `Some(false)` on Ice Lake and later Intel (including client
+    /// parts without AVX-512, such as Alder Lake) and on AMD Zen 4+; `None`
+    /// for anything else, on non-x86 targets, or where the model is unknown.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub avx512_downclock_risk: Option<bool>,
+    /// What detection could not read and had to assume or leave empty, one
+    /// short note per gap (`"cache info unavailable"`, ...). Empty on a fully
+    /// read machine - see [`CpuInfo::has_complete_topology`]. Meant for logs
+    /// and bug reports; the wording is not a stable API.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub detection_warnings: Vec<String>,
+
+    /// `os_id` of every entry in `lps`, flattened on first use so
+    /// [`logical_processor_ids`](Self::logical_processor_ids) can hand out a
+    /// slice instead of allocating on every call. `lps` is only writable
+    /// inside the crate, and `finalize` resets this after reordering it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) logical_processor_ids: OnceLock<Vec<usize>>,
+}
+
+#[cfg(feature = "serde")]
+fn schema_v1() -> u32 {
+    1
 }
 
 impl CpuInfo {
+    /// The serialized-shape version this build writes. Bumped whenever a field
+    /// is added to `CpuInfo` or one of its sub-structs, or a field changes
+    /// representation (schema 3: [`CpuFeatures`] as a list of names).
+    pub const SCHEMA_VERSION: u32 = 13;
+
+    /// Environment variable naming a JSON file (as written by
+    /// [`CpuInfo::to_json`]) that [`detect`](Self::detect) loads instead of
+    /// detecting - feature `json`.
+    ///
+    /// FOR TESTING ONLY: it replays a user-reported topology on a QA machine.
+    /// Only the returned value changes - thread affinity and priority calls
+    /// still act on the real CPUs, so pinning to an LP the file invents fails.
+    #[cfg(feature = "json")]
+    pub const TOPOLOGY_OVERRIDE_ENV: &'static str = "GDT_CPUS_TOPOLOGY_JSON";
+
     /// Detects the CPU topology using platform-specific methods.
     ///
     /// This reads OS interfaces only (sysfs, sysctl, Win32) - no global state
     /// is created and repeated calls are independent. Detect once at startup
     /// and keep the value.
+    ///
+    /// With feature `json`, a set `GDT_CPUS_TOPOLOGY_JSON`
+    /// (`CpuInfo::TOPOLOGY_OVERRIDE_ENV`, for testing only) replaces detection
+    /// with the topology in that file; an unreadable or invalid file is an
+    /// [`Error::InvalidParameter`](crate::Error::InvalidParameter).
     #[must_use = "detecting topology has a cost; keep and reuse the returned CpuInfo"]
     pub fn detect() -> Result<Self> {
+        Self::detect_with(DetectOptions::FULL)
+    }
+
+    /// [`detect`](Self::detect) running only the optional steps `options`
+    /// enables - for launchers that need the topology fast and can do
+    /// without caches or clocks. See [`DetectOptions`] for what each
+    /// skipped step leaves empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::{CpuInfo, DetectOptions};
+    ///
+    /// if let Ok(info) = CpuInfo::detect_with(DetectOptions::TOPOLOGY_ONLY) {
+    ///     assert!(info.l3_domains.is_empty());
+    ///     println!("{} cores", info.core_count);
+    /// }
+    /// ```
+    #[must_use = "detecting topology has a cost; keep and reuse the returned CpuInfo"]
+    pub fn detect_with(options: DetectOptions) -> Result<Self> {
+        Self::detect_with_progress(options, |_, _| {})
+    }
+
+    /// [`detect_with`](Self::detect_with), calling `progress(phase, fraction)`
+    /// as detection advances - for a progress indicator where detection is
+    /// slow (hundreds of LPs on a Linux server).
+    ///
+    /// Every phase the backend runs is reported once at `1.0` when it
+    /// completes, in the backend's order (see [`DetectionPhase`]); the Linux
+    /// cache walk also reports its fraction of LPs read as it goes. A
+    /// topology loaded from `GDT_CPUS_TOPOLOGY_JSON` reports nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::{CpuInfo, DetectOptions};
+    ///
+    /// let info = CpuInfo::detect_with_progress(DetectOptions::FULL, |phase, fraction| {
+    ///     eprintln!("{phase}: {:.0}%", fraction * 100.0);
+    /// });
+    /// ```
+    #[must_use = "detecting topology has a cost; keep and reuse the returned CpuInfo"]
+    pub fn detect_with_progress(
+        options: DetectOptions,
+        mut progress: impl FnMut(DetectionPhase, f32),
+    ) -> Result<Self> {
+        #[cfg(feature = "json")]
+        if let Some(loaded) = Self::topology_override(std::env::var_os(Self::TOPOLOGY_OVERRIDE_ENV))
+        {
+            return loaded.map(|info| info.without_skipped(&options));
+        }
+
         #[cfg(target_os = "linux")]
         {
-            crate::platform::linux::cpu::detect_cpu_info()
+            crate::platform::linux::cpu::detect_cpu_info(&options, &mut progress)
         }
         #[cfg(target_os = "macos")]
         {
-            crate::platform::macos::cpu::detect_cpu_info()
+            crate::platform::macos::cpu::detect_cpu_info(&mut progress)
+                .map(|info| info.without_skipped(&options))
         }
         #[cfg(target_os = "windows")]
         {
-            crate::platform::windows::cpu::detect_cpu_info()
+            crate::platform::windows::cpu::detect_cpu_info(&mut progress)
+                .map(|info| info.without_skipped(&options))
         }
-        #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+        #[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
         {
+            // Counts-only: one step.
+            let info = crate::platform::bsd::detect_cpu_info()?;
+            progress(DetectionPhase::Topology, 1.0);
+            Ok(info.without_skipped(&options))
+        }
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "openbsd",
+            target_os = "netbsd"
+        )))]
+        {
+            let _ = (options, &mut progress);
             Err(crate::Error::Unsupported(
-                "CPU information detection is not supported on this platform.".to_string(),
+                "CPU information detection is not supported on this platform.".into(),
             ))
         }
     }
@@ -108,6 +297,41 @@ impl CpuInfo {
         self.kind_core_counts[CoreKind::LpEfficiency.index()] as usize
     }
 
+    /// Worker threads to spawn for latency-sensitive job work: one per
+    /// Performance core, minus `reserve_for_main_and_render` cores kept free
+    /// for the threads that already own one (main, render, audio), never
+    /// below 1.
+    ///
+    /// The heuristic behind it: one heavy thread per PHYSICAL core (an SMT
+    /// sibling shares its core's execution units, so it adds little to a busy
+    /// worker), and only Performance cores - a frame-bound job system waits on
+    /// its slowest worker, and an E-core worker is that worker. Pin the pool
+    /// with [`primary_thread_mask`](Self::primary_thread_mask) intersected
+    /// with [`performance_core_mask`](Self::performance_core_mask). On a
+    /// homogeneous machine every core is Performance.
+    ///
+    /// Counts come from the detected topology. Under `taskset`, cpusets or
+    /// container CPU limits the process may run on fewer cores - size the
+    /// pool from the mask it is actually allowed (`current_affinity()`).
+    pub fn recommended_worker_count(&self, reserve_for_main_and_render: usize) -> usize {
+        self.num_performance_cores()
+            .saturating_sub(reserve_for_main_and_render)
+            .max(1)
+    }
+
+    /// Like [`recommended_worker_count`](Self::recommended_worker_count), but
+    /// for THROUGHPUT work (shader compilation, asset cooking, bakes) that has
+    /// no per-frame deadline and so also gains from Efficiency cores: one per
+    /// Performance and Efficiency core, minus the reserve, never below 1.
+    ///
+    /// [`CoreKind::LpEfficiency`] cores are left out - they sit on a weak
+    /// interconnect and are meant for trickle work.
+    pub fn recommended_throughput_worker_count(&self, reserve_for_main_and_render: usize) -> usize {
+        (self.num_performance_cores() + self.num_efficiency_cores())
+            .saturating_sub(reserve_for_main_and_render)
+            .max(1)
+    }
+
     /// `true` when more than one of the {Performance, Efficiency, LpEfficiency}
     /// kinds is present.
     pub fn is_hybrid(&self) -> bool {
@@ -122,9 +346,712 @@ impl CpuInfo {
         kinds_present > 1
     }
 
-    /// All OS logical-processor ids, in detection order.
-    pub fn logical_processor_ids(&self) -> Vec<usize> {
-        self.lps.iter().map(|lp| lp.os_id as usize).collect()
+    /// `true` when SMT is active: at least one physical core runs more than one
+    /// logical processor. On hybrid parts this is a per-core fact (E-cores
+    /// never have siblings), which a `logical / physical` ratio gets wrong.
+    pub fn is_smt_enabled(&self) -> bool {
+        self.lps.iter().any(|lp| lp.smt_index > 0)
+    }
+
+    /// The most logical processors any one core runs - 2 on an SMT-2 part,
+    /// including hybrid parts whose E-cores have no siblings (see
+    /// [`smt_width_for_kind`](Self::smt_width_for_kind) for per-kind sizing).
+    /// 1 with SMT off.
+    pub fn smt_width(&self) -> usize {
+        self.threads_per_core().into_iter().max().unwrap_or(1)
+    }
+
+    /// `true` when physical core `core` (an [`Lp::core`] index) runs more than
+    /// one online logical processor. `false` for unknown indices.
+    ///
+    /// ```
+    /// # #[cfg(feature = "test-util")] {
+    /// use gdt_cpus::{CoreKind, CpuInfoBuilder};
+    ///
+    /// let info = CpuInfoBuilder::new()
+    ///     .core(CoreKind::Performance, 2)
+    ///     .core(CoreKind::Efficiency, 1)
+    ///     .build();
+    /// assert!(info.is_smt_core(0));
+    /// assert!(!info.is_smt_core(1));
+    /// assert!(!info.is_smt_core(2));
+    /// # }
+    /// ```
+    pub fn is_smt_core(&self, core: u16) -> bool {
+        self.lps
+            .iter()
+            .filter(|lp| lp.core == core)
+            .nth(1)
+            .is_some()
+    }
+
+    /// The typical (most common) number of logical processors per core of
+    /// `kind` - 2 for Alder Lake P-cores, 1 for its E-cores. 0 when no core
+    /// is of `kind`.
+    ///
+    /// "Typical" rather than maximum so one core with a sibling offlined
+    /// does not change the answer for the rest.
+    pub fn smt_width_for_kind(&self, kind: CoreKind) -> usize {
+        let per_core = self.threads_per_core();
+        let widths: Vec<usize> = self
+            .lps
+            .iter()
+            .filter(|lp| lp.kind == kind && lp.smt_index == 0)
+            .filter_map(|lp| per_core.get(lp.core as usize).copied())
+            .collect();
+
+        // Ties go to the narrower width.
+        widths
+            .iter()
+            .copied()
+            .max_by_key(|&w| {
+                let cores = widths.iter().filter(|&&x| x == w).count();
+                (cores, std::cmp::Reverse(w))
+            })
+            .unwrap_or(0)
+    }
+
+    /// LP count per dense core index.
+    fn threads_per_core(&self) -> Vec<usize> {
+        let mut counts = vec![0usize; self.core_count as usize];
+        for lp in &self.lps {
+            if let Some(count) = counts.get_mut(lp.core as usize) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+
+    /// The x86-64 psABI microarchitecture level (1-4) the
+    /// [`features`](Self::features) satisfy - what `glibc-hwcaps` and
+    /// `-march=x86-64-vN` builds select by. `None` on other architectures.
+    ///
+    /// Checked from the flags this crate tracks: v2 = SSE3, SSSE3, SSE4.1,
+    /// SSE4.2, POPCNT; v3 adds AVX, AVX2, BMI1, BMI2, FMA3, F16C; v4 adds
+    /// AVX-512 F/BW/CD/DQ/VL. The untracked requirements (CMPXCHG16B,
+    /// LAHF/SAHF, MOVBE, LZCNT, XSAVE) come with every CPU that has the
+    /// tracked ones. Every x86-64 CPU is at least v1.
+    pub fn x86_64_level(&self) -> Option<u8> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            use CpuFeatures as F;
+
+            const V2: F = F::SSE3
+                .union(F::SSSE3)
+                .union(F::SSE4_1)
+                .union(F::SSE4_2)
+                .union(F::POPCNT);
+            const V3: F = V2
+                .union(F::AVX)
+                .union(F::AVX2)
+                .union(F::BMI1)
+                .union(F::BMI2)
+                .union(F::FMA3)
+                .union(F::F16C);
+            const V4: F = V3
+                .union(F::AVX512F)
+                .union(F::AVX512BW)
+                .union(F::AVX512CD)
+                .union(F::AVX512DQ)
+                .union(F::AVX512VL);
+
+            let above_v1 = [V2, V3, V4]
+                .iter()
+                .take_while(|level| self.features.contains(**level))
+                .count();
+
+            Some(1 + above_v1 as u8)
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        {
+            None
+        }
+    }
+
+    /// Sum of every physical core's [`Lp::capacity`] - the machine's compute
+    /// budget in units of its fastest core × 1024. An 8P + 8E part whose E
+    /// cores sit at 512 totals `8 * 1024 + 8 * 512`.
+    ///
+    /// SMT siblings are not counted twice (they share their core's
+    /// execution resources). `None` unless every core reports a capacity - a
+    /// partial sum would understate the machine.
+    pub fn total_capacity(&self) -> Option<u64> {
+        self.lps
+            .iter()
+            .filter(|lp| lp.smt_index == 0)
+            .map(|lp| lp.capacity.map(u64::from))
+            .sum()
+    }
+
+    /// Splits `total_work_units` across every physical core in proportion to
+    /// its [`Lp::capacity`] - a throughput-oriented parallel-for hands each
+    /// worker its share up front, so the P-cores are not left waiting on the
+    /// E-cores' equal slices.
+    ///
+    /// One `(lp_id, units)` entry per core, keyed by the core's first LP in
+    /// [`lps`](Self::lps()) order; the units always sum to `total_work_units`
+    /// (largest-remainder rounding, ties to the lower LP). A core without a
+    /// capacity is weighed by kind: Performance 1024, Efficiency 512,
+    /// LP-Efficiency 256 (the rough single-thread ratios on current hybrids).
+    pub fn weighted_core_assignments(&self, total_work_units: usize) -> Vec<(usize, usize)> {
+        let cores: Vec<(usize, u128)> = self
+            .lps
+            .iter()
+            .filter(|lp| lp.smt_index == 0)
+            .map(|lp| {
+                let weight = lp.capacity.unwrap_or(match lp.kind {
+                    CoreKind::Efficiency => Lp::MAX_CAPACITY / 2,
+                    CoreKind::LpEfficiency => Lp::MAX_CAPACITY / 4,
+                    _ => Lp::MAX_CAPACITY,
+                });
+                (lp.os_id as usize, u128::from(weight.max(1)))
+            })
+            .collect();
+        let total_weight: u128 = cores.iter().map(|&(_, weight)| weight).sum();
+        let total = total_work_units as u128;
+
+        let mut assignments: Vec<(usize, usize)> = Vec::with_capacity(cores.len());
+        let mut remainders: Vec<(u128, usize)> = Vec::with_capacity(cores.len());
+        let mut assigned = 0;
+        for (i, &(lp_id, weight)) in cores.iter().enumerate() {
+            let share = total * weight;
+            let units = (share / total_weight) as usize;
+            assignments.push((lp_id, units));
+            remainders.push((share % total_weight, i));
+            assigned += units;
+        }
+
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for &(_, i) in remainders.iter().take(total_work_units - assigned) {
+            assignments[i].1 += 1;
+        }
+        assignments
+    }
+
+    /// Total L2 capacity of the machine in bytes, each physical L2 instance
+    /// counted once.
+    ///
+    /// Summing [`l2`](Self::l2) per core overcounts wherever L2 is shared -
+    /// Apple Silicon and Intel E-core clusters give four cores ONE L2. This
+    /// sums the [`l2_domains`](Self::l2_domains) instead; without domains it
+    /// falls back to the per-kind entries, one instance per
+    /// [`CacheInfo::shared_by`] LPs.
+    pub fn total_l2_bytes(&self) -> u64 {
+        if !self.l2_domains.is_empty() {
+            return self.l2_domains.iter().map(|d| d.size_bytes).sum();
+        }
+
+        self.l2
+            .iter()
+            .enumerate()
+            .map(|(k, cache)| {
+                let lps = self.lps.iter().filter(|lp| lp.kind.index() == k).count() as u64;
+
+                cache.size_bytes * lps.div_ceil(u64::from(cache.shared_by.max(1)))
+            })
+            .sum()
+    }
+
+    /// A coarse [`MemoryTier`] for scaling asset budgets - a heuristic from
+    /// the topology, with these thresholds:
+    ///
+    /// * **High** - more than one socket; a server / workstation line
+    ///   (`EPYC`, `Xeon`, `Threadripper` in the model name); Apple `Max` /
+    ///   `Ultra` (wide unified memory); or 24+ physical cores.
+    /// * **Low** - at most 4 physical cores with under 8 MiB of L3 in total
+    ///   (no L3 counts as none): dual/quad-core laptops, handhelds, ARM boards.
+    /// * **Medium** - everything else.
+    ///
+    /// Cache size and core count stand in for memory channels, which no OS
+    /// reports to user space. Treat the result as a default the user can
+    /// override, not a fact about the machine.
+    pub fn memory_tier(&self) -> MemoryTier {
+        const SERVER_LINES: [&str; 3] = ["epyc", "xeon", "threadripper"];
+
+        let model = self.model_name.to_ascii_lowercase();
+        let wide_apple = self.vendor == Vendor::Apple
+            && model
+                .split_whitespace()
+                .any(|word| word == "max" || word == "ultra");
+        let cores = self.num_physical_cores();
+        let l3_bytes: u64 = self.l3_domains.iter().map(|d| d.size_bytes).sum();
+
+        if self.socket_count > 1
+            || SERVER_LINES.iter().any(|line| model.contains(line))
+            || wide_apple
+            || cores >= 24
+        {
+            MemoryTier::High
+        } else if cores <= 4 && l3_bytes < 8 << 20 {
+            MemoryTier::Low
+        } else {
+            MemoryTier::Medium
+        }
+    }
+
+    /// Best-effort name of the core microarchitecture (`"Zen 4"`,
+    /// `"Raptor Cove/Gracemont"`, `"Firestorm/Icestorm"`), for display and
+    /// coarse heuristics. Hybrid parts name the performance design first.
+    ///
+    /// x86 parts are looked up by [`x86_signature`](Self::x86_signature),
+    /// Apple Silicon by the chip generation in [`model_name`](Self::model_name).
+    /// `None` for signatures missing from the table - new parts until the
+    /// table learns them, most ARM boards - so never gate correctness on it;
+    /// test [`features`](Self::features) instead.
+    pub fn microarchitecture(&self) -> Option<&'static str> {
+        match self.x86_signature {
+            Some((family, model)) => {
+                super::microarch::x86_microarchitecture(self.vendor, family, model)
+            }
+            None if self.vendor == Vendor::Apple => {
+                super::microarch::apple_microarchitecture(&self.model_name)
+            }
+            None => None,
+        }
+    }
+
+    /// Every cache instance on the machine with its owner, as one flat list:
+    /// the L1d and L1i of each core, then each [`l2_domains`](Self::l2_domains)
+    /// and [`l3_domains`](Self::l3_domains) entry - the input a cache-topology
+    /// view wants.
+    ///
+    /// L1 entries come from the per-kind [`l1d`](Self::l1d) / [`l1i`](Self::l1i)
+    /// (kinds with an undetected L1 are left out); L2 and L3 entries from the
+    /// domain tables, so a value without domains lists no L2/L3. The model
+    /// keeps no separate L3 line size: L3 entries carry their cores' L2 line
+    /// size, which is the same on all shipping parts.
+    pub fn caches(&self) -> Vec<CacheScope> {
+        let mut cores: Vec<&Lp> = Vec::new();
+        for lp in &self.lps {
+            if !cores.iter().any(|c| c.core == lp.core) {
+                cores.push(lp);
+            }
+        }
+        cores.sort_by_key(|lp| lp.core);
+
+        let mut scopes = Vec::new();
+        for (level, per_kind) in [(CacheLevel::L1d, &self.l1d), (CacheLevel::L1i, &self.l1i)] {
+            for lp in &cores {
+                let info = per_kind[lp.kind.index()];
+                if info.size_bytes > 0 {
+                    scopes.push(CacheScope {
+                        info,
+                        level,
+                        owner: CacheOwner::Core(lp.core as usize),
+                    });
+                }
+            }
+        }
+
+        let shared = self
+            .l2_domains
+            .iter()
+            .map(|d| (CacheLevel::L2, d.size_bytes, &d.mask))
+            .chain(
+                self.l3_domains
+                    .iter()
+                    .map(|d| (CacheLevel::L3, d.size_bytes, &d.mask)),
+            );
+        scopes
+            .extend(shared.filter_map(|(level, size_bytes, mask)| {
+                self.shared_cache(level, size_bytes, mask)
+            }));
+
+        scopes
+    }
+
+    /// The caches on socket `socket` shared above the per-core level: cluster
+    /// L2s (Apple Silicon, Intel E-core clusters) and L3s, each with its
+    /// owner, in [`caches`](Self::caches) order.
+    ///
+    /// Core-private L2s (shared only by SMT siblings) are left out. The model
+    /// has no level above L3; Apple's system-level cache appears here only
+    /// when the OS reports it as an L3.
+    pub fn socket_shared_caches(&self, socket: u8) -> Vec<CacheScope> {
+        let on_socket = |core: &usize| {
+            self.lps
+                .iter()
+                .any(|lp| lp.core as usize == *core && lp.socket == socket)
+        };
+
+        self.caches()
+            .into_iter()
+            .filter(|scope| match &scope.owner {
+                CacheOwner::Core(_) => false,
+                CacheOwner::Socket(s) => *s == socket as usize,
+                CacheOwner::CoreGroup(cores) => cores.iter().any(on_socket),
+            })
+            .collect()
+    }
+
+    /// The L1i, L1d, L2 and L3 instances logical processor `lp_id` (OS id)
+    /// uses, each with its owner - its core's private caches and the
+    /// cluster / socket / CCD caches above them, in one lookup. `None` if
+    /// `lp_id` is not an online LP.
+    ///
+    /// Entries are built like [`caches`](Self::caches) builds them, except
+    /// that the L2 keeps the per-kind write policy and inclusiveness.
+    pub fn caches_for_logical_processor(&self, lp_id: usize) -> Option<CacheSet> {
+        let lp = self.lps.iter().find(|lp| lp.os_id as usize == lp_id)?;
+        let kind = lp.kind.index();
+
+        let private = |level, info: CacheInfo| {
+            (info.size_bytes > 0).then_some(CacheScope {
+                info,
+                level,
+                owner: CacheOwner::Core(lp.core as usize),
+            })
+        };
+
+        let l2 = match self.l2_domains.get(usize::from(lp.l2_domain)) {
+            Some(domain) => self
+                .shared_cache(CacheLevel::L2, domain.size_bytes, &domain.mask)
+                .map(|mut scope| {
+                    scope.info.write_policy = self.l2[kind].write_policy;
+                    scope.info.inclusive = self.l2[kind].inclusive;
+                    scope
+                }),
+            None => private(CacheLevel::L2, self.l2[kind]),
+        };
+        let l3 = self
+            .l3_domains
+            .get(usize::from(lp.l3_domain))
+            .and_then(|domain| self.shared_cache(CacheLevel::L3, domain.size_bytes, &domain.mask));
+
+        Some(CacheSet {
+            l1i: private(CacheLevel::L1i, self.l1i[kind]),
+            l1d: private(CacheLevel::L1d, self.l1d[kind]),
+            l2,
+            l3,
+        })
+    }
+
+    /// The [`CacheScope`] of a domain-table cache instance shared by `mask`,
+    /// or `None` when no detected LP is in it.
+    fn shared_cache(
+        &self,
+        level: CacheLevel,
+        size_bytes: u64,
+        mask: &AffinityMask,
+    ) -> Option<CacheScope> {
+        let members: Vec<&Lp> = self
+            .lps
+            .iter()
+            .filter(|lp| mask.contains(lp.os_id as usize))
+            .collect();
+        let first = members.first()?;
+
+        let mut core_ids: Vec<usize> = members.iter().map(|lp| lp.core as usize).collect();
+        core_ids.sort_unstable();
+        core_ids.dedup();
+
+        let whole_socket = members.iter().all(|lp| lp.socket == first.socket)
+            && self
+                .lps
+                .iter()
+                .filter(|lp| lp.socket == first.socket)
+                .count()
+                == members.len();
+
+        let owner = match core_ids.as_slice() {
+            [core] => CacheOwner::Core(*core),
+            _ if whole_socket => CacheOwner::Socket(first.socket as usize),
+            _ => CacheOwner::CoreGroup(core_ids),
+        };
+
+        Some(CacheScope {
+            info: CacheInfo {
+                size_bytes,
+                line_bytes: self.l2[first.kind.index()].line_bytes,
+                shared_by: members.len() as u16,
+                write_policy: None,
+                inclusive: None,
+                estimated_latency_cycles: match level {
+                    CacheLevel::L3 => self.microarchitecture().and_then(|arch| {
+                        super::microarch::cache_latency_cycles(arch, first.kind, level)
+                    }),
+                    _ => self.l2[first.kind.index()].estimated_latency_cycles,
+                },
+            },
+            level,
+            owner,
+        })
+    }
+
+    /// `true` when detection read every topology and cache source it looks
+    /// for, i.e. [`detection_warnings`](Self::detection_warnings) is empty.
+    ///
+    /// `false` does not mean the value is unusable - LP ids and counts are
+    /// still exact - only that some of it (kinds, cache sizes, SMT layout) is
+    /// an assumption. Containers and sandboxes that hide sysfs or GLPI are the
+    /// usual cause.
+    pub fn has_complete_topology(&self) -> bool {
+        self.detection_warnings.is_empty()
+    }
+
+    /// Checks the structural invariants every detection backend promises,
+    /// for startup assertions and for values rebuilt from JSON or by hand:
+    ///
+    /// - at least one LP, and no OS LP id listed twice;
+    /// - [`core_count`](Self::core_count) equals the number of distinct
+    ///   [`Lp::core`] indices, which are dense (`0..core_count`), each with
+    ///   exactly one `smt_index == 0` primary;
+    /// - [`kind_core_counts`](Self::kind_core_counts) partitions the cores
+    ///   (so Performance + Efficiency never exceeds the physical count);
+    /// - socket indices are contiguous, `0..socket_count`;
+    /// - every [`Lp::l3_domain`] / [`Lp::l2_domain`] names an existing domain.
+    ///
+    /// Unlike [`detection_warnings`](Self::detection_warnings), which record
+    /// what detection had to guess, a failure here means the value itself is
+    /// inconsistent - a detection bug or a corrupted blob.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::Error::Detection`] describing the first violated invariant.
+    pub fn validate(&self) -> Result<()> {
+        let fail = |msg: String| Err(crate::Error::Detection(msg.into()));
+
+        if self.lps.is_empty() {
+            return fail("no logical processors".to_string());
+        }
+
+        let mut seen = AffinityMask::empty();
+        for lp in &self.lps {
+            if seen.contains(lp.os_id as usize) {
+                return fail(format!("logical processor {} listed twice", lp.os_id));
+            }
+            seen.add(lp.os_id as usize);
+        }
+
+        if let Some(pair) = self
+            .lps
+            .windows(2)
+            .find(|pair| pair[0].os_id > pair[1].os_id)
+        {
+            return fail(format!(
+                "logical processor {} listed after {}",
+                pair[1].os_id, pair[0].os_id
+            ));
+        }
+
+        let mut primaries = vec![0u16; self.core_count as usize];
+        for lp in &self.lps {
+            let Some(count) = primaries.get_mut(lp.core as usize) else {
+                return fail(format!(
+                    "LP {} has core index {} but core_count is {}",
+                    lp.os_id, lp.core, self.core_count
+                ));
+            };
+            if lp.smt_index == 0 {
+                *count += 1;
+            }
+        }
+        if let Some(core) = primaries.iter().position(|&n| n != 1) {
+            return fail(format!(
+                "core {} has {} smt_index 0 LPs (expected exactly 1)",
+                core, primaries[core]
+            ));
+        }
+
+        let kind_sum: u16 = self.kind_core_counts.iter().sum();
+        if kind_sum != self.core_count {
+            return fail(format!(
+                "kind_core_counts sum to {} but core_count is {}",
+                kind_sum, self.core_count
+            ));
+        }
+
+        for socket in 0..self.socket_count {
+            if !self.lps.iter().any(|lp| lp.socket == socket) {
+                return fail(format!(
+                    "socket {} of {} has no LPs (socket ids not contiguous)",
+                    socket, self.socket_count
+                ));
+            }
+        }
+        if let Some(lp) = self.lps.iter().find(|lp| lp.socket >= self.socket_count) {
+            return fail(format!(
+                "LP {} has socket {} but socket_count is {}",
+                lp.os_id, lp.socket, self.socket_count
+            ));
+        }
+
+        for lp in &self.lps {
+            if lp.l3_domain != Lp::NO_L3 && lp.l3_domain as usize >= self.l3_domains.len() {
+                return fail(format!(
+                    "LP {} names L3 domain {} of {}",
+                    lp.os_id,
+                    lp.l3_domain,
+                    self.l3_domains.len()
+                ));
+            }
+            if lp.l2_domain != Lp::NO_L2 && lp.l2_domain as usize >= self.l2_domains.len() {
+                return fail(format!(
+                    "LP {} names L2 domain {} of {}",
+                    lp.os_id,
+                    lp.l2_domain,
+                    self.l2_domains.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// One LP per physical core (the `smt_index == 0` primary), best core
+    /// first - the order to fill cores in when spawning latency-sensitive
+    /// threads.
+    ///
+    /// Ordered by kind (Performance, Efficiency, LpEfficiency, Unknown), then
+    /// by descending [`Lp::perf_hint`] within a kind, then detection order, so
+    /// "favored" cores lead their tier wherever the OS reports them:
+    ///
+    /// - **Linux**: kernel `cpu_capacity` - ITMT favored cores and ARM
+    ///   prime-vs-mid tiers get a higher capacity than their kind peers - or
+    ///   ACPI CPPC `highest_perf` on x86, which has no capacity files.
+    /// - **Windows**: CPU Set `SchedulingClass`, the OS preferred-core rank.
+    /// - **macOS**: perflevel order, kind-granular only.
+    pub fn cores_by_preference(&self) -> Vec<&Lp> {
+        let mut cores: Vec<&Lp> = self.lps.iter().filter(|lp| lp.smt_index == 0).collect();
+
+        cores.sort_by_key(|lp| (lp.kind.index(), std::cmp::Reverse(lp.perf_hint)));
+        cores
+    }
+
+    /// The "preferred" cores - the Performance cores that turbo highest
+    /// (Intel Turbo Boost Max 3.0, AMD preferred cores), one LP per core
+    /// (`smt_index == 0`), in detection order. Pin the single most
+    /// latency-critical thread here.
+    ///
+    /// These are the Performance cores with the highest [`Lp::perf_hint`]
+    /// (see [`cores_by_preference`](Self::cores_by_preference) for the
+    /// per-OS source). When the OS reports no per-core ranking - every
+    /// Performance core ties - all of them are returned.
+    pub fn favored_cores(&self) -> Vec<&Lp> {
+        let p_cores = || {
+            self.lps
+                .iter()
+                .filter(|lp| lp.kind == CoreKind::Performance && lp.smt_index == 0)
+        };
+        let best = p_cores().map(|lp| lp.perf_hint).max().unwrap_or(0);
+
+        p_cores().filter(|lp| lp.perf_hint == best).collect()
+    }
+
+    /// OS id of the first LP (`smt_index == 0`, lowest OS id) on a
+    /// Performance core - the default pin target for a main thread.
+    /// Homogeneous machines are all-Performance; `None` when no LP is
+    /// classified Performance (kinds left `Unknown`, a topology override
+    /// without them, a hybrid part with every P-core offline).
+    pub fn first_performance_core_lp(&self) -> Option<usize> {
+        self.first_core_lp(CoreKind::Performance)
+    }
+
+    /// OS id of the first LP on an Efficiency core, or `None` on non-hybrid
+    /// machines. LpEfficiency cores are not considered.
+    pub fn first_efficiency_core_lp(&self) -> Option<usize> {
+        self.first_core_lp(CoreKind::Efficiency)
+    }
+
+    fn first_core_lp(&self, kind: CoreKind) -> Option<usize> {
+        self.lps
+            .iter()
+            .find(|lp| lp.kind == kind && lp.smt_index == 0)
+            .map(|lp| lp.os_id as usize)
+    }
+
+    /// One record per online logical processor, sorted by ascending
+    /// [`Lp::os_id`] on every platform.
+    ///
+    /// Read-only: [`logical_processor_ids`](Self::logical_processor_ids) is
+    /// cached from it, so it cannot change after detection.
+    pub fn lps(&self) -> &[Lp] {
+        &self.lps
+    }
+
+    /// All OS logical-processor ids, ascending - the order of
+    /// [`lps`](Self::lps()), guaranteed on every platform.
+    ///
+    /// Flattened on the first call and cached - later calls return the same
+    /// slice and never allocate, so this is safe to call from hot pinning
+    /// loops.
+    pub fn logical_processor_ids(&self) -> &[usize] {
+        self.logical_processor_ids
+            .get_or_init(|| self.lps.iter().map(|lp| lp.os_id as usize).collect())
+    }
+
+    /// The OS logical-processor id of the LP at library index `index` (its
+    /// position in [`lps`](Self::lps()) / [`logical_processor_ids`](Self::logical_processor_ids)),
+    /// or `None` past the end.
+    ///
+    /// The two numberings differ wherever the OS's ids have gaps (offline
+    /// CPUs, container cpusets, Windows processor groups that are not full).
+    /// Every affinity API takes OS ids, so translate before pinning.
+    pub fn os_logical_id(&self, index: usize) -> Option<usize> {
+        self.lps.get(index).map(|lp| lp.os_id as usize)
+    }
+
+    /// The library index (position in [`lps`](Self::lps())) of the LP the OS
+    /// calls `os_id` - the inverse of [`os_logical_id`](Self::os_logical_id),
+    /// for ids obtained from another API (`sched_getcpu`,
+    /// `GetCurrentProcessorNumberEx`, a profiler). `None` if `os_id` is not an
+    /// online LP.
+    pub fn library_index(&self, os_id: usize) -> Option<usize> {
+        self.lps.iter().position(|lp| lp.os_id as usize == os_id)
+    }
+
+    /// OS id of the bootstrap processor (BSP) - the LP firmware started and
+    /// the OS booted on, which usually also services most device interrupts
+    /// and timer housekeeping. `None` when that LP is not online here.
+    ///
+    /// Best-effort: user mode cannot read the BSP flag itself (bit 8 of the
+    /// IA32_APIC_BASE MSR on x86; ARM has no architectural equivalent), and
+    /// CPUID leaf 1 `EBX[31:24]` only yields the APIC id of the LP executing
+    /// it. What does hold is that Linux, Windows and the BSDs all number the
+    /// boot processor 0, so this is OS id 0. On macOS the LP ids are
+    /// synthetic (see [`lps`](Self::lps())) and the answer is nominal.
+    pub fn bootstrap_processor(&self) -> Option<usize> {
+        self.library_index(0).map(|_| 0)
+    }
+
+    /// `true` if OS LP `lp_id` is the [bootstrap
+    /// processor](Self::bootstrap_processor) - the LP to leave alone when
+    /// pinning latency-sensitive threads. Same best-effort caveats.
+    pub fn is_bootstrap_processor(&self, lp_id: usize) -> bool {
+        self.bootstrap_processor() == Some(lp_id)
+    }
+
+    /// The LP of kind `kind` closest to `home_lp` (an OS LP id) in the cache
+    /// hierarchy - for waking a latency task where its working set is still warm.
+    ///
+    /// Candidates are ranked by how much cache they share with the home LP: an
+    /// SMT sibling on the same physical core first, then the same
+    /// [`L2Domain`], then the same [`L3Domain`], then the same socket; ties go
+    /// to the higher [`Lp::perf_hint`], then to detection order. The home LP
+    /// itself is never returned. Falls back to any LP of `kind` when none shares
+    /// a cache, and returns `None` only when `home_lp` is not an online LP or no
+    /// other LP of `kind` exists.
+    pub fn nearest_core_of_kind(&self, home_lp: usize, kind: CoreKind) -> Option<&Lp> {
+        let home = self.lps.iter().find(|lp| lp.os_id as usize == home_lp)?;
+
+        let locality = |lp: &Lp| -> u8 {
+            if lp.core == home.core {
+                4
+            } else if lp.l2_domain != Lp::NO_L2 && lp.l2_domain == home.l2_domain {
+                3
+            } else if lp.l3_domain != Lp::NO_L3 && lp.l3_domain == home.l3_domain {
+                2
+            } else if lp.socket == home.socket {
+                1
+            } else {
+                0
+            }
+        };
+
+        self.lps
+            .iter()
+            .filter(|lp| lp.kind == kind && lp.os_id != home.os_id)
+            .min_by_key(|lp| std::cmp::Reverse((locality(lp), lp.perf_hint)))
     }
 
     /// Mask of every online LP.
@@ -153,6 +1080,58 @@ impl CpuInfo {
         self.kind_mask(CoreKind::LpEfficiency)
     }
 
+    /// Mask for background work while on battery (see [`crate::is_on_battery`]).
+    ///
+    /// On hybrid machines, the Efficiency and LpEfficiency LPs. Otherwise the
+    /// LPs with the lowest [`Lp::perf_hint`] - every LP when the OS ranks
+    /// none of them. Never empty.
+    pub fn battery_saver_mask(&self) -> AffinityMask {
+        if self.is_hybrid() {
+            return self.mask_where(|lp| lp.kind != CoreKind::Performance);
+        }
+        let slowest = self.lps.iter().map(|lp| lp.perf_hint).min().unwrap_or(0);
+        self.mask_where(|lp| lp.perf_hint == slowest)
+    }
+
+    /// Mask for a background pool that leaves headroom for the OS and the
+    /// foreground: every LP except those on the first `reserve_fastest` cores
+    /// of [`cores_by_preference`](Self::cores_by_preference). A reserved
+    /// core's SMT siblings are reserved with it.
+    ///
+    /// Every LP for `reserve_fastest == 0`; empty when `reserve_fastest` is
+    /// at least [`core_count`](Self::core_count) - check before pinning to it.
+    pub fn background_mask(&self, reserve_fastest: usize) -> AffinityMask {
+        let reserved: Vec<u16> = self
+            .cores_by_preference()
+            .iter()
+            .take(reserve_fastest)
+            .map(|lp| lp.core)
+            .collect();
+        self.mask_where(|lp| !reserved.contains(&lp.core))
+    }
+
+    /// Mask of the LPs the OS has set aside for exclusive use by another part
+    /// of the system - Windows background or system work, a real-time
+    /// partition - so a game should not pin threads there: work pinned to
+    /// them waits on the OS's use of them.
+    ///
+    /// Queried at call time like [`Lp::is_parked`], never cached at detection.
+    /// Windows reads the CPU Set flags (`GetSystemCpuSetInformation`): an LP
+    /// counts as reserved when its set is `Allocated` but not
+    /// `AllocatedToTargetProcess`. Empty where CPU Sets are unavailable and on
+    /// platforms without the concept (Linux `isolcpus` LPs never appear in the
+    /// process mask to begin with).
+    pub fn os_reserved_cores(&self) -> AffinityMask {
+        #[cfg(target_os = "windows")]
+        {
+            crate::platform::windows::cpu::os_reserved_lps().intersection(&self.all_cores_mask())
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            AffinityMask::empty()
+        }
+    }
+
     /// Mask with ONE LP per physical core (`smt_index == 0`) - "no SMT siblings".
     pub fn primary_thread_mask(&self) -> AffinityMask {
         self.mask_where(|lp| lp.smt_index == 0)
@@ -174,6 +1153,185 @@ impl CpuInfo {
             .unwrap_or_else(AffinityMask::empty)
     }
 
+    /// One mask per distinct shared-L2 group, in [`CpuInfo::l2_domains`]
+    /// order - the clusters for cluster-local scheduling.
+    ///
+    /// Apple Silicon and clustered ARM parts yield one mask per cluster;
+    /// x86 parts with a private L2 yield one per core (its SMT siblings).
+    /// Cores without L2 data each form their own cluster, appended last.
+    pub fn l2_clusters(&self) -> Vec<AffinityMask> {
+        let mut clusters: Vec<AffinityMask> = self.l2_domains.iter().map(|d| d.mask).collect();
+
+        for home in self
+            .lps
+            .iter()
+            .filter(|lp| lp.l2_domain == Lp::NO_L2 && lp.smt_index == 0)
+        {
+            clusters.push(self.mask_where(|lp| lp.l2_domain == Lp::NO_L2 && lp.core == home.core));
+        }
+
+        clusters
+    }
+
+    /// Clusters of Performance cores - the groups cross-cluster scheduling
+    /// cares about, where migrating work between groups costs a cache
+    /// refill. 0 without Performance cores.
+    ///
+    /// Cores sharing an L2 form a cluster (Apple Silicon's
+    /// `hw.perflevelN.cpusperl2`, Intel E-core modules). Where every core of
+    /// the kind has its own L2 - DynamIQ ARM parts - cores group by
+    /// [`Lp::capacity`] instead, so a prime core and the big cores beside it
+    /// count as two clusters; capacities within 1/16 of each other (scheduler
+    /// jitter) stay one group.
+    pub fn performance_cluster_count(&self) -> usize {
+        self.cluster_count(CoreKind::Performance)
+    }
+
+    /// Clusters of [`CoreKind::Efficiency`] cores, grouped as in
+    /// [`performance_cluster_count`](Self::performance_cluster_count).
+    /// 0 on homogeneous machines.
+    pub fn efficiency_cluster_count(&self) -> usize {
+        self.cluster_count(CoreKind::Efficiency)
+    }
+
+    fn cluster_count(&self, kind: CoreKind) -> usize {
+        let cores = || {
+            self.lps
+                .iter()
+                .filter(move |lp| lp.kind == kind && lp.smt_index == 0)
+        };
+        let shares_l2 = cores().any(|lp| {
+            self.l2_domains
+                .get(usize::from(lp.l2_domain))
+                .is_some_and(|domain| domain.core_count > 1)
+        });
+
+        if shares_l2 {
+            let mut domains: Vec<u16> = cores().map(|lp| lp.l2_domain).collect();
+            domains.sort_unstable();
+            domains.dedup();
+            return domains.len();
+        }
+
+        let mut capacities: Vec<u16> = cores().map(|lp| lp.capacity.unwrap_or(0)).collect();
+        capacities.sort_unstable();
+        let steps = capacities
+            .windows(2)
+            .filter(|pair| pair[1] - pair[0] > pair[1] / 16)
+            .count();
+        if capacities.is_empty() { 0 } else { steps + 1 }
+    }
+
+    /// Mask of the online LPs sharing the last-level cache with LP `lp_id`
+    /// (OS id), `lp_id` included - the set to build a cache-coherent work
+    /// group from.
+    ///
+    /// The LLC is the LP's [`L3Domain`], or its [`L2Domain`] on parts that
+    /// report no L3 (Apple Silicon). With no cache data at all it degrades
+    /// to the LP's own core (its SMT siblings). Empty when `lp_id` is not an
+    /// online LP.
+    pub fn llc_siblings_mask(&self, lp_id: usize) -> AffinityMask {
+        let Some(home) = self.lps.iter().find(|lp| lp.os_id as usize == lp_id) else {
+            return AffinityMask::empty();
+        };
+
+        if home.l3_domain != Lp::NO_L3 {
+            self.mask_where(|lp| lp.l3_domain == home.l3_domain)
+        } else if home.l2_domain != Lp::NO_L2 {
+            self.mask_where(|lp| lp.l2_domain == home.l2_domain)
+        } else {
+            self.mask_where(|lp| lp.core == home.core)
+        }
+    }
+
+    /// Mask of the LPs on physical core `core` (the dense [`Lp::core`]
+    /// index) - every SMT sibling, for "pin to this whole core". Empty for
+    /// a core index that does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::CpuInfo;
+    ///
+    /// let info = CpuInfo::detect().unwrap();
+    /// let core = info.lps()[0].core;
+    /// let mask = info.core_mask(core);
+    ///
+    /// // Both SMT siblings (or the single LP with SMT off), nothing else.
+    /// let siblings: Vec<usize> = info
+    ///     .lps()
+    ///     .iter()
+    ///     .filter(|lp| lp.core == core)
+    ///     .map(|lp| lp.os_id as usize)
+    ///     .collect();
+    /// assert_eq!(mask.iter().collect::<Vec<_>>(), siblings);
+    /// ```
+    pub fn core_mask(&self, core: u16) -> AffinityMask {
+        self.mask_where(|lp| lp.core == core)
+    }
+
+    /// Mask of the LPs on socket `socket` (the dense [`Lp::socket`] index).
+    pub fn socket_mask(&self, socket: u8) -> AffinityMask {
+        self.mask_where(|lp| lp.socket == socket)
+    }
+
+    /// The cores (dense [`Lp::core`] ids) on socket `socket`, in the OS's own
+    /// numbering ([`Lp::native_core_id`]) - the ids `lscpu` and
+    /// `/proc/cpuinfo` show, which the dense ids need not follow. Cores
+    /// without a native id come last, by dense id.
+    pub fn cores_by_native_id(&self, socket: u8) -> Vec<u16> {
+        let mut cores: Vec<(Option<u32>, u16)> = self
+            .lps
+            .iter()
+            .filter(|lp| lp.socket == socket)
+            .map(|lp| (lp.native_core_id, lp.core))
+            .collect();
+        cores.sort_unstable_by_key(|&(native, core)| (native.is_none(), native, core));
+        cores.dedup();
+
+        cores.into_iter().map(|(_, core)| core).collect()
+    }
+
+    /// Physical cores per [`CoreKind`] on socket `socket`, indexed by
+    /// [`CoreKind::index()`] - the per-socket slice of
+    /// [`kind_core_counts`](Self::kind_core_counts). All zero for a socket
+    /// index that does not exist.
+    ///
+    /// Matters on multi-socket hybrid servers, where each package can carry
+    /// a different P/E mix (or a different SKU entirely).
+    pub fn socket_kind_core_counts(&self, socket: u8) -> [u16; CoreKind::COUNT] {
+        let mut counts = [0u16; CoreKind::COUNT];
+
+        for lp in self
+            .lps
+            .iter()
+            .filter(|lp| lp.socket == socket && lp.smt_index == 0)
+        {
+            counts[lp.kind.index()] += 1;
+        }
+
+        counts
+    }
+
+    /// OS LP ids on socket `socket`, ascending; empty for a socket index that
+    /// does not exist.
+    pub fn socket_logical_processor_ids(&self, socket: u8) -> Vec<usize> {
+        self.socket_mask(socket).iter().collect()
+    }
+
+    /// Splits `mask` into one sub-mask per socket: `(socket, masked LPs on it)`
+    /// pairs in ascending socket order, skipping sockets `mask` does not touch.
+    /// Ids in `mask` that are not online LPs are dropped.
+    ///
+    /// The building block for per-socket worker pools: hand each pool the
+    /// sub-mask of its socket.
+    pub fn partition_mask_by_socket(&self, mask: &AffinityMask) -> Vec<(usize, AffinityMask)> {
+        (0..self.socket_count)
+            .map(|socket| (socket as usize, self.socket_mask(socket).intersection(mask)))
+            .filter(|(_, sub)| !sub.is_empty())
+            .collect()
+    }
+
     /// Mask of the LPs on NUMA node `node`.
     pub fn numa_node_mask(&self, node: u8) -> AffinityMask {
         self.mask_where(|lp| lp.numa_node == node)
@@ -189,6 +1347,97 @@ impl CpuInfo {
         mask
     }
 
+    /// Post-construction fixups every detection backend runs on the
+    /// `CpuInfo` it assembled: sorts [`lps`](Self::lps()) by OS id and restores
+    /// the documented domain order, records
+    /// the gaps every backend shares (no cache data at all) in
+    /// [`detection_warnings`](Self::detection_warnings).
+    ///
+    /// Steps `options` skipped are not reported as gaps.
+    pub(crate) fn finalize(&mut self, options: &DetectOptions) {
+        // Stable, so SMT siblings sharing an id (a broken backend) keep their
+        // order for validate() to report.
+        self.lps.sort_by_key(|lp| lp.os_id);
+        self.logical_processor_ids = OnceLock::new();
+        self.normalize_domain_order();
+        self.estimate_cache_latencies();
+
+        let no_caches = self.l3_domains.is_empty()
+            && self.l2_domains.is_empty()
+            && self
+                .l1d
+                .iter()
+                .chain(&self.l1i)
+                .chain(&self.l2)
+                .all(|c| c.size_bytes == 0);
+        if no_caches && options.caches {
+            self.detection_warnings
+                .push("cache info unavailable".to_string());
+        }
+
+        #[cfg(feature = "log")]
+        for warning in &self.detection_warnings {
+            log::warn!("CPU detection incomplete: {}", warning);
+        }
+    }
+
+    /// Fills [`CacheInfo::estimated_latency_cycles`] of the detected per-kind
+    /// L1d and L2 from the microarchitecture table. L3 estimates are looked
+    /// up when [`caches`](Self::caches) builds the domain entries. Backends
+    /// that stamp [`x86_signature`](Self::x86_signature) after assembly
+    /// re-run it.
+    pub(crate) fn estimate_cache_latencies(&mut self) {
+        let Some(arch) = self.microarchitecture() else {
+            return;
+        };
+        for kind in [
+            CoreKind::Performance,
+            CoreKind::Efficiency,
+            CoreKind::LpEfficiency,
+            CoreKind::Unknown,
+        ] {
+            for (level, cache) in [
+                (CacheLevel::L1d, &mut self.l1d[kind.index()]),
+                (CacheLevel::L2, &mut self.l2[kind.index()]),
+            ] {
+                if cache.size_bytes > 0 {
+                    cache.estimated_latency_cycles =
+                        super::microarch::cache_latency_cycles(arch, kind, level);
+                }
+            }
+        }
+    }
+
+    /// Clears what `options` skips, for backends whose caches and clocks come
+    /// from the same queries as the topology (nothing to save by skipping).
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn without_skipped(mut self, options: &DetectOptions) -> Self {
+        if !options.caches {
+            self.l3_domains.clear();
+            self.l2_domains.clear();
+            self.l1d = Default::default();
+            self.l1i = Default::default();
+            self.l2 = Default::default();
+            for lp in &mut self.lps {
+                lp.l3_domain = Lp::NO_L3;
+                lp.l2_domain = Lp::NO_L2;
+            }
+            self.detection_warnings
+                .retain(|w| w != "cache info unavailable");
+        }
+
+        if !options.frequencies {
+            self.base_frequency_mhz = None;
+            self.max_frequency_mhz = None;
+            for lp in &mut self.lps {
+                lp.freq_min_mhz = None;
+                lp.freq_max_mhz = None;
+            }
+        }
+
+        self
+    }
+
     /// Sorts the L3 and L2 domain tables by ascending lowest member LP and
     /// remaps every `Lp`'s domain index to match - the order
     /// [`l3_domains`](Self::l3_domains) / [`l2_domains`](Self::l2_domains) promise.
@@ -198,7 +1447,7 @@ impl CpuInfo {
     /// offline LP that its `shared_cpu_list` still names, so iteration order and
     /// lowest-member order disagree; this restores the contract. Stable - an
     /// already-ascending table is left untouched.
-    pub(crate) fn normalize_domain_order(&mut self) {
+    fn normalize_domain_order(&mut self) {
         let l3_remap = sort_domains_by_lowest_lp(&mut self.l3_domains, |d| &d.mask);
         let l2_remap = sort_domains_by_lowest_lp(&mut self.l2_domains, |d| &d.mask);
 
@@ -213,6 +1462,60 @@ impl CpuInfo {
     }
 }
 
+#[cfg(feature = "json")]
+impl CpuInfo {
+    /// Parses a `CpuInfo` from JSON written by [`CpuInfo::to_json`] - by this
+    /// release or any older one.
+    ///
+    /// Fields newer than the blob take their defaults (`None` for optional
+    /// values), and defaults that older data can still prove are back-filled:
+    /// a schema-1 blob with SMT siblings reports `smt_supported`.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidParameter`](crate::Error::InvalidParameter) when the
+    /// text is not valid JSON or misses a field every schema has.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut info: CpuInfo = serde_json::from_str(json)
+            .map_err(|e| crate::Error::InvalidParameter(format!("CpuInfo JSON: {}", e).into()))?;
+
+        if info.schema_version < 2 {
+            info.smt_supported |= info.is_smt_enabled();
+        }
+
+        Ok(info)
+    }
+
+    /// Loads the [`TOPOLOGY_OVERRIDE_ENV`](Self::TOPOLOGY_OVERRIDE_ENV) file;
+    /// `None` when the variable is unset or empty.
+    fn topology_override(path: Option<std::ffi::OsString>) -> Option<Result<Self>> {
+        let path = std::path::PathBuf::from(path.filter(|p| !p.is_empty())?);
+
+        #[cfg(feature = "log")]
+        log::warn!(
+            "{} set: loading CPU topology from {} instead of detecting",
+            Self::TOPOLOGY_OVERRIDE_ENV,
+            path.display()
+        );
+
+        Some(
+            std::fs::read_to_string(&path)
+                .map_err(|e| {
+                    crate::Error::InvalidParameter(
+                        format!("{}={}: {}", Self::TOPOLOGY_OVERRIDE_ENV, path.display(), e).into(),
+                    )
+                })
+                .and_then(|json| Self::from_json(&json)),
+        )
+    }
+
+    /// Serializes to JSON, stamped with [`CpuInfo::SCHEMA_VERSION`] for
+    /// freshly detected values.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("CpuInfo serializes to JSON infallibly")
+    }
+}
+
 /// Sorts `domains` by ascending lowest member LP and returns the old-to-new
 /// index `remap` (`remap[old] == new`). Stable, so an already-sorted table is
 /// untouched and its remap is the identity.
@@ -231,3 +1534,1175 @@ fn sort_domains_by_lowest_lp<D: Clone>(
     *domains = order.iter().map(|&i| domains[i].clone()).collect();
     remap
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lp(os_id: u16, core: u16, kind: CoreKind) -> Lp {
+        cached_lp(os_id, core, kind, Lp::NO_L3, Lp::NO_L2)
+    }
+
+    fn cached_lp(os_id: u16, core: u16, kind: CoreKind, l3_domain: u8, l2_domain: u16) -> Lp {
+        Lp {
+            os_id,
+            core,
+            socket: 0,
+            l3_domain,
+            l2_domain,
+            numa_node: 0,
+            kind,
+            smt_index: 0,
+            perf_hint: 0,
+            cpu_part: 0,
+            native_core_id: None,
+            capacity: None,
+            freq_min_mhz: None,
+            freq_max_mhz: None,
+        }
+    }
+
+    /// Assembles a `CpuInfo` around `lps`, deriving the domain tables from
+    /// the LPs' `l3_domain` / `l2_domain` indices.
+    fn info(lps: Vec<Lp>) -> CpuInfo {
+        let mut l3_domains: Vec<L3Domain> = Vec::new();
+        let mut l2_domains: Vec<L2Domain> = Vec::new();
+        for lp in &lps {
+            if lp.l3_domain != Lp::NO_L3 {
+                let idx = lp.l3_domain as usize;
+                while l3_domains.len() <= idx {
+                    l3_domains.push(L3Domain {
+                        size_bytes: 0,
+                        mask: AffinityMask::empty(),
+                        core_count: 0,
+                    });
+                }
+                l3_domains[idx].mask.add(lp.os_id as usize);
+                l3_domains[idx].core_count += 1;
+            }
+            if lp.l2_domain != Lp::NO_L2 {
+                let idx = lp.l2_domain as usize;
+                while l2_domains.len() <= idx {
+                    l2_domains.push(L2Domain {
+                        size_bytes: 0,
+                        mask: AffinityMask::empty(),
+                        core_count: 0,
+                        l3_domain: lp.l3_domain,
+                    });
+                }
+                l2_domains[idx].mask.add(lp.os_id as usize);
+                l2_domains[idx].core_count += 1;
+            }
+        }
+
+        let mut kind_core_counts = [0u16; CoreKind::COUNT];
+        for lp in lps.iter().filter(|lp| lp.smt_index == 0) {
+            kind_core_counts[lp.kind.index()] += 1;
+        }
+
+        let mut info = CpuInfo {
+            core_count: lps.iter().filter(|lp| lp.smt_index == 0).count() as u16,
+            socket_count: lps.iter().map(|lp| lp.socket + 1).max().unwrap_or(1),
+            lps,
+            numa_node_count: 1,
+            smt_supported: false,
+            kind_core_counts,
+            l3_domains,
+            l2_domains,
+            l1d: Default::default(),
+            l1i: Default::default(),
+            l2: Default::default(),
+            vendor: Vendor::Unknown,
+            model_name: "test cpu".to_string(),
+            features: CpuFeatures::default(),
+            base_frequency_mhz: None,
+            max_frequency_mhz: None,
+            clflush_line_size: None,
+            has_invariant_tsc: false,
+            tsc_frequency_hz: None,
+            microcode: None,
+            tdp_watts: None,
+            x86_signature: None,
+            avx512_downclock_risk: None,
+            schema_version: CpuInfo::SCHEMA_VERSION,
+            detection_warnings: Vec::new(),
+            logical_processor_ids: Default::default(),
+        };
+        info.finalize(&DetectOptions::FULL);
+        info
+    }
+
+    /// An SMT sibling (`smt_index` 1) on `core`.
+    fn sibling(os_id: u16, core: u16, kind: CoreKind, l3_domain: u8, l2_domain: u16) -> Lp {
+        Lp {
+            smt_index: 1,
+            ..cached_lp(os_id, core, kind, l3_domain, l2_domain)
+        }
+    }
+
+    /// 8 SMT P-cores (LPs 0-15, each with a private L2) + 4 E-cores (16-19)
+    /// sharing one L2, all under one L3 - Alder Lake style.
+    fn hybrid_8p_4e() -> CpuInfo {
+        let mut lps = Vec::new();
+        for core in 0..8u16 {
+            lps.push(cached_lp(core * 2, core, CoreKind::Performance, 0, core));
+            lps.push(sibling(core * 2 + 1, core, CoreKind::Performance, 0, core));
+        }
+        for core in 8..12u16 {
+            lps.push(cached_lp(core + 8, core, CoreKind::Efficiency, 0, 8));
+        }
+        info(lps)
+    }
+
+    #[test]
+    fn validate_accepts_consistent_and_names_each_corruption() {
+        let good = || {
+            let mut lps = vec![
+                lp(0, 0, CoreKind::Performance),
+                lp(1, 0, CoreKind::Performance),
+                lp(2, 1, CoreKind::Efficiency),
+            ];
+            lps[1].smt_index = 1;
+            info(lps)
+        };
+        assert!(good().validate().is_ok());
+
+        let err = |info: CpuInfo| match info.validate() {
+            Err(crate::Error::Detection(msg)) => msg,
+            other => panic!("expected Error::Detection, got {:?}", other),
+        };
+
+        let mut dup = good();
+        dup.lps[2].os_id = 0;
+        assert!(err(dup).contains("listed twice"));
+
+        let mut order = good();
+        order.lps.swap(0, 2);
+        assert!(err(order).contains("listed after"));
+
+        let mut cores = good();
+        cores.core_count = 3;
+        cores.kind_core_counts[CoreKind::Performance.index()] += 1;
+        assert!(err(cores).contains("core 2 has 0"));
+
+        let mut dense = good();
+        dense.lps[2].core = 7;
+        assert!(err(dense).contains("core index 7"));
+
+        let mut kinds = good();
+        kinds.kind_core_counts[CoreKind::Efficiency.index()] += 1;
+        assert!(err(kinds).contains("kind_core_counts"));
+
+        let mut sockets = good();
+        sockets.socket_count = 2;
+        assert!(err(sockets).contains("socket 1 of 2"));
+
+        let mut socket_range = good();
+        socket_range.lps[2].socket = 1;
+        assert!(err(socket_range).contains("socket 1 but socket_count"));
+
+        let mut l3 = good();
+        l3.lps[0].l3_domain = 4;
+        assert!(err(l3).contains("L3 domain 4"));
+
+        let mut empty = good();
+        empty.lps.clear();
+        assert!(err(empty).contains("no logical processors"));
+    }
+
+    // The id list is flattened once: repeated calls hand back the very same
+    // buffer rather than a fresh allocation.
+    #[test]
+    fn logical_processor_ids_are_cached() {
+        let info = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(2, 1, CoreKind::Performance),
+            lp(5, 2, CoreKind::Efficiency),
+        ]);
+
+        let first = info.logical_processor_ids();
+        let second = info.logical_processor_ids();
+        assert_eq!(first, [0, 2, 5]);
+        assert_eq!(first.as_ptr(), second.as_ptr());
+    }
+
+    // finalize reorders `lps`: an id list read before it must not survive.
+    #[test]
+    fn finalize_resets_the_id_cache() {
+        let mut info = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(2, 1, CoreKind::Performance),
+        ]);
+        assert_eq!(info.logical_processor_ids(), [0, 2]);
+
+        info.lps.swap(0, 1);
+        info.finalize(&DetectOptions::FULL);
+        assert_eq!(info.logical_processor_ids(), [0, 2]);
+        assert_eq!(info.lps()[0].os_id, 0);
+    }
+
+    // Interleaved per-socket numbering (socket 0 = 0,1,4,5) comes back in
+    // ascending OS id order, the same on every platform.
+    #[test]
+    fn multi_socket_ids_are_sorted() {
+        let on_socket = |lp: Lp, socket: u8| Lp { socket, ..lp };
+        let info = info(vec![
+            on_socket(lp(0, 0, CoreKind::Performance), 0),
+            on_socket(
+                sibling(4, 0, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+                0,
+            ),
+            on_socket(lp(1, 1, CoreKind::Performance), 0),
+            on_socket(
+                sibling(5, 1, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+                0,
+            ),
+            on_socket(lp(2, 2, CoreKind::Performance), 1),
+            on_socket(
+                sibling(6, 2, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+                1,
+            ),
+            on_socket(lp(3, 3, CoreKind::Performance), 1),
+            on_socket(
+                sibling(7, 3, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+                1,
+            ),
+        ]);
+
+        assert_eq!(info.logical_processor_ids(), [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert!(info.lps.is_sorted_by_key(|lp| lp.os_id));
+        assert_eq!(info.os_logical_id(4), Some(4));
+        assert_eq!(info.lps[4].socket, 0);
+        assert_eq!(info.lps[4].smt_index, 1);
+    }
+
+    // Two L3 domains of P-cores (CCD-style): a home core in the second domain
+    // must be answered from its own domain, never the lower-numbered one.
+    #[test]
+    fn nearest_core_of_kind_prefers_shared_l3() {
+        let info = info(vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, 0),
+            cached_lp(1, 1, CoreKind::Performance, 0, 1),
+            cached_lp(2, 2, CoreKind::Performance, 1, 2),
+            cached_lp(3, 3, CoreKind::Performance, 1, 3),
+        ]);
+
+        assert_eq!(
+            info.nearest_core_of_kind(3, CoreKind::Performance)
+                .map(|lp| lp.os_id),
+            Some(2)
+        );
+        assert_eq!(
+            info.nearest_core_of_kind(0, CoreKind::Performance)
+                .map(|lp| lp.os_id),
+            Some(1)
+        );
+    }
+
+    // L2 sharing beats L3 sharing, and with no matching kind nearby the
+    // selector still falls back to any core of that kind.
+    #[test]
+    fn nearest_core_of_kind_ranks_l2_then_falls_back() {
+        let info = info(vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, 0),
+            cached_lp(1, 1, CoreKind::Efficiency, 0, 1),
+            cached_lp(2, 2, CoreKind::Efficiency, 0, 2),
+            cached_lp(3, 3, CoreKind::Efficiency, 0, 2),
+            cached_lp(4, 4, CoreKind::Performance, 1, 3),
+        ]);
+
+        assert_eq!(
+            info.nearest_core_of_kind(3, CoreKind::Efficiency)
+                .map(|lp| lp.os_id),
+            Some(2)
+        );
+        assert_eq!(
+            info.nearest_core_of_kind(0, CoreKind::Performance)
+                .map(|lp| lp.os_id),
+            Some(4)
+        );
+        assert!(
+            info.nearest_core_of_kind(0, CoreKind::LpEfficiency)
+                .is_none()
+        );
+        assert!(
+            info.nearest_core_of_kind(99, CoreKind::Performance)
+                .is_none()
+        );
+    }
+
+    // A mask spanning both sockets of a 2S box splits along the socket line;
+    // a socket the mask misses is left out rather than returned empty.
+    #[test]
+    fn partition_mask_by_socket_splits_two_sockets() {
+        let mut lps: Vec<Lp> = (0..8).map(|id| lp(id, id, CoreKind::Performance)).collect();
+        for lp in &mut lps[4..] {
+            lp.socket = 1;
+        }
+        let info = info(lps);
+        assert_eq!(info.socket_count, 2);
+
+        let parts = info.partition_mask_by_socket(&AffinityMask::from_cores(&[1, 2, 5, 7, 100]));
+        assert_eq!(
+            parts,
+            vec![
+                (0, AffinityMask::from_cores(&[1, 2])),
+                (1, AffinityMask::from_cores(&[5, 7])),
+            ]
+        );
+
+        let parts = info.partition_mask_by_socket(&AffinityMask::from_cores(&[6]));
+        assert_eq!(parts, vec![(1, AffinityMask::single(6))]);
+        assert!(
+            info.partition_mask_by_socket(&AffinityMask::empty())
+                .is_empty()
+        );
+    }
+
+    // 2S hybrid with different SKUs per package: socket 0 is 2P (one with an
+    // SMT sibling) + 2E, socket 1 is 1P + 3E. Counts are per physical core.
+    #[test]
+    fn socket_kind_core_counts_split_per_package() {
+        use CoreKind::{Efficiency as E, Performance as P};
+
+        let kinds = [P, P, P, E, E, P, E, E, E];
+        let mut lps: Vec<Lp> = kinds
+            .iter()
+            .enumerate()
+            .map(|(id, &kind)| lp(id as u16, id as u16, kind))
+            .collect();
+        lps[1].core = 0;
+        lps[1].smt_index = 1;
+        for lp in &mut lps[5..] {
+            lp.socket = 1;
+        }
+        let info = info(lps);
+
+        let s0 = info.socket_kind_core_counts(0);
+        let s1 = info.socket_kind_core_counts(1);
+        assert_eq!((s0[P.index()], s0[E.index()]), (2, 2));
+        assert_eq!((s1[P.index()], s1[E.index()]), (1, 3));
+        assert_eq!(info.socket_kind_core_counts(2), [0; CoreKind::COUNT]);
+
+        // Per-socket counts add up to the machine totals.
+        for k in 0..CoreKind::COUNT {
+            assert_eq!(s0[k] + s1[k], info.kind_core_counts[k]);
+        }
+
+        assert_eq!(info.socket_logical_processor_ids(0), [0, 1, 2, 3, 4]);
+        assert_eq!(info.socket_logical_processor_ids(1), [5, 6, 7, 8]);
+        assert!(info.socket_logical_processor_ids(2).is_empty());
+    }
+
+    // Hybrid with a favored P-core: the favored core leads, the other P-cores
+    // follow in detection order, E-cores and SMT siblings come last / never.
+    #[test]
+    fn cores_by_preference_puts_favored_p_cores_first() {
+        let mut lps = vec![
+            lp(0, 0, CoreKind::Efficiency),
+            lp(1, 1, CoreKind::Performance),
+            lp(2, 1, CoreKind::Performance),
+            lp(3, 2, CoreKind::Performance),
+            lp(4, 3, CoreKind::Efficiency),
+            lp(5, 4, CoreKind::Performance),
+        ];
+        lps[2].smt_index = 1;
+        for lp in &mut lps {
+            lp.perf_hint = if lp.kind == CoreKind::Performance {
+                1000
+            } else {
+                400
+            };
+        }
+        lps[3].perf_hint = 1024; // favored
+        let info = info(lps);
+
+        let order: Vec<u16> = info
+            .cores_by_preference()
+            .iter()
+            .map(|lp| lp.os_id)
+            .collect();
+        assert_eq!(order, [3, 1, 5, 0, 4]);
+    }
+
+    #[test]
+    fn battery_saver_mask_prefers_slow_cores() {
+        assert_eq!(
+            hybrid_8p_4e()
+                .battery_saver_mask()
+                .iter()
+                .collect::<Vec<_>>(),
+            (16..20).collect::<Vec<_>>()
+        );
+
+        // Non-hybrid: the cores ranked lowest; with no ranking, all of them.
+        let ranked = info(
+            [200, 180, 200]
+                .into_iter()
+                .enumerate()
+                .flat_map(|(core, perf_hint)| {
+                    let (id, core) = (core as u16 * 2, core as u16);
+                    [
+                        Lp {
+                            perf_hint,
+                            ..lp(id, core, CoreKind::Performance)
+                        },
+                        Lp {
+                            perf_hint,
+                            ..sibling(id + 1, core, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2)
+                        },
+                    ]
+                })
+                .collect(),
+        );
+        assert_eq!(
+            ranked.battery_saver_mask().iter().collect::<Vec<_>>(),
+            [2, 3]
+        );
+        let flat = info((0..4).map(|id| lp(id, id, CoreKind::Performance)).collect());
+        assert_eq!(flat.battery_saver_mask(), flat.all_cores_mask());
+    }
+
+    #[test]
+    fn background_mask_leaves_the_preferred_cores_free() {
+        // Eight SMT-2 cores; cores 2 and 5 are the favored ones.
+        let mut lps: Vec<Lp> = (0..16)
+            .map(|id| lp(id, id / 2, CoreKind::Performance))
+            .collect();
+        for lp in &mut lps {
+            lp.smt_index = (lp.os_id % 2) as u8;
+            lp.perf_hint = match lp.core {
+                2 => 1024,
+                5 => 1020,
+                _ => 1000,
+            };
+        }
+        let info = info(lps);
+
+        let background = info.background_mask(2);
+        let expected: Vec<usize> = (0..16).filter(|id| !matches!(id / 2, 2 | 5)).collect();
+        assert_eq!(background.iter().collect::<Vec<_>>(), expected);
+
+        assert_eq!(info.background_mask(0), info.all_cores_mask());
+        assert!(info.background_mask(8).is_empty());
+        assert!(info.background_mask(100).is_empty());
+    }
+
+    #[test]
+    fn os_and_library_ids_round_trip() {
+        let contiguous = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(1, 1, CoreKind::Performance),
+        ]);
+        for index in 0..2 {
+            assert_eq!(contiguous.os_logical_id(index), Some(index));
+            assert_eq!(contiguous.library_index(index), Some(index));
+        }
+
+        // LXC-style sparse online set: OS ids 0, 4, 5.
+        let sparse = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(4, 1, CoreKind::Performance),
+            lp(5, 2, CoreKind::Performance),
+        ]);
+        assert_eq!(sparse.os_logical_id(1), Some(4));
+        assert_eq!(sparse.library_index(5), Some(2));
+        assert_eq!(sparse.library_index(2), None);
+        assert_eq!(sparse.os_logical_id(3), None);
+        for index in 0..3 {
+            let os_id = sparse.os_logical_id(index).unwrap();
+            assert_eq!(sparse.library_index(os_id), Some(index));
+        }
+    }
+
+    #[test]
+    fn smt_width_is_per_kind_on_hybrid() {
+        let hybrid = hybrid_8p_4e();
+
+        assert_eq!(hybrid.smt_width(), 2);
+        assert_eq!(hybrid.smt_width_for_kind(CoreKind::Performance), 2);
+        assert_eq!(hybrid.smt_width_for_kind(CoreKind::Efficiency), 1);
+        assert_eq!(hybrid.smt_width_for_kind(CoreKind::LpEfficiency), 0);
+
+        // One P-core with its sibling offlined does not narrow the rest.
+        let partial = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            sibling(1, 0, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+            lp(2, 1, CoreKind::Performance),
+            sibling(3, 1, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+            lp(4, 2, CoreKind::Performance),
+        ]);
+        assert_eq!(partial.smt_width_for_kind(CoreKind::Performance), 2);
+    }
+
+    #[test]
+    fn first_core_lps_by_kind() {
+        let hybrid = hybrid_8p_4e();
+        assert_eq!(hybrid.first_performance_core_lp(), Some(0));
+        assert_eq!(hybrid.first_efficiency_core_lp(), Some(16));
+
+        // E-cores enumerated first still come back for the E query only.
+        let e_first = info(vec![
+            lp(0, 0, CoreKind::Efficiency),
+            lp(1, 1, CoreKind::Efficiency),
+            lp(2, 2, CoreKind::Performance),
+            sibling(3, 2, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+        ]);
+        assert_eq!(e_first.first_performance_core_lp(), Some(2));
+        assert_eq!(e_first.first_efficiency_core_lp(), Some(0));
+
+        let homogeneous = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            sibling(1, 0, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+            lp(2, 1, CoreKind::Performance),
+            sibling(3, 1, CoreKind::Performance, Lp::NO_L3, Lp::NO_L2),
+        ]);
+        assert_eq!(homogeneous.first_performance_core_lp(), Some(0));
+        assert_eq!(homogeneous.first_efficiency_core_lp(), None);
+
+        // No Performance LP at all: no made-up LP 0.
+        let unknown = info(vec![
+            lp(4, 0, CoreKind::Unknown),
+            sibling(5, 0, CoreKind::Unknown, Lp::NO_L3, Lp::NO_L2),
+        ]);
+        assert_eq!(unknown.first_performance_core_lp(), None);
+    }
+
+    #[test]
+    fn favored_cores_fall_back_to_all_p_cores_without_ranking() {
+        let mut lps = vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(1, 0, CoreKind::Performance),
+            lp(2, 1, CoreKind::Performance),
+            lp(3, 2, CoreKind::Efficiency),
+        ];
+        lps[1].smt_index = 1;
+        let info = info(lps);
+
+        let ids: Vec<u16> = info.favored_cores().iter().map(|lp| lp.os_id).collect();
+        assert_eq!(ids, [0, 2]);
+    }
+
+    // A blob in the 0.2606.1 shape (schema 1): no schema_version, no
+    // frequencies / SMT capability / CLFLUSH / native core ids. It must keep
+    // loading, with the newer fields defaulted or derived.
+    #[cfg(feature = "json")]
+    const SCHEMA_V1_JSON: &str = r#"{
+        "lps": [
+            {"os_id": 0, "core": 0, "socket": 0, "l3_domain": 0, "l2_domain": 0,
+             "numa_node": 0, "kind": "Performance", "smt_index": 0, "perf_hint": 0, "cpu_part": 0},
+            {"os_id": 1, "core": 0, "socket": 0, "l3_domain": 0, "l2_domain": 0,
+             "numa_node": 0, "kind": "Performance", "smt_index": 1, "perf_hint": 0, "cpu_part": 0}
+        ],
+        "core_count": 1,
+        "socket_count": 1,
+        "numa_node_count": 1,
+        "kind_core_counts": [1, 0, 0, 0],
+        "l3_domains": [
+            {"size_bytes": 8388608, "mask": {"bits": [3,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}, "core_count": 1}
+        ],
+        "l2_domains": [
+            {"size_bytes": 262144, "mask": {"bits": [3,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}, "core_count": 1, "l3_domain": 0}
+        ],
+        "l1d": [{"size_bytes": 32768, "line_bytes": 64, "shared_by": 2},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0}],
+        "l1i": [{"size_bytes": 32768, "line_bytes": 64, "shared_by": 2},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+                {"size_bytes": 0, "line_bytes": 0, "shared_by": 0}],
+        "l2": [{"size_bytes": 262144, "line_bytes": 64, "shared_by": 2},
+               {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+               {"size_bytes": 0, "line_bytes": 0, "shared_by": 0},
+               {"size_bytes": 0, "line_bytes": 0, "shared_by": 0}],
+        "vendor": "Intel",
+        "model_name": "Intel(R) Core(TM) i7-6700 CPU @ 3.40GHz",
+        "features": ""
+    }"#;
+
+    #[test]
+    fn l2_clusters_fall_back_to_single_cores() {
+        // Private L2 per SMT core (x86), plus a core with no cache data.
+        let mut lps = vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, 0),
+            cached_lp(2, 0, CoreKind::Performance, 0, 0),
+            cached_lp(1, 1, CoreKind::Performance, 0, 1),
+            cached_lp(3, 1, CoreKind::Performance, 0, 1),
+            lp(4, 2, CoreKind::Efficiency),
+        ];
+        lps[1].smt_index = 1;
+        lps[3].smt_index = 1;
+
+        let clusters: Vec<Vec<usize>> = info(lps)
+            .l2_clusters()
+            .iter()
+            .map(|mask| mask.iter().collect())
+            .collect();
+        assert_eq!(clusters, [vec![0, 2], vec![1, 3], vec![4]]);
+    }
+
+    #[test]
+    fn bootstrap_processor_is_an_online_lp() {
+        let detected = CpuInfo::detect().unwrap();
+        let bsp = detected.bootstrap_processor().unwrap();
+        assert!(detected.library_index(bsp).is_some());
+        assert!(detected.is_bootstrap_processor(bsp));
+
+        // A cpuset without LP 0 (container, offlined boot CPU).
+        let trimmed = info(vec![
+            lp(2, 0, CoreKind::Performance),
+            lp(3, 1, CoreKind::Performance),
+        ]);
+        assert_eq!(trimmed.bootstrap_processor(), None);
+        assert!(!trimmed.is_bootstrap_processor(0));
+    }
+
+    #[test]
+    fn llc_siblings_stay_inside_one_domain() {
+        // Two CCDs of two SMT cores each; LP 8 has no cache data at all.
+        let mut lps = vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, Lp::NO_L2),
+            cached_lp(4, 0, CoreKind::Performance, 0, Lp::NO_L2),
+            cached_lp(1, 1, CoreKind::Performance, 0, Lp::NO_L2),
+            cached_lp(5, 1, CoreKind::Performance, 0, Lp::NO_L2),
+            cached_lp(2, 2, CoreKind::Performance, 1, Lp::NO_L2),
+            cached_lp(6, 2, CoreKind::Performance, 1, Lp::NO_L2),
+            cached_lp(3, 3, CoreKind::Performance, 1, Lp::NO_L2),
+            cached_lp(7, 3, CoreKind::Performance, 1, Lp::NO_L2),
+            lp(8, 4, CoreKind::Efficiency),
+        ];
+        for i in [1, 3, 5, 7] {
+            lps[i].smt_index = 1;
+        }
+        let ccds = info(lps);
+
+        let ids = |mask: AffinityMask| mask.iter().collect::<Vec<_>>();
+        assert_eq!(ids(ccds.llc_siblings_mask(1)), [0, 1, 4, 5]);
+        assert_eq!(ids(ccds.llc_siblings_mask(6)), [2, 3, 6, 7]);
+        assert_eq!(ids(ccds.llc_siblings_mask(8)), [8]);
+        assert!(ccds.llc_siblings_mask(42).is_empty());
+
+        // L2 stands in for the LLC when no L3 is reported.
+        let l2_only = info(vec![
+            cached_lp(0, 0, CoreKind::Performance, Lp::NO_L3, 0),
+            cached_lp(1, 1, CoreKind::Performance, Lp::NO_L3, 0),
+            cached_lp(2, 2, CoreKind::Efficiency, Lp::NO_L3, 1),
+        ]);
+        assert_eq!(ids(l2_only.llc_siblings_mask(1)), [0, 1]);
+        assert_eq!(ids(l2_only.llc_siblings_mask(2)), [2]);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn x86_64_level_follows_feature_sets() {
+        use CpuFeatures as F;
+
+        let level = |features: F| {
+            let mut cpu = info(vec![lp(0, 0, CoreKind::Performance)]);
+            cpu.features = features;
+            cpu.x86_64_level()
+        };
+        let v2 = F::SSE | F::SSE2 | F::SSE3 | F::SSSE3 | F::SSE4_1 | F::SSE4_2 | F::POPCNT;
+        let v3 = v2 | F::AVX | F::AVX2 | F::BMI1 | F::BMI2 | F::FMA3 | F::F16C;
+        let v4 = v3 | F::AVX512F | F::AVX512BW | F::AVX512CD | F::AVX512DQ | F::AVX512VL;
+
+        assert_eq!(level(F::SSE | F::SSE2), Some(1));
+        assert_eq!(level(v2 - F::POPCNT), Some(1));
+        assert_eq!(level(v2), Some(2));
+        // Sandy Bridge: AVX without AVX2 stays v2.
+        assert_eq!(level(v2 | F::AVX), Some(2));
+        assert_eq!(level(v3), Some(3));
+        // Xeon Phi: AVX-512F/CD without BW/DQ/VL is not v4.
+        assert_eq!(level(v3 | F::AVX512F | F::AVX512CD), Some(3));
+        assert_eq!(level(v4), Some(4));
+        // Levels are cumulative - AVX-512 alone does not skip v2/v3.
+        assert_eq!(level(v4 - F::POPCNT), Some(1));
+    }
+
+    #[test]
+    fn worker_count_reserves_performance_cores() {
+        // 8 P-cores with SMT, 16 E-cores, 2 LP-E cores.
+        let mut lps = Vec::new();
+        for core in 0..8u16 {
+            lps.push(lp(core * 2, core, CoreKind::Performance));
+            let mut sibling = lp(core * 2 + 1, core, CoreKind::Performance);
+            sibling.smt_index = 1;
+            lps.push(sibling);
+        }
+        for core in 8..24u16 {
+            lps.push(lp(core + 8, core, CoreKind::Efficiency));
+        }
+        for core in 24..26u16 {
+            lps.push(lp(core + 8, core, CoreKind::LpEfficiency));
+        }
+        let hybrid = info(lps);
+
+        assert_eq!(hybrid.recommended_worker_count(0), 8);
+        assert_eq!(hybrid.recommended_worker_count(2), 6);
+        assert_eq!(hybrid.recommended_worker_count(8), 1);
+        assert_eq!(hybrid.recommended_throughput_worker_count(0), 24);
+        assert_eq!(hybrid.recommended_throughput_worker_count(2), 22);
+        assert_eq!(hybrid.recommended_throughput_worker_count(40), 1);
+
+        // Homogeneous quad core: both recommendations agree.
+        let quad = info((0..4).map(|i| lp(i, i, CoreKind::Performance)).collect());
+
+        assert_eq!(quad.recommended_worker_count(1), 3);
+        assert_eq!(quad.recommended_throughput_worker_count(1), 3);
+        assert_eq!(quad.recommended_worker_count(4), 1);
+    }
+
+    #[test]
+    fn weighted_assignments_follow_capacity() {
+        let mut lps = Vec::new();
+        for core in 0..4u16 {
+            for lp in [
+                lp(core * 2, core, CoreKind::Performance),
+                sibling(
+                    core * 2 + 1,
+                    core,
+                    CoreKind::Performance,
+                    Lp::NO_L3,
+                    Lp::NO_L2,
+                ),
+            ] {
+                lps.push(Lp {
+                    capacity: Some(1024),
+                    ..lp
+                });
+            }
+        }
+        for core in 4..8u16 {
+            lps.push(Lp {
+                capacity: Some(512),
+                ..lp(core + 4, core, CoreKind::Efficiency)
+            });
+        }
+        let info = info(lps);
+
+        // One entry per core, P-cores keyed by their first SMT sibling.
+        let split = info.weighted_core_assignments(1200);
+        assert_eq!(
+            split,
+            [
+                (0, 200),
+                (2, 200),
+                (4, 200),
+                (6, 200),
+                (8, 100),
+                (9, 100),
+                (10, 100),
+                (11, 100)
+            ]
+        );
+
+        // Uneven totals still add up: 2 per P-core and 1 per E-core leave
+        // one unit, which goes to the largest remainder - the first P-core.
+        let units: Vec<usize> = info
+            .weighted_core_assignments(13)
+            .iter()
+            .map(|&(_, units)| units)
+            .collect();
+        assert_eq!(units, [3, 2, 2, 2, 1, 1, 1, 1]);
+
+        // Without capacities the kinds stand in: same 2:1 split.
+        assert_eq!(
+            hybrid_8p_4e().weighted_core_assignments(20)[..],
+            [
+                (0, 2),
+                (2, 2),
+                (4, 2),
+                (6, 2),
+                (8, 2),
+                (10, 2),
+                (12, 2),
+                (14, 2),
+                (16, 1),
+                (17, 1),
+                (18, 1),
+                (19, 1)
+            ]
+        );
+        assert!(
+            info.weighted_core_assignments(0)
+                .iter()
+                .all(|&(_, units)| units == 0)
+        );
+    }
+
+    #[test]
+    fn total_capacity_counts_each_core_once() {
+        let mut lps = vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(1, 0, CoreKind::Performance),
+            lp(2, 1, CoreKind::Efficiency),
+            lp(3, 2, CoreKind::Efficiency),
+        ];
+        lps[1].smt_index = 1;
+        for lp in &mut lps {
+            lp.capacity = Some(match lp.kind {
+                CoreKind::Performance => 1024,
+                _ => 512,
+            });
+        }
+        let mut cpu = info(lps);
+
+        assert_eq!(cpu.total_capacity(), Some(1024 + 2 * 512));
+
+        cpu.lps[3].capacity = None;
+        assert_eq!(cpu.total_capacity(), None);
+    }
+
+    #[test]
+    fn memory_tier_follows_documented_thresholds() {
+        let machine = |cores: u16, l3_domains: &[u64], vendor: Vendor, model: &str| {
+            let lps = (0..cores)
+                .map(|core| {
+                    let domain = (core as usize * l3_domains.len() / cores as usize) as u8;
+                    let l3 = if l3_domains.is_empty() {
+                        Lp::NO_L3
+                    } else {
+                        domain
+                    };
+                    cached_lp(core, core, CoreKind::Performance, l3, Lp::NO_L2)
+                })
+                .collect();
+            let mut cpu = info(lps);
+            for (domain, &size) in cpu.l3_domains.iter_mut().zip(l3_domains) {
+                domain.size_bytes = size << 20;
+            }
+            cpu.vendor = vendor;
+            cpu.model_name = model.to_string();
+            cpu
+        };
+
+        let laptop = machine(2, &[3], Vendor::Intel, "Intel(R) Core(TM) i3-7100U CPU");
+        assert_eq!(laptop.memory_tier(), MemoryTier::Low);
+
+        let pi = machine(4, &[], Vendor::Arm, "Cortex-A72");
+        assert_eq!(pi.memory_tier(), MemoryTier::Low);
+
+        let desktop = machine(8, &[32], Vendor::Amd, "AMD Ryzen 7 5800X 8-Core Processor");
+        assert_eq!(desktop.memory_tier(), MemoryTier::Medium);
+
+        // Four cores, but a big cache: not a budget part.
+        let quad = machine(4, &[12], Vendor::Intel, "Intel(R) Core(TM) i5-12400");
+        assert_eq!(quad.memory_tier(), MemoryTier::Medium);
+
+        let server = machine(
+            64,
+            &[32, 32, 32, 32, 32, 32, 32, 32],
+            Vendor::Amd,
+            "AMD EPYC 7763 64-Core Processor",
+        );
+        assert_eq!(server.memory_tier(), MemoryTier::High);
+
+        // Model lines decide below the core threshold; "Core Ultra" is not Apple.
+        let xeon = machine(8, &[16], Vendor::Intel, "Intel(R) Xeon(R) E-2388G");
+        assert_eq!(xeon.memory_tier(), MemoryTier::High);
+        let max = machine(12, &[], Vendor::Apple, "Apple M2 Max");
+        assert_eq!(max.memory_tier(), MemoryTier::High);
+        let ultra = machine(16, &[24], Vendor::Intel, "Intel(R) Core(TM) Ultra 9 185H");
+        assert_eq!(ultra.memory_tier(), MemoryTier::Medium);
+    }
+
+    #[test]
+    fn microarchitecture_uses_signature_then_apple_model() {
+        let mut desktop = info(vec![lp(0, 0, CoreKind::Performance)]);
+        desktop.vendor = Vendor::Amd;
+        assert_eq!(desktop.microarchitecture(), None);
+        desktop.x86_signature = Some((0x19, 0x61));
+        assert_eq!(desktop.microarchitecture(), Some("Zen 4"));
+
+        let mut laptop = info(vec![lp(0, 0, CoreKind::Performance)]);
+        laptop.vendor = Vendor::Apple;
+        laptop.model_name = "Apple M1 Pro".to_string();
+        assert_eq!(laptop.microarchitecture(), Some("Firestorm/Icestorm"));
+    }
+
+    #[test]
+    fn cache_latency_estimates_follow_the_microarchitecture() {
+        let mut cpu = info(vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, 0),
+            cached_lp(1, 1, CoreKind::Efficiency, 0, 1),
+        ]);
+        cpu.vendor = Vendor::Intel;
+        cpu.x86_signature = Some((6, 0xB7));
+        for kind in [CoreKind::Performance, CoreKind::Efficiency] {
+            cpu.l1d[kind.index()].size_bytes = 48 << 10;
+            cpu.l2[kind.index()].size_bytes = 2 << 20;
+        }
+        cpu.l2_domains[0].size_bytes = 2 << 20;
+        cpu.l2_domains[1].size_bytes = 4 << 20;
+        cpu.l3_domains[0].size_bytes = 36 << 20;
+        cpu.finalize(&DetectOptions::FULL);
+
+        // Raptor Cove P-core, Gracemont E-core.
+        let set = |lp_id| cpu.caches_for_logical_processor(lp_id).unwrap();
+        let latency = |scope: Option<CacheScope>| scope.unwrap().info.estimated_latency_cycles;
+        assert_eq!(latency(set(0).l1d), Some(5));
+        assert_eq!(latency(set(0).l2), Some(16));
+        assert_eq!(latency(set(0).l3), Some(60));
+        assert_eq!(latency(set(1).l1d), Some(3));
+        assert_eq!(latency(set(1).l2), Some(17));
+        assert_eq!(
+            cpu.l1i[CoreKind::Performance.index()].estimated_latency_cycles,
+            None
+        );
+
+        // Unknown design: no estimates at all.
+        let mut unknown = info(vec![cached_lp(0, 0, CoreKind::Performance, 0, 0)]);
+        unknown.l1d[CoreKind::Performance.index()].size_bytes = 32 << 10;
+        unknown.finalize(&DetectOptions::FULL);
+        assert!(
+            unknown
+                .caches()
+                .iter()
+                .all(|c| c.info.estimated_latency_cycles.is_none())
+        );
+    }
+
+    #[test]
+    fn lp_caches_are_its_cores_and_its_l3() {
+        let l1 = |kib: u64| CacheInfo {
+            size_bytes: kib << 10,
+            line_bytes: 64,
+            ..Default::default()
+        };
+        let mut info = hybrid_8p_4e();
+        let (p, e) = (CoreKind::Performance.index(), CoreKind::Efficiency.index());
+        (info.l1d[p], info.l1i[p], info.l2[p]) = (l1(48), l1(32), l1(1280));
+        (info.l1d[e], info.l1i[e], info.l2[e]) = (l1(32), l1(64), l1(2048));
+        info.l3_domains[0].size_bytes = 25 << 20;
+        for (i, domain) in info.l2_domains.iter_mut().enumerate() {
+            domain.size_bytes = if i < 8 { 1280 << 10 } else { 2 << 20 };
+        }
+
+        // LP 3: second SMT thread of P-core 1.
+        let set = info.caches_for_logical_processor(3).unwrap();
+        let l1d = set.l1d.unwrap();
+        assert_eq!(
+            (l1d.info.size_bytes, l1d.owner),
+            (48 << 10, CacheOwner::Core(1))
+        );
+        assert_eq!(set.l1i.unwrap().info.size_bytes, 32 << 10);
+        let l2 = set.l2.unwrap();
+        assert_eq!(
+            (l2.info.size_bytes, l2.owner),
+            (1280 << 10, CacheOwner::Core(1))
+        );
+        let l3 = set.l3.unwrap();
+        assert_eq!((l3.level, l3.info.size_bytes), (CacheLevel::L3, 25 << 20));
+        assert_eq!(l3.owner, CacheOwner::Socket(0));
+
+        // LP 17: an E-core, behind its cluster's shared L2.
+        let set = info.caches_for_logical_processor(17).unwrap();
+        assert_eq!(set.l1d.unwrap().info.size_bytes, 32 << 10);
+        assert_eq!(
+            set.l2.unwrap().owner,
+            CacheOwner::CoreGroup(vec![8, 9, 10, 11])
+        );
+        assert_eq!(set.l3.unwrap().owner, CacheOwner::Socket(0));
+
+        assert!(info.caches_for_logical_processor(20).is_none());
+    }
+
+    #[test]
+    fn caches_list_private_and_shared_instances() {
+        // Two SMT cores with private L2s under one L3, plus two E-cores that
+        // share an L2 and sit in a second L3 domain.
+        let mut lps = vec![
+            cached_lp(0, 0, CoreKind::Performance, 0, 0),
+            cached_lp(1, 0, CoreKind::Performance, 0, 0),
+            cached_lp(2, 1, CoreKind::Performance, 0, 1),
+            cached_lp(3, 1, CoreKind::Performance, 0, 1),
+        ];
+        lps[1].smt_index = 1;
+        lps[3].smt_index = 1;
+        let mut cpu = info(lps);
+        cpu.l1d[CoreKind::Performance.index()] = CacheInfo {
+            size_bytes: 48 << 10,
+            line_bytes: 64,
+            shared_by: 2,
+            ..Default::default()
+        };
+        cpu.l2[CoreKind::Performance.index()] = CacheInfo {
+            size_bytes: 2 << 20,
+            line_bytes: 64,
+            shared_by: 2,
+            ..Default::default()
+        };
+        cpu.l2_domains[0].size_bytes = 2 << 20;
+        cpu.l2_domains[1].size_bytes = 2 << 20;
+        cpu.l3_domains[0].size_bytes = 30 << 20;
+
+        let caches = cpu.caches();
+        let owners = |level: CacheLevel| -> Vec<CacheOwner> {
+            caches
+                .iter()
+                .filter(|c| c.level == level)
+                .map(|c| c.owner.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            owners(CacheLevel::L1d),
+            [CacheOwner::Core(0), CacheOwner::Core(1)]
+        );
+        assert!(
+            owners(CacheLevel::L1i).is_empty(),
+            "undetected L1i is left out"
+        );
+        assert_eq!(
+            owners(CacheLevel::L2),
+            [CacheOwner::Core(0), CacheOwner::Core(1)]
+        );
+        assert_eq!(owners(CacheLevel::L3), [CacheOwner::Socket(0)]);
+
+        let l3 = caches.iter().find(|c| c.level == CacheLevel::L3).unwrap();
+        assert_eq!(l3.info.size_bytes, 30 << 20);
+        assert_eq!(l3.info.shared_by, 4);
+        assert_eq!(l3.info.line_bytes, 64);
+
+        // A second L3 domain on the same socket makes both core groups.
+        let mut lps = cpu.lps.clone();
+        lps.push(cached_lp(4, 2, CoreKind::Efficiency, 1, 2));
+        lps.push(cached_lp(5, 3, CoreKind::Efficiency, 1, 2));
+        let cpu = info(lps);
+        let shared: Vec<_> = cpu
+            .caches()
+            .into_iter()
+            .filter(|c| matches!(c.owner, CacheOwner::CoreGroup(_)))
+            .map(|c| (c.level, c.owner))
+            .collect();
+
+        assert_eq!(
+            shared,
+            [
+                (CacheLevel::L2, CacheOwner::CoreGroup(vec![2, 3])),
+                (CacheLevel::L3, CacheOwner::CoreGroup(vec![0, 1])),
+                (CacheLevel::L3, CacheOwner::CoreGroup(vec![2, 3])),
+            ]
+        );
+    }
+
+    #[test]
+    fn total_l2_counts_each_instance_once() {
+        // Two P-cores with private L2 domains, four E-cores sharing one.
+        let lps = vec![
+            cached_lp(0, 0, CoreKind::Performance, Lp::NO_L3, 0),
+            cached_lp(1, 1, CoreKind::Performance, Lp::NO_L3, 1),
+            cached_lp(2, 2, CoreKind::Efficiency, Lp::NO_L3, 2),
+            cached_lp(3, 3, CoreKind::Efficiency, Lp::NO_L3, 2),
+            cached_lp(4, 4, CoreKind::Efficiency, Lp::NO_L3, 2),
+            cached_lp(5, 5, CoreKind::Efficiency, Lp::NO_L3, 2),
+        ];
+        let mut with_domains = info(lps.clone());
+        for (domain, size) in with_domains.l2_domains.iter_mut().zip([2u64, 2, 4]) {
+            domain.size_bytes = size << 20;
+        }
+        assert_eq!(with_domains.total_l2_bytes(), 8 << 20);
+
+        // No domain table: fall back to the per-kind sharing degree.
+        let mut per_kind = info(lps);
+        per_kind.l2_domains.clear();
+        per_kind.l2[CoreKind::Performance.index()] = CacheInfo {
+            size_bytes: 2 << 20,
+            line_bytes: 64,
+            shared_by: 1,
+            ..Default::default()
+        };
+        per_kind.l2[CoreKind::Efficiency.index()] = CacheInfo {
+            size_bytes: 4 << 20,
+            line_bytes: 64,
+            shared_by: 4,
+            ..Default::default()
+        };
+        assert_eq!(per_kind.total_l2_bytes(), 8 << 20);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn schema_v1_json_still_loads() {
+        let info = CpuInfo::from_json(SCHEMA_V1_JSON).unwrap();
+
+        assert_eq!(info.schema_version, 1);
+        assert_eq!(info.num_logical_cores(), 2);
+        assert_eq!(info.l3_domains[0].size_bytes, 8 * 1024 * 1024);
+        assert_eq!(info.logical_processor_ids(), [0, 1]);
+        assert!(info.lps.iter().all(|lp| lp.native_core_id.is_none()));
+        assert!(info.lps.iter().all(|lp| lp.capacity.is_none()));
+        assert_eq!(info.base_frequency_mhz, None);
+        assert_eq!(info.max_frequency_mhz, None);
+        assert_eq!(info.clflush_line_size, None);
+        assert!(!info.has_invariant_tsc);
+        assert_eq!(info.tsc_frequency_hz, None);
+        // Derived, not defaulted: the siblings prove SMT-capable hardware.
+        assert!(info.smt_supported);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip_preserves_current_schema() {
+        let mut lps = vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(4, 1, CoreKind::Efficiency),
+        ];
+        lps[1].native_core_id = Some(8);
+        lps[1].capacity = Some(512);
+        let mut original = info(lps);
+        original.base_frequency_mhz = Some(3400);
+        original.clflush_line_size = Some(64);
+        original.has_invariant_tsc = true;
+        original.tsc_frequency_hz = Some(3_187_200_000);
+
+        let restored = CpuInfo::from_json(&original.to_json()).unwrap();
+        assert_eq!(restored.schema_version, CpuInfo::SCHEMA_VERSION);
+        assert_eq!(restored.logical_processor_ids(), [0, 4]);
+        assert_eq!(restored.lps[1].native_core_id, Some(8));
+        assert_eq!(restored.lps[1].capacity, Some(512));
+        assert_eq!(restored.lps[1].kind, CoreKind::Efficiency);
+        assert_eq!(restored.base_frequency_mhz, Some(3400));
+        assert_eq!(restored.clflush_line_size, Some(64));
+        assert!(restored.has_invariant_tsc);
+        assert_eq!(restored.tsc_frequency_hz, Some(3_187_200_000));
+        assert_eq!(restored.to_json(), original.to_json());
+
+        assert!(CpuInfo::from_json("{}").is_err());
+    }
+
+    // Goes through the loader detect_with() hands the variable's value to,
+    // rather than setting the process environment: that would swap the
+    // topology under every other test detecting in parallel.
+    #[cfg(feature = "json")]
+    #[test]
+    fn topology_override_loads_injected_file() {
+        let mut injected = info(vec![
+            lp(0, 0, CoreKind::Performance),
+            lp(1, 1, CoreKind::Efficiency),
+            lp(2, 2, CoreKind::Efficiency),
+        ]);
+        injected.model_name = "injected 1P+2E".to_string();
+        let path = std::env::temp_dir().join(format!(
+            "gdt-cpus-topology-override-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, injected.to_json()).unwrap();
+
+        let loaded = CpuInfo::topology_override(Some(path.clone().into()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.model_name, "injected 1P+2E");
+        assert_eq!(loaded.num_efficiency_cores(), 2);
+        assert_eq!(loaded.to_json(), injected.to_json());
+
+        assert!(CpuInfo::topology_override(None).is_none());
+        assert!(CpuInfo::topology_override(Some("".into())).is_none());
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(
+            CpuInfo::topology_override(Some(path.into())),
+            Some(Err(crate::Error::InvalidParameter(_)))
+        ));
+    }
+}
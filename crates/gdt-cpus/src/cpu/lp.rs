@@ -33,8 +33,10 @@ pub struct Lp {
     /// Relative performance hint - ORDINAL and MACHINE-LOCAL: a higher value
     /// means a faster core *on this machine*, equal values are
     /// indistinguishable, and the scale differs per OS (Linux: kernel
-    /// `cpu_capacity`, 0-1024; Windows: GLPI `EfficiencyClass`; macOS:
-    /// perflevel order). 0 = no finer signal than [`Lp::kind`].
+    /// `cpu_capacity`, 0-1024, or ACPI CPPC `highest_perf` where the kernel
+    /// has no capacity (x86); Windows: GLPI `EfficiencyClass` in the high
+    /// byte, CPU Set `SchedulingClass` in the low byte; macOS: perflevel
+    /// order). 0 = no finer signal than [`Lp::kind`].
     ///
     /// The intended use: picking the BEST cores within a kind - e.g. a chip
     /// whose Performance tier spans several frequency bins (Intel ITMT
@@ -54,6 +56,44 @@ pub struct Lp {
     /// It is NOT a kind signal: classification uses [`Lp::kind`] / [`Lp::perf_hint`],
     /// which are vendor-neutral.
     pub cpu_part: u16,
+    /// The kernel's own number for this LP's physical core, for correlating
+    /// with OS tools (`/proc/cpuinfo` `core id`, `perf`, `turbostat`). Linux
+    /// reads sysfs `topology/core_id`; it is unique only within a socket and
+    /// often sparse (e.g. `0, 1, 2, 8, 9, 10`). Distinct from both
+    /// [`Lp::os_id`] (the LP, not the core) and [`Lp::core`] (the library's
+    /// dense index). `None` where the OS exposes no such number (Windows,
+    /// macOS).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub native_core_id: Option<u32>,
+    /// Relative compute capacity of this LP's physical core, normalized so
+    /// the machine's fastest core is [`MAX_CAPACITY`](Self::MAX_CAPACITY) (1024,
+    /// the Linux scheduler's scale) -
+    /// a WEIGHT for splitting work, unlike the ordinal
+    /// [`perf_hint`](Self::perf_hint). SMT siblings carry their core's value.
+    ///
+    /// Linux: kernel `cpu_capacity`; Windows: GLPI `EfficiencyClass` scaled
+    /// linearly (`1024 * (class + 1) / (max_class + 1)`); macOS: 1024 for
+    /// Performance cores and an estimate for Efficiency cores (Apple
+    /// publishes no figure). `None` where the OS gives no signal - including
+    /// x86 Linux without `cpu_capacity`. Summed by
+    /// [`CpuInfo::total_capacity`](crate::CpuInfo::total_capacity).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capacity: Option<u16>,
+    /// Lowest clock in MHz the OS governor may currently run this LP at -
+    /// Linux cpufreq `scaling_min_freq`. `None` elsewhere, without cpufreq
+    /// (most VMs), or when [`DetectOptions::frequencies`](crate::DetectOptions::frequencies)
+    /// is off.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub freq_min_mhz: Option<u32>,
+    /// Highest clock in MHz the OS governor currently allows this LP -
+    /// Linux cpufreq `scaling_max_freq`. Below the hardware ceiling
+    /// ([`CpuInfo::max_frequency_mhz`](crate::CpuInfo::max_frequency_mhz),
+    /// from `cpuinfo_max_freq`) when a power profile or thermal policy caps
+    /// the clock, which is the throttling headroom. A snapshot from detection
+    /// time: governors change the limits at runtime. `None` where
+    /// [`freq_min_mhz`](Self::freq_min_mhz) is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub freq_max_mhz: Option<u32>,
 }
 
 impl Lp {
@@ -61,4 +101,74 @@ impl Lp {
     pub const NO_L3: u8 = 0xFF;
     /// Sentinel for [`Lp::l2_domain`]: this LP belongs to no detected L2 domain.
     pub const NO_L2: u16 = 0xFFFF;
+    /// [`Lp::capacity`] of the machine's fastest core.
+    pub const MAX_CAPACITY: u16 = 1024;
+
+    /// `true` when this LP's core is a [`CoreKind::Performance`] core - every
+    /// core of a homogeneous machine.
+    ///
+    /// ```
+    /// # #[cfg(feature = "test-util")] {
+    /// use gdt_cpus::{CoreKind, CpuInfoBuilder};
+    ///
+    /// let info = CpuInfoBuilder::new()
+    ///     .core(CoreKind::Performance, 2)
+    ///     .core(CoreKind::Efficiency, 1)
+    ///     .build();
+    /// assert!(info.lps()[0].is_performance_core());
+    /// assert!(!info.lps()[2].is_performance_core());
+    /// # }
+    /// ```
+    pub fn is_performance_core(&self) -> bool {
+        self.kind == CoreKind::Performance
+    }
+
+    /// `true` when this LP's core is an efficiency core of either tier -
+    /// [`CoreKind::Efficiency`] or [`CoreKind::LpEfficiency`]. Match on
+    /// [`kind`](Self::kind) to tell the tiers apart.
+    ///
+    /// ```
+    /// # #[cfg(feature = "test-util")] {
+    /// use gdt_cpus::{CoreKind, CpuInfoBuilder};
+    ///
+    /// let info = CpuInfoBuilder::new()
+    ///     .core(CoreKind::Performance, 1)
+    ///     .core(CoreKind::Efficiency, 1)
+    ///     .core(CoreKind::LpEfficiency, 1)
+    ///     .build();
+    /// assert!(!info.lps()[0].is_efficiency_core());
+    /// assert!(info.lps()[1].is_efficiency_core());
+    /// assert!(info.lps()[2].is_efficiency_core());
+    /// # }
+    /// ```
+    pub fn is_efficiency_core(&self) -> bool {
+        matches!(self.kind, CoreKind::Efficiency | CoreKind::LpEfficiency)
+    }
+
+    /// Whether the OS has parked this LP right now - a thread pinned to a
+    /// parked LP waits for it to be unparked, a latency spike under light load.
+    ///
+    /// Queried at call time (parking follows load from moment to moment), never
+    /// cached at detection. Windows reads the `Parked` flag of the LP's CPU Set
+    /// (`GetSystemCpuSetInformation`) - the per-LP state the power manager
+    /// publishes; `CallNtPowerInformation` only reports clocks and idle states.
+    ///
+    /// # Errors
+    ///
+    /// - [`crate::Error::Unsupported`] outside Windows (Linux and macOS do not
+    ///   park cores) and where CPU Sets are unavailable.
+    /// - [`crate::Error::InvalidCoreId`] if the LP is no longer visible to
+    ///   the process.
+    pub fn is_parked(&self) -> crate::Result<bool> {
+        #[cfg(target_os = "windows")]
+        {
+            crate::platform::windows::cpu::is_lp_parked(self.os_id)
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            Err(crate::Error::Unsupported(
+                "Core parking state is only reported on Windows.".into(),
+            ))
+        }
+    }
 }
@@ -0,0 +1,26 @@
+/// Coarse class of a machine's memory subsystem, from
+/// [`CpuInfo::memory_tier`](crate::CpuInfo::memory_tier).
+///
+/// A HEURISTIC for scaling asset and streaming budgets, not a measurement:
+/// nothing here knows the DIMM configuration. Ordered, so
+/// `tier >= MemoryTier::Medium` reads naturally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryTier {
+    /// Budget laptops, handhelds, small ARM boards.
+    Low,
+    /// Mainstream desktops and laptops.
+    Medium,
+    /// Workstations, servers, wide unified-memory SoCs.
+    High,
+}
+
+impl std::fmt::Display for MemoryTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryTier::Low => write!(f, "Low"),
+            MemoryTier::Medium => write!(f, "Medium"),
+            MemoryTier::High => write!(f, "High"),
+        }
+    }
+}
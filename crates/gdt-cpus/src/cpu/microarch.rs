@@ -0,0 +1,261 @@
+//! Best-effort microarchitecture names behind
+//! [`CpuInfo::microarchitecture`](crate::CpuInfo::microarchitecture).
+//!
+//! x86 parts are keyed on the display family / model (extended fields folded
+//! in, as [`CpuInfo::x86_signature`](crate::CpuInfo::x86_signature) stores
+//! them); Apple Silicon, which has no such signature, on the chip generation
+//! in the model name. Hybrid parts name both core designs, performance first
+//! (`"Golden Cove/Gracemont"`). Steppings are not consulted, so refreshes
+//! sharing a model number (Skylake-SP / Cascade Lake) get the base name.
+
+use crate::{CacheLevel, CoreKind, Vendor};
+
+/// Names the x86 core design for `vendor`'s `family`/`model`; `None` when
+/// the signature is not in the table.
+pub(crate) fn x86_microarchitecture(vendor: Vendor, family: u8, model: u8) -> Option<&'static str> {
+    let name = match (vendor, family) {
+        (Vendor::Intel, 6) => match model {
+            0x3C | 0x3F | 0x45 | 0x46 => "Haswell",
+            0x3D | 0x47 | 0x4F | 0x56 => "Broadwell",
+            // Skylake through Comet Lake, client and server: one core design.
+            0x4E | 0x5E | 0x55 | 0x8E | 0x9E | 0xA5 | 0xA6 => "Skylake",
+            0x66 => "Palm Cove",
+            0x6A | 0x6C | 0x7D | 0x7E => "Sunny Cove",
+            0x8C | 0x8D => "Willow Cove",
+            0xA7 => "Cypress Cove",
+            0x8F => "Golden Cove",
+            0x97 | 0x9A => "Golden Cove/Gracemont",
+            0xB7 | 0xBA | 0xBF => "Raptor Cove/Gracemont",
+            0xCF => "Raptor Cove",
+            0xAA | 0xAC => "Redwood Cove/Crestmont",
+            0xAD | 0xAE => "Redwood Cove",
+            0xAF => "Crestmont",
+            0xC5 | 0xC6 | 0xBD => "Lion Cove/Skymont",
+            0x5C | 0x5F => "Goldmont",
+            0x7A => "Goldmont Plus",
+            0x86 | 0x96 | 0x9C => "Tremont",
+            _ => return None,
+        },
+        (Vendor::Amd, 0x17) => match model {
+            // Pinnacle Ridge and Picasso are the 12 nm refresh.
+            0x08 | 0x18 => "Zen+",
+            0x00..=0x2F => "Zen",
+            _ => "Zen 2",
+        },
+        (Vendor::Amd, 0x19) => match model {
+            0x00..=0x0F | 0x20..=0x3F | 0x50..=0x5F => "Zen 3",
+            0x40..=0x4F => "Zen 3+",
+            0x10..=0x1F | 0x60..=0x7F => "Zen 4",
+            0xA0..=0xAF => "Zen 4c",
+            _ => return None,
+        },
+        (Vendor::Amd, 0x1A) => match model {
+            0x00..=0x4F | 0x60..=0x7F => "Zen 5",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Names the core designs of an Apple Silicon chip from its model name
+/// (`"Apple M1 Pro"`); `None` for other names and unlisted generations.
+pub(crate) fn apple_microarchitecture(model_name: &str) -> Option<&'static str> {
+    let chip = model_name
+        .strip_prefix("Apple ")?
+        .split_whitespace()
+        .next()?;
+    match chip {
+        "M1" => Some("Firestorm/Icestorm"),
+        "M2" => Some("Avalanche/Blizzard"),
+        "M3" => Some("Everest/Sawtooth"),
+        _ => None,
+    }
+}
+
+/// Estimated load-to-use latency, in core cycles, of `level` on the `kind`
+/// cores of `microarchitecture` (a name from the functions above). Hybrid
+/// names pick the design by kind: the first for performance cores, the
+/// second for (LP-)efficiency cores. `None` for L1i, for designs without the
+/// level, and for designs missing from the table.
+///
+/// Figures are rounded published measurements (pointer chasing at the
+/// stock clock); L3 figures are for the client parts - server meshes run
+/// noticeably slower.
+pub(crate) fn cache_latency_cycles(
+    microarchitecture: &str,
+    kind: CoreKind,
+    level: CacheLevel,
+) -> Option<u32> {
+    let mut designs = microarchitecture.split('/');
+    let performance = designs.next()?;
+    let design = match kind {
+        CoreKind::Efficiency | CoreKind::LpEfficiency => designs.next().unwrap_or(performance),
+        CoreKind::Performance | CoreKind::Unknown => performance,
+    };
+
+    // (L1d, L2, L3)
+    let (l1d, l2, l3) = match design {
+        "Haswell" => (4, 12, Some(36)),
+        "Broadwell" => (4, 12, Some(40)),
+        "Skylake" | "Palm Cove" => (4, 12, Some(42)),
+        "Sunny Cove" | "Cypress Cove" => (5, 13, Some(45)),
+        "Willow Cove" => (5, 14, Some(50)),
+        "Golden Cove" => (5, 15, Some(60)),
+        "Raptor Cove" => (5, 16, Some(60)),
+        "Redwood Cove" => (5, 16, Some(75)),
+        "Lion Cove" => (5, 17, Some(80)),
+        "Gracemont" => (3, 17, Some(70)),
+        "Crestmont" => (3, 17, Some(80)),
+        "Skymont" => (3, 19, Some(90)),
+        "Goldmont" | "Goldmont Plus" | "Tremont" => (3, 17, None),
+        "Zen" | "Zen+" => (4, 12, Some(35)),
+        "Zen 2" => (4, 12, Some(39)),
+        "Zen 3" | "Zen 3+" => (4, 12, Some(46)),
+        "Zen 4" | "Zen 4c" | "Zen 5" => (4, 14, Some(50)),
+        // Apple's system-level cache is not a core cache; no L3.
+        "Firestorm" | "Avalanche" | "Everest" => (3, 18, None),
+        "Icestorm" | "Blizzard" | "Sawtooth" => (3, 14, None),
+        _ => return None,
+    };
+    match level {
+        CacheLevel::L1d => Some(l1d),
+        CacheLevel::L1i => None,
+        CacheLevel::L2 => Some(l2),
+        CacheLevel::L3 => l3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_signatures_name_their_core() {
+        assert_eq!(
+            x86_microarchitecture(Vendor::Intel, 6, 0x9E),
+            Some("Skylake")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Intel, 6, 0x8F),
+            Some("Golden Cove")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Intel, 6, 0xB7),
+            Some("Raptor Cove/Gracemont")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Amd, 0x17, 0x71),
+            Some("Zen 2")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Amd, 0x19, 0x21),
+            Some("Zen 3")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Amd, 0x19, 0x61),
+            Some("Zen 4")
+        );
+        assert_eq!(
+            x86_microarchitecture(Vendor::Amd, 0x1A, 0x44),
+            Some("Zen 5")
+        );
+
+        // Unknown model, other vendor's family number, future family.
+        assert_eq!(x86_microarchitecture(Vendor::Intel, 6, 0x01), None);
+        assert_eq!(x86_microarchitecture(Vendor::Intel, 0x19, 0x21), None);
+        assert_eq!(x86_microarchitecture(Vendor::Amd, 0x1B, 0x00), None);
+    }
+
+    #[test]
+    fn apple_chip_generation_names_its_cores() {
+        assert_eq!(
+            apple_microarchitecture("Apple M1"),
+            Some("Firestorm/Icestorm")
+        );
+        assert_eq!(
+            apple_microarchitecture("Apple M2 Max"),
+            Some("Avalanche/Blizzard")
+        );
+        assert_eq!(
+            apple_microarchitecture("Apple M3 Pro"),
+            Some("Everest/Sawtooth")
+        );
+        assert_eq!(apple_microarchitecture("Apple M10"), None);
+        assert_eq!(apple_microarchitecture("Intel(R) Core(TM) i9"), None);
+    }
+
+    #[test]
+    fn known_designs_have_monotonic_latencies() {
+        let names = [
+            "Haswell",
+            "Broadwell",
+            "Skylake",
+            "Palm Cove",
+            "Sunny Cove",
+            "Willow Cove",
+            "Cypress Cove",
+            "Golden Cove/Gracemont",
+            "Raptor Cove/Gracemont",
+            "Redwood Cove/Crestmont",
+            "Lion Cove/Skymont",
+            "Goldmont",
+            "Goldmont Plus",
+            "Tremont",
+            "Zen",
+            "Zen+",
+            "Zen 2",
+            "Zen 3",
+            "Zen 3+",
+            "Zen 4",
+            "Zen 4c",
+            "Zen 5",
+            "Firestorm/Icestorm",
+            "Avalanche/Blizzard",
+            "Everest/Sawtooth",
+        ];
+        for name in names {
+            for kind in [CoreKind::Performance, CoreKind::Efficiency] {
+                let latency = |level| cache_latency_cycles(name, kind, level);
+                let l1d = latency(CacheLevel::L1d).unwrap_or_else(|| panic!("{name} L1d"));
+                let l2 = latency(CacheLevel::L2).unwrap_or_else(|| panic!("{name} L2"));
+                assert!((3..=6).contains(&l1d), "{name} {kind:?} L1d {l1d}");
+                assert!(l1d < l2, "{name} {kind:?} L1d {l1d} vs L2 {l2}");
+                if let Some(l3) = latency(CacheLevel::L3) {
+                    assert!(l2 < l3, "{name} {kind:?} L2 {l2} vs L3 {l3}");
+                }
+                assert_eq!(latency(CacheLevel::L1i), None);
+            }
+        }
+
+        // Hybrid names pick the design by kind.
+        assert_eq!(
+            cache_latency_cycles(
+                "Raptor Cove/Gracemont",
+                CoreKind::Performance,
+                CacheLevel::L1d
+            ),
+            Some(5)
+        );
+        assert_eq!(
+            cache_latency_cycles(
+                "Raptor Cove/Gracemont",
+                CoreKind::Efficiency,
+                CacheLevel::L1d
+            ),
+            Some(3)
+        );
+        assert_eq!(
+            cache_latency_cycles("Zen 4", CoreKind::Performance, CacheLevel::L3),
+            Some(50)
+        );
+        assert_eq!(
+            cache_latency_cycles("Firestorm/Icestorm", CoreKind::Efficiency, CacheLevel::L3),
+            None
+        );
+        assert_eq!(
+            cache_latency_cycles("Cortex-A78", CoreKind::Performance, CacheLevel::L1d),
+            None
+        );
+    }
+}
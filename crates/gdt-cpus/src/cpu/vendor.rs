@@ -23,12 +23,42 @@ pub enum Vendor {
     Nvidia,
     /// Marvell / Cavium (implementers 0x56 / 0x43).
     Marvell,
+    /// Ampere Computing (implementer 0xc0 - Altra, AmpereOne).
+    Ampere,
     /// A vendor not listed above.
     Other,
     /// The vendor could not be determined.
     Unknown,
 }
 
+impl Vendor {
+    /// Maps an aarch64 `MIDR_EL1` implementer code (bits 31:24 - Linux's
+    /// `CPU implementer` line) to a vendor; [`Vendor::Other`] for codes not
+    /// listed here.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdt_cpus::Vendor;
+    ///
+    /// assert_eq!(Vendor::from_arm_implementer(0xc0), Vendor::Ampere);
+    /// assert_eq!(Vendor::from_arm_implementer(0x00), Vendor::Other);
+    /// ```
+    pub fn from_arm_implementer(implementer: u8) -> Self {
+        match implementer {
+            0x41 => Vendor::Arm,
+            0x42 => Vendor::Broadcom,
+            // Cavium was acquired by Marvell; both implementer codes map there.
+            0x43 | 0x56 => Vendor::Marvell,
+            0x4e => Vendor::Nvidia,
+            0x51 => Vendor::Qualcomm,
+            0x61 => Vendor::Apple,
+            0xc0 => Vendor::Ampere,
+            _ => Vendor::Other,
+        }
+    }
+}
+
 impl std::fmt::Display for Vendor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -40,6 +70,7 @@ impl std::fmt::Display for Vendor {
             Vendor::Broadcom => write!(f, "Broadcom"),
             Vendor::Nvidia => write!(f, "NVIDIA"),
             Vendor::Marvell => write!(f, "Marvell"),
+            Vendor::Ampere => write!(f, "Ampere"),
             Vendor::Other => write!(f, "Other"),
             Vendor::Unknown => write!(f, "Unknown"),
         }
@@ -1,5 +1,6 @@
 //! Error types and the `Result` alias used throughout the `gdt-cpus` crate.
 
+use std::borrow::Cow;
 use std::fmt;
 
 /// A specialized `Result` type for `gdt-cpus` operations.
@@ -7,39 +8,101 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 /// The error enum for all operations within the `gdt-cpus` crate.
 ///
-/// Payloads are descriptive strings; callers that need machine-readable
-/// scheduler outcomes should use [`crate::AppliedPriority`] for priority calls.
+/// Each variant is one [`ErrorKind`]; match on [`Error::kind`] to branch
+/// without caring about the payload. Payloads are descriptive details for
+/// logs. Fixed messages are `&'static str` ([`Cow::Borrowed`]), so failing
+/// with one allocates nothing - only details that carry runtime values (a
+/// path, an OS error code) are formatted into an owned string. Callers that
+/// need machine-readable scheduler outcomes should use
+/// [`crate::AppliedPriority`] for priority calls.
+///
+/// New variants may be added in minor releases.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Error {
     /// CPU information detection failed (parsing, unexpected system response,
     /// platform-specific trouble). Contains a descriptive message.
-    Detection(String),
+    Detection(Cow<'static, str>),
 
     /// An invalid logical-core ID was provided. Contains the offending ID.
     InvalidCoreId(usize),
 
     /// A thread affinity operation failed. Contains a descriptive message.
-    Affinity(String),
+    Affinity(Cow<'static, str>),
 
     /// The requested operation is not supported on this OS or hardware.
     /// Contains a message explaining why.
-    Unsupported(String),
+    Unsupported(Cow<'static, str>),
 
     /// The operation requires permissions the process doesn't have (e.g.
     /// raising thread priority unprivileged). Contains the detail message.
-    PermissionDenied(String),
+    PermissionDenied(Cow<'static, str>),
 
     /// A system call failed (sysctl, Win32, raw syscalls). Contains a
     /// descriptive message.
-    SystemCall(String),
+    SystemCall(Cow<'static, str>),
 
     /// A requested resource was not found (file, registry key, sysctl key).
     /// Contains a message describing what was missing.
-    NotFound(String),
+    NotFound(Cow<'static, str>),
 
     /// An invalid parameter was supplied. Contains the explanation.
-    InvalidParameter(String),
+    InvalidParameter(Cow<'static, str>),
+}
+
+/// The category of an [`Error`], without its detail - `Copy`, for matching
+/// and for mapping onto error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// [`Error::Detection`].
+    Detection,
+    /// [`Error::InvalidCoreId`].
+    InvalidCoreId,
+    /// [`Error::Affinity`].
+    Affinity,
+    /// [`Error::Unsupported`].
+    Unsupported,
+    /// [`Error::PermissionDenied`].
+    PermissionDenied,
+    /// [`Error::SystemCall`].
+    SystemCall,
+    /// [`Error::NotFound`].
+    NotFound,
+    /// [`Error::InvalidParameter`].
+    InvalidParameter,
+}
+
+impl Error {
+    /// The category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Detection(_) => ErrorKind::Detection,
+            Error::InvalidCoreId(_) => ErrorKind::InvalidCoreId,
+            Error::Affinity(_) => ErrorKind::Affinity,
+            Error::Unsupported(_) => ErrorKind::Unsupported,
+            Error::PermissionDenied(_) => ErrorKind::PermissionDenied,
+            Error::SystemCall(_) => ErrorKind::SystemCall,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::InvalidParameter(_) => ErrorKind::InvalidParameter,
+        }
+    }
+
+    /// The descriptive message, or `None` for [`Error::InvalidCoreId`] (whose
+    /// payload is the id) and for an empty message.
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Error::InvalidCoreId(_) => None,
+            Error::Detection(msg)
+            | Error::Affinity(msg)
+            | Error::Unsupported(msg)
+            | Error::PermissionDenied(msg)
+            | Error::SystemCall(msg)
+            | Error::NotFound(msg)
+            | Error::InvalidParameter(msg) => Some(msg.as_ref()).filter(|msg| !msg.is_empty()),
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -58,3 +121,42 @@ impl fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_messages_stay_borrowed() {
+        // The fixed-message form every `"...".into()` call site builds.
+        let err = Error::Unsupported("Reading thread affinity is not supported.".into());
+        assert!(matches!(&err, Error::Unsupported(Cow::Borrowed(_))));
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert_eq!(
+            err.detail(),
+            Some("Reading thread affinity is not supported.")
+        );
+
+        // Cloning a borrowed detail copies the reference, not the text.
+        assert!(matches!(err.clone(), Error::Unsupported(Cow::Borrowed(_))));
+
+        assert_eq!(Error::InvalidCoreId(7).kind(), ErrorKind::InvalidCoreId);
+        assert_eq!(Error::InvalidCoreId(7).detail(), None);
+        assert_eq!(Error::NotFound("".into()).detail(), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn failed_detection_reports_a_static_detail() {
+        // A sysfs without `cpu/online`: the error names the file, no runtime
+        // values, so it is built without allocating.
+        let root = std::env::temp_dir().join(format!("gdt-cpus-error-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("devices/system/cpu")).unwrap();
+
+        let err = crate::platform::linux::cpu::detect_at(&root, &root, &crate::DetectOptions::FULL)
+            .unwrap_err();
+        assert!(matches!(err, Error::Detection(Cow::Borrowed(_))), "{err:?}");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
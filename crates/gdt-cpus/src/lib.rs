@@ -15,7 +15,14 @@
 //!     ([`CpuInfo::l3_domain_mask`]) - cross-domain latency is the real cliff.
 //! *   **Thread Affinity**: pin threads to logical cores or sets of them.
 //! *   **Thread Priority**: 7 portable levels mapped to each OS's scheduler.
-//! *   **No global state**: [`CpuInfo::detect()`] returns a plain value you own.
+//! *   **Detection keeps no global state**: [`CpuInfo::detect()`] returns a
+//!     plain value you own and caches nothing. Two conveniences do keep
+//!     process-wide state: [`has_feature`] / [`check_required_features`]
+//!     cache the first successful feature detection, and
+//!     `set_default_thread_affinity` (feature `affinity`) stores the mask
+//!     `gdt_cpus::spawn` applies to new threads. To avoid both, test
+//!     [`CpuInfo::features`] on a `CpuInfo` you hold and call
+//!     `set_thread_affinity` from your own threads.
 //!
 //! # Getting Started
 //!
@@ -26,7 +33,7 @@
 //!     let info = CpuInfo::detect()?;
 //!
 //!     println!("CPU: {} ({})", info.model_name, info.vendor);
-//!     println!("{} cores / {} threads", info.core_count, info.lps.len());
+//!     println!("{} cores / {} threads", info.core_count, info.lps().len());
 //!
 //!     if info.is_hybrid() {
 //!         println!("hybrid: {}P + {}E + {}LP-E",
@@ -90,13 +97,14 @@
 //!     for the one thread with a hard deadline.
 //!
 //! ```no_run
+//! # #[cfg(feature = "affinity")]
+//! # fn main() -> Result<(), gdt_cpus::Error> {
 //! use gdt_cpus::{CoreKind, CpuInfo, ThreadPriority, pin_thread_to_core, set_thread_priority};
 //!
-//! # fn main() -> Result<(), gdt_cpus::Error> {
 //! let info = CpuInfo::detect()?;
 //!
 //! // Best Performance-core primaries first - render thread gets the top one.
-//! let mut p_cores: Vec<_> = info.lps.iter()
+//! let mut p_cores: Vec<_> = info.lps().iter()
 //!     .filter(|lp| lp.kind == CoreKind::Performance && lp.smt_index == 0)
 //!     .collect();
 //!
@@ -120,39 +128,110 @@
 //!
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "affinity"))]
+//! # fn main() {}
 //! ```
 //!
 //! # Cargo Features
 //!
-//! *   `rtkit` *(default)*: on Linux, negotiate priority through rtkit and
-//!     the xdg realtime portal (hand-rolled minimal D-Bus client, no extra
-//!     dependencies) when direct syscalls are denied. Opt out with
-//!     `default-features = false`.
-//! *   `serde`: serialization for the CPU information structures.
+//! *   `affinity` *(default)*: the thread-control surface - affinity,
+//!     priority, real-time promotion, [`PinnableThread`] and the system
+//!     report. Without it only detection ([`CpuInfo`], [`AffinityMask`]
+//!     builders, spin helpers) is compiled, for info-only consumers that
+//!     want no thread-control `unsafe` in their audit.
+//! *   `rtkit` *(default, implies `affinity`)*: on Linux, negotiate priority
+//!     through rtkit and the xdg realtime portal (hand-rolled minimal D-Bus
+//!     client, no extra dependencies) when direct syscalls are denied. Opt
+//!     out with `default-features = false`.
+//! *   `serde`: serialization for the CPU information structures. Blobs from
+//!     older releases keep deserializing (see [`CpuInfo::schema_version`]).
+//!     [`AffinityMask`] fields can opt into a compact hex string with
+//!     `#[serde(with = "gdt_cpus::affinity_mask::hex")]`.
+//! *   `json`: `CpuInfo::from_json` / `CpuInfo::to_json` (implies `serde`).
+//! *   `log`: per-phase detection timing (topology, caches, NUMA, identity)
+//!     at `debug` level through the [`log`](https://docs.rs/log) facade -
+//!     enable with any backend, e.g. `RUST_LOG=gdt_cpus=debug` and env_logger.
+//! *   `test-util`: `CpuInfoBuilder` for constructing synthetic topologies
+//!     (hybrid, multi-socket, chiplet) in downstream unit tests. Enable it
+//!     under `[dev-dependencies]` only.
+//! *   `diagnostics`: `dump_cpuid_leaves` on x86_64 - raw cpuid register
+//!     dumps for bug reports about wrong detection. Not for decoding
+//!     features in shipping code; [`CpuInfo`] does that.
+// Info-only builds: prove the thread-control surface is really gone.
+#![cfg_attr(
+    not(feature = "affinity"),
+    doc = r#"
+Built without `affinity`: the thread-control API is compiled out.
 
+```compile_fail
+gdt_cpus::set_thread_affinity(&gdt_cpus::AffinityMask::single(0)).unwrap();
+```
+"#
+)]
 #![deny(missing_docs)]
 
 // Modules
+#[cfg(feature = "affinity")]
 mod affinity;
-mod affinity_mask;
+pub mod affinity_mask;
+#[cfg(feature = "affinity")]
 mod capabilities;
 mod cpu;
 mod error;
+#[cfg(feature = "affinity")]
+mod pinnable;
 mod platform;
+#[cfg(feature = "affinity")]
 mod priority;
+#[cfg(feature = "affinity")]
+mod profile;
+#[cfg(feature = "affinity")]
 mod realtime;
+#[cfg(feature = "affinity")]
+mod report;
+mod spin;
 
-// Re-exports - Public API
-pub use affinity::*;
+// Re-exports - Public API. Everything that changes thread state is behind
+// feature `affinity`; detection, masks and spin helpers are always present.
 pub use affinity_mask::AffinityMask;
+#[cfg(feature = "test-util")]
+pub use cpu::CpuInfoBuilder;
+pub use cpu::{
+    CacheInfo, CacheLevel, CacheOwner, CacheScope, CacheSet, CoreKind, CpuFeatures, CpuInfo,
+    DetectOptions, DetectionPhase, L2Domain, L3Domain, Lp, MemoryTier, Vendor, WritePolicy,
+};
+pub use error::{Error, ErrorKind, Result};
+pub use spin::{Backoff, SpinCalibration, backoff_for_core_kind, spin_for, spin_hint};
+
+#[cfg(feature = "affinity")]
+pub use affinity::*;
+#[cfg(feature = "affinity")]
 pub use capabilities::{PriorityCaps, priority_capabilities};
-pub use cpu::{CacheInfo, CoreKind, CpuFeatures, CpuInfo, L2Domain, L3Domain, Lp, Vendor};
-pub use error::{Error, Result};
+#[cfg(feature = "affinity")]
+pub use pinnable::{CurrentThread, PinnableThread, RawThread, configure_thread};
+#[cfg(feature = "affinity")]
 pub use priority::{
     AppliedPriority, BrokerError, FallbackReason, Grant, Mechanism, MechanismPolicy, QosClass,
     ThreadPriority,
 };
-pub use realtime::{demote_thread_from_realtime, promote_thread_to_realtime};
+#[cfg(feature = "affinity")]
+pub use profile::{ProfileGuard, ThreadProfile, apply_thread_profile};
+#[cfg(feature = "affinity")]
+pub use realtime::{
+    can_set_realtime_priority, configure_audio_thread, demote_thread_from_realtime,
+    promote_thread_to_realtime, set_thread_deadline,
+};
+#[cfg(feature = "affinity")]
+pub use report::{SystemReport, system_report};
+
+/// Full detection with a progress callback; see
+/// [`CpuInfo::detect_with_progress`].
+///
+/// Unlike the `num_*` helpers this always detects afresh - the point is to
+/// show progress while it happens.
+pub fn detect_cpu_info_with_progress(progress: impl FnMut(DetectionPhase, f32)) -> Result<CpuInfo> {
+    CpuInfo::detect_with_progress(DetectOptions::FULL, progress)
+}
 
 /// Total number of physical cores (SMT siblings counted once).
 ///
@@ -189,3 +268,257 @@ pub fn num_lp_efficiency_cores() -> Result<usize> {
 pub fn is_hybrid() -> Result<bool> {
     CpuInfo::detect().map(|info| info.is_hybrid())
 }
+
+/// OS id of the first logical processor of the first Performance core - see
+/// [`CpuInfo::first_performance_core_lp`].
+///
+/// # Errors
+///
+/// [`Error::NotFound`] when no logical processor is classified Performance,
+/// or the detection error.
+pub fn first_performance_core_lp() -> Result<usize> {
+    CpuInfo::detect()?
+        .first_performance_core_lp()
+        .ok_or_else(|| {
+            Error::NotFound("no logical processor is classified as a Performance core".into())
+        })
+}
+
+/// OS id of the first logical processor of the first Efficiency core,
+/// `Ok(None)` on non-hybrid machines - see
+/// [`CpuInfo::first_efficiency_core_lp`].
+pub fn first_efficiency_core_lp() -> Result<Option<usize>> {
+    CpuInfo::detect().map(|info| info.first_efficiency_core_lp())
+}
+
+/// Mask of the logical processors on NUMA node `node_id` (OS node id), or
+/// `None` when detection fails or no online LP is on that node - see
+/// [`CpuInfo::numa_node_mask`].
+pub fn numa_node_mask(node_id: usize) -> Option<AffinityMask> {
+    let node = u8::try_from(node_id).ok()?;
+    let mask = CpuInfo::detect().ok()?.numa_node_mask(node);
+
+    (!mask.is_empty()).then_some(mask)
+}
+
+/// `true` if the detected CPU supports `feature` - the runtime counterpart of
+/// `std::arch::is_x86_feature_detected!` / `is_aarch64_feature_detected!`,
+/// in this crate's [`CpuFeatures`] vocabulary.
+///
+/// Instruction-set support cannot change while a process runs, so the first
+/// call runs [`CpuInfo::detect()`] and caches only the feature set; later
+/// calls are a load and a mask test. A multi-flag value asks for ALL of its
+/// flags. Only a successful detection is cached: if it fails, that call
+/// answers `false` (the safe side for picking a code path) and the next call
+/// detects again, so a transient sysfs / OS error does not stick for the
+/// life of the process.
+///
+/// # Example
+///
+/// ```
+/// use gdt_cpus::{CpuFeatures, has_feature};
+///
+/// # #[cfg(target_arch = "x86_64")]
+/// if has_feature(CpuFeatures::AVX2) {
+///     // AVX2 kernel
+/// }
+///
+/// # #[cfg(target_arch = "aarch64")]
+/// if has_feature(CpuFeatures::NEON) {
+///     // NEON kernel
+/// }
+///
+/// # #[cfg(target_arch = "x86_64")]
+/// assert!(has_feature(CpuFeatures::SSE2)); // x86-64 baseline
+/// ```
+pub fn has_feature(feature: CpuFeatures) -> bool {
+    detected_features().is_ok_and(|features| features.contains(feature))
+}
+
+/// The ISA flags this binary was compiled to assume - `cfg!(target_feature)`
+/// for every [`CpuFeatures`] flag rustc has a target feature for. Raised by
+/// `-C target-cpu=...` / `-C target-feature=+avx2`; on a default x86_64
+/// build just SSE / SSE2.
+///
+/// Code outside `#[target_feature]` functions may use these instructions
+/// anywhere, so a CPU lacking one crashes with an illegal instruction - pass
+/// the result to [`check_required_features`] at startup, before the hot code
+/// runs.
+pub fn compiled_features() -> CpuFeatures {
+    CpuFeatures::compiled()
+}
+
+/// `Ok` if the running CPU has every flag in `required`, for a clear startup
+/// error instead of an illegal-instruction crash later.
+///
+/// # Errors
+///
+/// [`Error::Unsupported`] naming each missing flag (`"CPU lacks required
+/// features: AVX2, FMA3"`), or the detection error if the CPU's features
+/// could not be read. Detection is shared with [`has_feature`].
+///
+/// # Example
+///
+/// ```
+/// // Refuse to start rather than die on the first AVX2 instruction.
+/// if let Err(e) = gdt_cpus::check_required_features(gdt_cpus::compiled_features()) {
+///     eprintln!("this build does not run on this CPU: {e}");
+///     std::process::exit(1);
+/// }
+/// ```
+pub fn check_required_features(required: CpuFeatures) -> Result<()> {
+    missing_features(required, detected_features()?)
+}
+
+fn missing_features(required: CpuFeatures, detected: CpuFeatures) -> Result<()> {
+    let missing = required.difference(detected);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let names: Vec<&str> = missing.iter_names().map(|(name, _)| name).collect();
+    Err(Error::Unsupported(
+        format!("CPU lacks required features: {}", names.join(", ")).into(),
+    ))
+}
+
+/// [`CpuInfo::features`] of this machine, detected once per process.
+fn detected_features() -> Result<CpuFeatures> {
+    static FEATURES: std::sync::OnceLock<CpuFeatures> = std::sync::OnceLock::new();
+
+    cache_success(&FEATURES, || CpuInfo::detect().map(|info| info.features))
+}
+
+/// The value in `cell`, or the result of `detect` stored there if it
+/// succeeds. A failure is returned and NOT stored - the next call runs
+/// `detect` again. Racing first calls may each detect; one value wins.
+fn cache_success<T: Copy>(
+    cell: &std::sync::OnceLock<T>,
+    detect: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    if let Some(value) = cell.get() {
+        return Ok(*value);
+    }
+    detect().map(|value| *cell.get_or_init(|| value))
+}
+
+/// Whether the machine is running on battery power, for deciding when to
+/// move background work onto [`CpuInfo::battery_saver_mask`].
+///
+/// Read fresh on every call (the answer changes when a cable is pulled):
+///
+/// - **Linux**: `/sys/class/power_supply` - `Some(true)` when a system
+///   battery is discharging and no adapter is online. Peripheral batteries
+///   (`scope` = `Device`) are ignored.
+/// - **Windows**: `GetSystemPowerStatus`'s AC line status.
+///
+/// Machines without a battery answer `Some(false)`. `None` means unknown:
+/// the source is unreadable (e.g. a container without the sysfs class), the
+/// OS reports an unknown line status, or the platform (including macOS) has
+/// no backend yet.
+pub fn is_on_battery() -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        platform::linux::power::on_battery()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        platform::windows::power::on_battery()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// The raw `(leaf, subleaf, [eax, ebx, ecx, edx])` of every cpuid leaf
+/// detection reads - a DIAGNOSTIC to attach to bug reports about wrong
+/// detection, not an interface to decode features from (use [`CpuInfo`]).
+///
+/// Covers leaves 0, 1, 4 (each cache subleaf), 7, 0x15 and 0x16, then
+/// 0x8000_0000, the brand string (0x8000_0002-4), 0x8000_0007 and AMD's
+/// 0x8000_001D cache subleaves, skipping leaves above the reported maxima.
+/// Registers come from whichever LP the calling thread runs on, so per-LP
+/// fields (APIC ids) differ between calls.
+#[cfg(all(feature = "diagnostics", target_arch = "x86_64"))]
+pub fn dump_cpuid_leaves() -> Vec<(u32, u32, [u32; 4])> {
+    platform::common_x86_64::dump_cpuid_leaves()
+}
+
+/// Whether CPU turbo / boost is currently enabled, for benchmark harnesses
+/// that want to warn about (or refuse) runs with a floating clock.
+///
+/// Read fresh on every call. On **Linux**, from
+/// `/sys/devices/system/cpu/intel_pstate/no_turbo` (inverted) or, without
+/// `intel_pstate`, `/sys/devices/system/cpu/cpufreq/boost`. `Ok(None)` when
+/// neither switch exists - VMs, and drivers with no boost control.
+///
+/// # Errors
+///
+/// [`Error::Unsupported`] on other platforms.
+pub fn is_turbo_enabled() -> Result<Option<bool>> {
+    #[cfg(target_os = "linux")]
+    {
+        Ok(platform::linux::turbo::turbo_enabled())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(Error::Unsupported(
+            "Reading the turbo state is not supported on this platform.".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_detection_is_retried_not_cached() {
+        let cell = std::sync::OnceLock::new();
+        let mut calls = 0;
+        let mut flaky = || {
+            calls += 1;
+            if calls == 1 {
+                Err(Error::Detection("sysfs read interrupted".into()))
+            } else {
+                Ok(CpuFeatures::SSE2)
+            }
+        };
+
+        assert!(cache_success(&cell, &mut flaky).is_err());
+        assert_eq!(
+            cache_success(&cell, &mut flaky).ok(),
+            Some(CpuFeatures::SSE2)
+        );
+        // Cached now: the detector is not run again.
+        assert_eq!(
+            cache_success(&cell, &mut flaky).ok(),
+            Some(CpuFeatures::SSE2)
+        );
+        assert_eq!(calls, 2);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn missing_features_are_named() {
+        let detected = CpuFeatures::SSE2 | CpuFeatures::AVX2;
+
+        assert!(missing_features(CpuFeatures::AVX2, detected).is_ok());
+        let Err(Error::Unsupported(message)) = missing_features(
+            CpuFeatures::AVX2 | CpuFeatures::FMA3 | CpuFeatures::BMI2,
+            detected,
+        ) else {
+            panic!("missing flags must be Unsupported");
+        };
+        assert_eq!(message, "CPU lacks required features: FMA3, BMI2");
+    }
+
+    #[test]
+    fn this_binary_runs_on_this_cpu() {
+        // Running at all proves the CPU has what the build assumed.
+        check_required_features(compiled_features()).unwrap();
+        #[cfg(target_arch = "x86_64")]
+        assert!(compiled_features().contains(CpuFeatures::SSE2));
+    }
+}
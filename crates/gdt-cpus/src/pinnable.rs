@@ -0,0 +1,270 @@
+//! Affinity and priority for threads the caller does not run on.
+//!
+//! The free functions in [`crate::set_thread_affinity`] & co. only touch the
+//! CALLING thread, which is awkward for pool builders that spawn workers
+//! through someone else's API (rayon's `spawn_handler`, an engine job system,
+//! a C library handing back a handle). [`PinnableThread`] is the hook such a
+//! builder accepts: [`CurrentThread`] applies to the caller, [`RawThread`] to
+//! a thread named by its OS id or handle, and tests can plug in a mock to
+//! check which placement a pool decided on without touching the scheduler.
+
+use crate::{AffinityMask, AppliedPriority, Result, ThreadPriority};
+
+/// A thread whose hard affinity and priority can be set.
+///
+/// Implement it to route placement through your own thread abstraction;
+/// [`configure_thread`] drives any implementor.
+pub trait PinnableThread {
+    /// Sets the thread's hard CPU affinity to `mask` (OS LP ids).
+    fn apply_affinity(&self, mask: &AffinityMask) -> Result<()>;
+
+    /// Sets the thread's scheduling priority, reporting what was actually
+    /// applied (see [`crate::set_thread_priority`]).
+    fn apply_priority(&self, priority: ThreadPriority) -> Result<AppliedPriority>;
+}
+
+/// The calling thread - forwards to [`crate::set_thread_affinity`] and
+/// [`crate::set_thread_priority`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CurrentThread;
+
+impl PinnableThread for CurrentThread {
+    fn apply_affinity(&self, mask: &AffinityMask) -> Result<()> {
+        crate::set_thread_affinity(mask)
+    }
+
+    fn apply_priority(&self, priority: ThreadPriority) -> Result<AppliedPriority> {
+        crate::set_thread_priority(priority)
+    }
+}
+
+/// Any thread of this process, by OS identity: a kernel TID on Linux, a
+/// thread `HANDLE` on Windows.
+///
+/// Does not own the thread or the handle - the caller keeps it alive for as
+/// long as the `RawThread` is used. Unsupported elsewhere: macOS QoS can only
+/// be set by a thread on itself, so every call returns
+/// [`crate::Error::Unsupported`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawThread {
+    raw: usize,
+}
+
+impl RawThread {
+    /// Wraps a kernel thread id (`gettid()`, `/proc/self/task/<tid>`).
+    #[cfg(target_os = "linux")]
+    pub fn from_tid(tid: i32) -> Self {
+        Self { raw: tid as usize }
+    }
+
+    /// The calling thread's kernel id - hand it to the thread that will do
+    /// the configuring.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::Error::SystemCall`] if `gettid` fails.
+    #[cfg(target_os = "linux")]
+    pub fn current() -> Result<Self> {
+        crate::platform::linux::affinity::current_tid().map(|tid| Self::from_tid(tid as i32))
+    }
+
+    /// Wraps a thread handle (`JoinHandle::as_raw_handle`, `OpenThread`).
+    /// Affinity needs `THREAD_SET_INFORMATION` + `THREAD_QUERY_INFORMATION`
+    /// access, priority `THREAD_SET_LIMITED_INFORMATION`.
+    #[cfg(target_os = "windows")]
+    pub fn from_raw_handle(handle: std::os::windows::io::RawHandle) -> Self {
+        Self {
+            raw: handle as usize,
+        }
+    }
+}
+
+impl PinnableThread for RawThread {
+    fn apply_affinity(&self, mask: &AffinityMask) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            crate::platform::linux::affinity::set_affinity_of(self.raw as libc::pid_t, mask)
+        }
+        #[cfg(target_os = "windows")]
+        {
+            crate::platform::windows::affinity::set_affinity_of(
+                windows::Win32::Foundation::HANDLE(self.raw as *mut core::ffi::c_void),
+                mask,
+            )
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        {
+            let _ = mask;
+            Err(crate::Error::Unsupported(
+                "Affinity of another thread is not supported on this platform.".into(),
+            ))
+        }
+    }
+
+    fn apply_priority(&self, priority: ThreadPriority) -> Result<AppliedPriority> {
+        #[cfg(target_os = "linux")]
+        {
+            crate::platform::linux::affinity::set_priority_of(self.raw as libc::id_t, priority)
+        }
+        #[cfg(target_os = "windows")]
+        {
+            crate::platform::windows::affinity::set_priority_of(
+                windows::Win32::Foundation::HANDLE(self.raw as *mut core::ffi::c_void),
+                priority,
+            )
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        {
+            let _ = priority;
+            Err(crate::Error::Unsupported(
+                "Priority of another thread is not supported on this platform.".into(),
+            ))
+        }
+    }
+}
+
+/// Applies `affinity`, then `priority`, to `thread`, skipping whichever is
+/// `None`.
+///
+/// Affinity goes first so a priority boost never lands while the thread can
+/// still run on cores it is about to leave. Stops at the first error.
+///
+/// # Returns
+///
+/// The [`AppliedPriority`] when a priority was requested, `None` otherwise.
+///
+/// # Example
+///
+/// ```
+/// use gdt_cpus::{CpuInfo, CurrentThread, ThreadPriority, configure_thread};
+///
+/// if let Ok(info) = CpuInfo::detect() {
+///     let mask = info.performance_core_mask();
+///     let _ = configure_thread(&CurrentThread, Some(&mask), Some(ThreadPriority::AboveNormal));
+/// }
+/// ```
+pub fn configure_thread<T: PinnableThread + ?Sized>(
+    thread: &T,
+    affinity: Option<&AffinityMask>,
+    priority: Option<ThreadPriority>,
+) -> Result<Option<AppliedPriority>> {
+    if let Some(mask) = affinity {
+        thread.apply_affinity(mask)?;
+    }
+
+    priority.map(|p| thread.apply_priority(p)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Grant, Mechanism, MechanismPolicy};
+    use std::cell::RefCell;
+
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Affinity(Vec<usize>),
+        Priority(ThreadPriority),
+    }
+
+    #[derive(Default)]
+    struct MockThread {
+        calls: RefCell<Vec<Call>>,
+        fail_affinity: bool,
+    }
+
+    impl PinnableThread for MockThread {
+        fn apply_affinity(&self, mask: &AffinityMask) -> Result<()> {
+            self.calls
+                .borrow_mut()
+                .push(Call::Affinity(mask.iter().collect()));
+            if self.fail_affinity {
+                return Err(Error::PermissionDenied("mock".into()));
+            }
+            Ok(())
+        }
+
+        fn apply_priority(&self, priority: ThreadPriority) -> Result<AppliedPriority> {
+            self.calls.borrow_mut().push(Call::Priority(priority));
+            Ok(AppliedPriority::new(
+                priority,
+                priority,
+                Grant::Direct,
+                Mechanism {
+                    policy: MechanismPolicy::Nice,
+                    value: 0,
+                },
+            ))
+        }
+    }
+
+    #[test]
+    fn configure_applies_affinity_before_priority() {
+        let thread = MockThread::default();
+        let mask = AffinityMask::from_cores(&[2, 3]);
+
+        let applied = configure_thread(&thread, Some(&mask), Some(ThreadPriority::Highest))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(applied.requested(), ThreadPriority::Highest);
+        assert_eq!(
+            *thread.calls.borrow(),
+            [
+                Call::Affinity(vec![2, 3]),
+                Call::Priority(ThreadPriority::Highest)
+            ]
+        );
+    }
+
+    #[test]
+    fn configure_skips_unset_parts() {
+        let thread = MockThread::default();
+
+        assert!(configure_thread(&thread, None, None).unwrap().is_none());
+        assert!(thread.calls.borrow().is_empty());
+
+        let dyn_thread: &dyn PinnableThread = &thread;
+        configure_thread(dyn_thread, None, Some(ThreadPriority::Lowest)).unwrap();
+        assert_eq!(
+            *thread.calls.borrow(),
+            [Call::Priority(ThreadPriority::Lowest)]
+        );
+    }
+
+    #[test]
+    fn configure_stops_at_affinity_error() {
+        let thread = MockThread {
+            fail_affinity: true,
+            ..Default::default()
+        };
+        let mask = AffinityMask::single(0);
+
+        let result = configure_thread(&thread, Some(&mask), Some(ThreadPriority::Highest));
+
+        assert!(matches!(result, Err(Error::PermissionDenied(_))));
+        assert_eq!(*thread.calls.borrow(), [Call::Affinity(vec![0])]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn raw_thread_configures_another_thread() {
+        let allowed = crate::current_affinity().unwrap();
+        let first = allowed.iter().next().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let worker = std::thread::spawn(move || {
+            tx.send(RawThread::current().unwrap()).unwrap();
+            done_rx.recv().unwrap();
+            crate::current_affinity().unwrap()
+        });
+
+        let raw = rx.recv().unwrap();
+        raw.apply_affinity(&AffinityMask::single(first)).unwrap();
+        done_tx.send(()).unwrap();
+
+        let seen = worker.join().unwrap();
+        assert_eq!(seen.iter().collect::<Vec<_>>(), [first]);
+    }
+}
@@ -21,6 +21,9 @@
 #[cfg(target_arch = "x86_64")]
 pub(crate) mod common_x86_64;
 
+#[cfg(target_arch = "aarch64")]
+pub(crate) mod common_aarch64;
+
 // Production consumer is Linux (sysfs range lists); the shared fixture
 // checker uses it in test builds on every platform.
 #[cfg(any(target_os = "linux", test))]
@@ -31,6 +34,9 @@ pub(crate) mod ranges;
 #[cfg(test)]
 pub(crate) mod fixture_expected;
 
+// Per-phase detection timing; a no-op unless the `log` feature is enabled.
+pub(crate) mod timing;
+
 #[cfg(target_os = "linux")]
 pub(crate) mod linux;
 
@@ -43,7 +49,77 @@ pub(crate) mod macos;
 #[cfg(target_os = "windows")]
 pub(crate) mod windows;
 
+// Counts-only detection; like `macos`, the assembly is pure logic that also
+// compiles for tests elsewhere.
+#[cfg(any(target_os = "openbsd", target_os = "netbsd", test))]
+pub(crate) mod bsd;
+
 // NOTE: priority mapping is an internal detail since 26.x - each platform's
 // table lives in its own scheduling_policy module and is consumed only by
 // that platform's set_thread_priority. There is no public SchedulingPolicy
 // type anymore (nothing outside the crate ever consumed it).
+
+/// Invariant-TSC flag and counter frequency (Hz) of the running CPU - the
+/// same user-mode instructions on every OS, so detection backends share it.
+pub(crate) fn detect_timestamp_counter() -> (bool, Option<u64>) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        common_x86_64::detect_timestamp_counter()
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        (false, common_aarch64::detect_generic_timer_frequency())
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        (false, None)
+    }
+}
+
+/// [`crate::CpuInfo::x86_signature`] of the running CPU (x86 only).
+pub(crate) fn detect_x86_signature() -> Option<(u8, u8)> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        common_x86_64::detect_signature()
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        None
+    }
+}
+
+/// [`crate::CpuInfo::avx512_downclock_risk`] of the running CPU (x86 only).
+pub(crate) fn detect_avx512_downclock_risk(vendor: crate::Vendor) -> Option<bool> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        common_x86_64::detect_avx512_downclock_risk(vendor)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = vendor;
+        None
+    }
+}
+
+/// Fills [`crate::CacheInfo::inclusive`] on the per-kind L1d / L1i / L2 from
+/// cpuid (x86 only). Left `None` on hybrid machines: cpuid describes the core
+/// the detecting thread happens to run on, not every kind. Caches that were
+/// not detected (size 0) stay `None` too.
+pub(crate) fn fill_cache_inclusiveness(info: &mut crate::CpuInfo) {
+    #[cfg(target_arch = "x86_64")]
+    if !info.is_hybrid() {
+        let [l1d, l1i, l2] = common_x86_64::detect_cache_inclusiveness();
+
+        for (caches, inclusive) in [
+            (&mut info.l1d, l1d),
+            (&mut info.l1i, l1i),
+            (&mut info.l2, l2),
+        ] {
+            for cache in caches.iter_mut().filter(|c| c.size_bytes != 0) {
+                cache.inclusive = inclusive;
+            }
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = info;
+}
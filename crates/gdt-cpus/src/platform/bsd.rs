@@ -0,0 +1,221 @@
+//! OpenBSD / NetBSD: counts-only CPU detection, no thread control.
+//!
+//! Neither kernel tells user space how LPs map onto cores, caches or core
+//! kinds, and OpenBSD deliberately has no thread affinity API at all - its
+//! scheduler alone places threads. Detection therefore reports what sysctl
+//! does know: the online LP count (`hw.ncpuonline`, else `hw.ncpu`), each LP
+//! assumed to be its own Performance core, plus vendor/model/features from
+//! cpuid on x86_64 (`hw.model` elsewhere). The assumption is recorded in
+//! [`CpuInfo::detection_warnings`]. Thread control returns
+//! [`crate::Error::Unsupported`] through the generic fallbacks.
+//!
+//! [`build_cpu_info`] is pure logic and compiles for tests on every platform;
+//! only the sysctl reads are BSD-gated.
+
+use crate::{CacheInfo, CoreKind, CpuFeatures, CpuInfo, Lp, Vendor};
+
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+use crate::{Error, Result};
+
+// `<sys/sysctl.h>` second-level names; libc does not export them for these
+// targets. HW_NCPUONLINE differs between the two kernels.
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+const HW_MODEL: libc::c_int = 2;
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+const HW_NCPU: libc::c_int = 3;
+#[cfg(target_os = "openbsd")]
+const HW_NCPUONLINE: libc::c_int = 25;
+#[cfg(target_os = "netbsd")]
+const HW_NCPUONLINE: libc::c_int = 16;
+
+/// Detects CPU information on OpenBSD / NetBSD (live sysctl).
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+pub(crate) fn detect_cpu_info() -> Result<CpuInfo> {
+    let online = sysctl_hw_int(HW_NCPUONLINE)
+        .or_else(|| sysctl_hw_int(HW_NCPU))
+        .filter(|&n| n > 0)
+        .ok_or_else(|| Error::Detection("hw.ncpuonline / hw.ncpu unavailable".into()))?;
+
+    #[cfg_attr(not(target_arch = "x86_64"), allow(unused_mut))]
+    let mut vendor = Vendor::Unknown;
+    let mut model_name = sysctl_hw_string(HW_MODEL).unwrap_or_else(|| "Unknown".to_string());
+    #[cfg_attr(not(target_arch = "x86_64"), allow(unused_mut))]
+    let mut features = CpuFeatures::default();
+
+    #[cfg(target_arch = "x86_64")]
+    crate::platform::common_x86_64::detect_via_cpuid(&mut vendor, &mut model_name, &mut features);
+
+    // Before assembly: finalize estimates cache latencies from it.
+    let x86_signature = crate::platform::detect_x86_signature();
+    let mut info = build_cpu_info(online as usize, vendor, model_name, features, x86_signature);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        (info.base_frequency_mhz, info.max_frequency_mhz) =
+            crate::platform::common_x86_64::detect_frequency_via_cpuid();
+        info.clflush_line_size = crate::platform::common_x86_64::detect_clflush_line_size();
+    }
+    (info.has_invariant_tsc, info.tsc_frequency_hz) = crate::platform::detect_timestamp_counter();
+    info.avx512_downclock_risk = crate::platform::detect_avx512_downclock_risk(info.vendor);
+
+    Ok(info)
+}
+
+/// Reads an integer `hw.*` sysctl by MIB.
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+fn sysctl_hw_int(name: libc::c_int) -> Option<u32> {
+    let mib = [libc::CTL_HW, name];
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>();
+
+    // SAFETY: valid two-level MIB; `value` is a live c_int and `len` its size.
+    let res = unsafe {
+        libc::sysctl(
+            mib.as_ptr(),
+            mib.len() as libc::c_uint,
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+            std::ptr::null_mut::<libc::c_void>(),
+            0,
+        )
+    };
+
+    (res == 0).then(|| value.max(0) as u32)
+}
+
+/// Reads a string `hw.*` sysctl by MIB.
+#[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+fn sysctl_hw_string(name: libc::c_int) -> Option<String> {
+    let mib = [libc::CTL_HW, name];
+    let mut buf = [0u8; 256];
+    let mut len = buf.len();
+
+    // SAFETY: valid two-level MIB; the kernel writes at most `len` bytes.
+    let res = unsafe {
+        libc::sysctl(
+            mib.as_ptr(),
+            mib.len() as libc::c_uint,
+            buf.as_mut_ptr() as *mut libc::c_void,
+            &mut len,
+            std::ptr::null_mut::<libc::c_void>(),
+            0,
+        )
+    };
+    if res != 0 {
+        return None;
+    }
+
+    let bytes = &buf[..len.min(buf.len())];
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let model = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+
+    (!model.is_empty()).then_some(model)
+}
+
+/// Assembles the counts-only [`CpuInfo`]: `online` LPs with dense ids, one
+/// Performance core each, one socket, no caches.
+pub(crate) fn build_cpu_info(
+    online: usize,
+    vendor: Vendor,
+    model_name: String,
+    features: CpuFeatures,
+    x86_signature: Option<(u8, u8)>,
+) -> CpuInfo {
+    let lps: Vec<Lp> = (0..online)
+        .map(|i| Lp {
+            os_id: i as u16,
+            core: i as u16,
+            socket: 0,
+            l3_domain: Lp::NO_L3,
+            l2_domain: Lp::NO_L2,
+            numa_node: 0,
+            kind: CoreKind::Performance,
+            smt_index: 0,
+            perf_hint: 0,
+            cpu_part: 0,
+            native_core_id: None,
+            capacity: None,
+            freq_min_mhz: None,
+            freq_max_mhz: None,
+        })
+        .collect();
+
+    let mut kind_core_counts = [0u16; CoreKind::COUNT];
+    kind_core_counts[CoreKind::Performance.index()] = online as u16;
+
+    let mut info = CpuInfo {
+        lps,
+        core_count: online as u16,
+        socket_count: 1,
+        numa_node_count: 1,
+        smt_supported: false,
+        kind_core_counts,
+        l3_domains: Vec::new(),
+        l2_domains: Vec::new(),
+        l1d: [CacheInfo::default(); CoreKind::COUNT],
+        l1i: [CacheInfo::default(); CoreKind::COUNT],
+        l2: [CacheInfo::default(); CoreKind::COUNT],
+        vendor,
+        model_name,
+        features,
+        base_frequency_mhz: None,
+        max_frequency_mhz: None,
+        clflush_line_size: None,
+        has_invariant_tsc: false,
+        tsc_frequency_hz: None,
+        microcode: None,
+        tdp_watts: None,
+        x86_signature,
+        avx512_downclock_risk: None,
+        detection_warnings: vec![
+            "topology unavailable: one core per logical processor assumed".to_string(),
+        ],
+        logical_processor_ids: Default::default(),
+        schema_version: CpuInfo::SCHEMA_VERSION,
+    };
+    info.finalize(&crate::DetectOptions::FULL);
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_info_is_valid() {
+        let info = build_cpu_info(
+            4,
+            Vendor::Unknown,
+            "Unknown".to_string(),
+            CpuFeatures::empty(),
+            None,
+        );
+
+        info.validate().unwrap();
+        assert_eq!(info.num_logical_cores(), 4);
+        assert_eq!(info.num_physical_cores(), 4);
+        assert_eq!(info.num_performance_cores(), 4);
+        assert_eq!(info.logical_processor_ids(), [0, 1, 2, 3]);
+        assert!(!info.is_hybrid());
+        assert!(!info.has_complete_topology());
+        assert!(
+            info.detection_warnings
+                .iter()
+                .any(|w| w == "cache info unavailable")
+        );
+    }
+
+    #[cfg(any(target_os = "openbsd", target_os = "netbsd"))]
+    #[test]
+    fn live_detection_reports_counts() {
+        let info = CpuInfo::detect().unwrap();
+
+        assert!(info.num_logical_cores() >= 1);
+        assert_eq!(info.num_physical_cores(), info.num_logical_cores());
+        #[cfg(feature = "affinity")]
+        assert!(matches!(
+            crate::pin_thread_to_core(0),
+            Err(crate::Error::Unsupported(_))
+        ));
+    }
+}
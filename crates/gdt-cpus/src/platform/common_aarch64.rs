@@ -0,0 +1,30 @@
+//! Common aarch64 detection logic: registers every OS lets user mode read.
+
+/// Reads the generic timer frequency (Hz) from `CNTFRQ_EL0` - the rate of
+/// the `CNTVCT_EL0` virtual counter. `None` when firmware left it zero.
+pub(crate) fn detect_generic_timer_frequency() -> Option<u64> {
+    let frequency: u64;
+
+    // SAFETY: CNTFRQ_EL0 is readable at EL0 wherever the OS exposes the
+    // virtual counter (Linux, macOS, Windows all do); no memory is touched.
+    unsafe {
+        core::arch::asm!("mrs {}, cntfrq_el0", out(reg) frequency, options(nomem, nostack));
+    }
+
+    (frequency != 0).then_some(frequency)
+}
+
+/// Reads the cache type register `CTR_EL0`: `DminLine` (bits 19:16) and
+/// `IminLine` (bits 3:0) give the smallest data and instruction cache line
+/// in the hierarchy - in practice the L1 lines - as log2 of 4-byte words.
+pub(crate) fn read_cache_type_register() -> u64 {
+    let ctr: u64;
+
+    // SAFETY: CTR_EL0 is readable at EL0 on macOS and Windows, and Linux
+    // emulates the read when it traps; no memory is touched.
+    unsafe {
+        core::arch::asm!("mrs {}, ctr_el0", out(reg) ctr, options(nomem, nostack));
+    }
+
+    ctr
+}
@@ -16,6 +16,9 @@ use crate::{CpuFeatures, Vendor};
 /// the presence of common x86_64 instruction set extensions like MMX, SSE,
 /// AVX, AVX2, AVX512, AES, SHA, etc.
 ///
+/// AVX-family flags are reported only when the OS has also enabled their
+/// register state in XCR0 - i.e. when they are usable, not merely present.
+///
 /// The detected features are added to the mutable `features` argument.
 ///
 /// # Arguments
@@ -96,9 +99,80 @@ pub(crate) fn detect_features_via_cpuid(features: &mut CpuFeatures) {
         if ext_fi.has_bmi2() {
             features.insert(CpuFeatures::BMI2);
         }
+        features.insert(tsx_features_from_leaf7_ebx(raw_cpuid::cpuid!(7, 0).ebx));
+    }
+
+    // CPUID says what the silicon can do; XCR0 says which register state the
+    // OS actually saves on context switch. A hypervisor or kernel
+    // (`noxsave`, `clearcpuid=`) can leave YMM/ZMM disabled while CPUID still
+    // reports AVX - executing AVX then faults with #UD.
+    let xcr0 = cpuid
+        .get_feature_info()
+        .is_some_and(|fi| fi.has_oxsave())
+        .then(read_xcr0);
+    mask_os_disabled_vector_features(features, xcr0);
+}
+
+/// Decodes RTM (bit 11) and HLE (bit 4) from cpuid leaf 7 sub-leaf 0 EBX.
+///
+/// Only what CPUID reports: microcode that disables TSX (`TSX_CTRL`) may or
+/// may not clear these bits, see [`CpuFeatures::RTM`].
+pub(crate) fn tsx_features_from_leaf7_ebx(ebx: u32) -> CpuFeatures {
+    let mut features = CpuFeatures::empty();
+    features.set(CpuFeatures::RTM, ebx & (1 << 11) != 0);
+    features.set(CpuFeatures::HLE, ebx & (1 << 4) != 0);
+    features
+}
+
+/// XCR0 bits 1-2: SSE (XMM) and AVX (upper YMM) state.
+const XCR0_AVX_STATE: u64 = 0b110;
+
+/// XCR0 bits 5-7 (opmask, ZMM_Hi256, Hi16_ZMM) on top of [`XCR0_AVX_STATE`].
+const XCR0_AVX512_STATE: u64 = 0b1110_0000 | XCR0_AVX_STATE;
+
+/// Clears vector features whose register state the OS has not enabled.
+///
+/// `xcr0` is `None` when CPUID reports no OSXSAVE (the OS never turned XSAVE
+/// on, so no VEX/EVEX state is usable). FMA3 and F16C are VEX-encoded and go
+/// with AVX.
+pub(crate) fn mask_os_disabled_vector_features(features: &mut CpuFeatures, xcr0: Option<u64>) {
+    let xcr0 = xcr0.unwrap_or(0);
+
+    if xcr0 & XCR0_AVX_STATE != XCR0_AVX_STATE {
+        features
+            .remove(CpuFeatures::AVX | CpuFeatures::AVX2 | CpuFeatures::FMA3 | CpuFeatures::F16C);
+    }
+    if xcr0 & XCR0_AVX512_STATE != XCR0_AVX512_STATE {
+        features.remove(
+            CpuFeatures::AVX512F
+                | CpuFeatures::AVX512BW
+                | CpuFeatures::AVX512CD
+                | CpuFeatures::AVX512DQ
+                | CpuFeatures::AVX512VL,
+        );
     }
 }
 
+/// Reads XCR0 with `XGETBV`. Only call when CPUID reports OSXSAVE - the
+/// instruction raises #UD otherwise.
+fn read_xcr0() -> u64 {
+    let (lo, hi): (u32, u32);
+
+    // SAFETY: XGETBV with ECX=0 reads XCR0 and has no side effects; the
+    // caller guarantees OSXSAVE (CR4.OSXSAVE set), so it cannot fault.
+    unsafe {
+        std::arch::asm!(
+            "xgetbv",
+            in("ecx") 0,
+            out("eax") lo,
+            out("edx") hi,
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+
+    (u64::from(hi) << 32) | u64::from(lo)
+}
+
 /// Detects CPU vendor, model name, and features on x86_64 using `cpuid`.
 ///
 /// This function populates the provided mutable references with the CPU vendor
@@ -142,3 +216,339 @@ pub(crate) fn detect_via_cpuid(
 
     detect_features_via_cpuid(features);
 }
+
+/// Reads the base and maximum clock (MHz) from `cpuid` leaf 0x16.
+///
+/// Intel-only in practice (Skylake onward); AMD parts and most hypervisors
+/// leave the leaf absent or zeroed, which yields `(None, None)`.
+pub(crate) fn detect_frequency_via_cpuid() -> (Option<u32>, Option<u32>) {
+    let cpuid = raw_cpuid::CpuId::new();
+
+    let Some(fi) = cpuid.get_processor_frequency_info() else {
+        return (None, None);
+    };
+
+    let nonzero = |mhz: u16| (mhz != 0).then_some(u32::from(mhz));
+
+    (
+        nonzero(fi.processor_base_frequency()),
+        nonzero(fi.processor_max_frequency()),
+    )
+}
+
+/// Decodes the `CLFLUSH` line size from cpuid leaf 1 EBX: bits 8-15 count
+/// 8-byte units. `None` when the field is zero (not reported).
+pub(crate) fn clflush_line_size_from_ebx(ebx: u32) -> Option<u16> {
+    let units = ((ebx >> 8) & 0xFF) as u16;
+
+    (units != 0).then_some(units * 8)
+}
+
+/// Reads the `CLFLUSH` line size (bytes) from cpuid leaf 1.
+pub(crate) fn detect_clflush_line_size() -> Option<u16> {
+    clflush_line_size_from_ebx(raw_cpuid::cpuid!(1).ebx)
+}
+
+/// Inclusiveness of the L1d, L1i and L2 from the deterministic cache
+/// parameters leaf (Intel 4, AMD 0x8000_001D) - EDX bit 1, "inclusive of
+/// lower cache levels". `None` for a level the leaf does not list.
+pub(crate) fn detect_cache_inclusiveness() -> [Option<bool>; 3] {
+    let mut inclusive = [None; 3];
+    let Some(params) = raw_cpuid::CpuId::new().get_cache_parameters() else {
+        return inclusive;
+    };
+
+    for cache in params {
+        let slot = match (cache.level(), cache.cache_type()) {
+            (1, raw_cpuid::CacheType::Data) => 0,
+            (1, raw_cpuid::CacheType::Instruction) => 1,
+            (2, _) => 2,
+            _ => continue,
+        };
+        inclusive[slot] = Some(cache.is_inclusive());
+    }
+    inclusive
+}
+
+/// The AVX-512 frequency-license heuristic behind
+/// [`crate::CpuInfo::avx512_downclock_risk`], keyed on the display family /
+/// model (extended fields folded in, as `raw_cpuid` reports them).
+pub(crate) fn avx512_downclock_risk(vendor: Vendor, family: u8, model: u8) -> Option<bool> {
+    match (vendor, family) {
+        (Vendor::Intel, 6) => match model {
+            // Skylake-SP/X/W, Cascade Lake, Cooper Lake (all model 0x55);
+            // Cannon Lake.
+            0x55 | 0x66 => Some(true),
+            // Ice Lake (SP, D, client), Tiger Lake, Rocket Lake, Sapphire /
+            // Emerald / Granite Rapids: the license drops shrank to noise.
+            0x6A | 0x6C | 0x7D | 0x7E | 0x8C | 0x8D | 0xA7 | 0x8F | 0xCF | 0xAD | 0xAE => {
+                Some(false)
+            }
+            // Alder / Raptor / Meteor / Arrow / Lunar Lake: AVX-512 fused off.
+            0x97 | 0x9A | 0xB7 | 0xBA | 0xBF | 0xAA | 0xAC | 0xC5 | 0xC6 | 0xBD => Some(false),
+            _ => None,
+        },
+        // Zen: no AVX-512 before Zen 4, no frequency license since.
+        (Vendor::Amd, 0x17..) => Some(false),
+        _ => None,
+    }
+}
+
+/// The display `(family, model)` of the running CPU (cpuid leaf 1).
+pub(crate) fn detect_signature() -> Option<(u8, u8)> {
+    let info = raw_cpuid::CpuId::new().get_feature_info()?;
+    Some((info.family_id(), info.model_id()))
+}
+
+/// [`avx512_downclock_risk`] for the running CPU (cpuid leaf 1).
+pub(crate) fn detect_avx512_downclock_risk(vendor: Vendor) -> Option<bool> {
+    let (family, model) = detect_signature()?;
+    avx512_downclock_risk(vendor, family, model)
+}
+
+/// Decodes the invariant-TSC flag from cpuid leaf 0x8000_0007 EDX (bit 8).
+pub(crate) fn invariant_tsc_from_edx(edx: u32) -> bool {
+    edx & (1 << 8) != 0
+}
+
+/// Decodes the TSC frequency (Hz) from cpuid leaf 0x15: EAX/EBX are the
+/// TSC / crystal ratio, ECX the crystal clock in Hz. `None` when any of them
+/// is zero (ECX is, on AMD and Skylake-era client Intel).
+pub(crate) fn tsc_frequency_from_leaf15(eax: u32, ebx: u32, ecx: u32) -> Option<u64> {
+    if eax == 0 || ebx == 0 || ecx == 0 {
+        return None;
+    }
+
+    Some(u64::from(ecx) * u64::from(ebx) / u64::from(eax))
+}
+
+/// Reads the invariant-TSC flag and the leaf-0x15 TSC frequency, checking
+/// the maximum basic / extended leaf first (reading past it returns the
+/// highest leaf's data on Intel, not zeros).
+pub(crate) fn detect_timestamp_counter() -> (bool, Option<u64>) {
+    let invariant = raw_cpuid::cpuid!(0x8000_0000).eax >= 0x8000_0007
+        && invariant_tsc_from_edx(raw_cpuid::cpuid!(0x8000_0007).edx);
+
+    let frequency = if raw_cpuid::cpuid!(0).eax >= 0x15 {
+        let leaf = raw_cpuid::cpuid!(0x15);
+        tsc_frequency_from_leaf15(leaf.eax, leaf.ebx, leaf.ecx)
+    } else {
+        None
+    };
+
+    (invariant, frequency)
+}
+
+/// Raw `(leaf, subleaf, [eax, ebx, ecx, edx])` of every leaf the detection
+/// above reads, in leaf order - the body of [`crate::dump_cpuid_leaves`].
+///
+/// Leaves above the reported maximum basic / extended leaf are skipped (on
+/// Intel they echo the highest leaf). The cache-parameter leaves (4, and
+/// 0x8000_001D on AMD) are walked until the null cache type, at most 16
+/// subleaves in case a hypervisor never reports one.
+#[cfg(feature = "diagnostics")]
+pub(crate) fn dump_cpuid_leaves() -> Vec<(u32, u32, [u32; 4])> {
+    const MAX_CACHE_SUBLEAVES: u32 = 16;
+
+    let mut leaves = Vec::new();
+    let mut read = |leaf: u32, subleaf: u32| {
+        let r = raw_cpuid::cpuid!(leaf, subleaf);
+        leaves.push((leaf, subleaf, [r.eax, r.ebx, r.ecx, r.edx]));
+        r.eax
+    };
+    let read_caches = |read: &mut dyn FnMut(u32, u32) -> u32, leaf: u32| {
+        for subleaf in 0..MAX_CACHE_SUBLEAVES {
+            if read(leaf, subleaf) & 0x1F == 0 {
+                break;
+            }
+        }
+    };
+
+    let max_basic = read(0, 0);
+    for leaf in [1, 4, 7, 0x15, 0x16] {
+        match leaf {
+            _ if leaf > max_basic => break,
+            4 => read_caches(&mut read, 4),
+            _ => {
+                read(leaf, 0);
+            }
+        }
+    }
+
+    let max_extended = read(0x8000_0000, 0);
+    for leaf in [
+        0x8000_0002,
+        0x8000_0003,
+        0x8000_0004,
+        0x8000_0007,
+        0x8000_001D,
+    ] {
+        match leaf {
+            _ if leaf > max_extended => break,
+            0x8000_001D => read_caches(&mut read, leaf),
+            _ => {
+                read(leaf, 0);
+            }
+        }
+    }
+
+    leaves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_vector_features() -> CpuFeatures {
+        CpuFeatures::SSE4_2
+            | CpuFeatures::AVX
+            | CpuFeatures::AVX2
+            | CpuFeatures::FMA3
+            | CpuFeatures::F16C
+            | CpuFeatures::AVX512F
+            | CpuFeatures::AVX512BW
+            | CpuFeatures::AVX512CD
+            | CpuFeatures::AVX512DQ
+            | CpuFeatures::AVX512VL
+    }
+
+    #[test]
+    fn leaf7_ebx_decodes_tsx() {
+        // Skylake client leaf-7 EBX: BMI1/2, AVX2, ... with RTM and HLE.
+        let ebx = 0x029c_6fbf;
+        assert_eq!(
+            tsx_features_from_leaf7_ebx(ebx),
+            CpuFeatures::RTM | CpuFeatures::HLE
+        );
+        // Same part after the TAA microcode cleared both bits.
+        assert_eq!(
+            tsx_features_from_leaf7_ebx(ebx & !(1 << 11) & !(1 << 4)),
+            CpuFeatures::empty()
+        );
+        assert_eq!(tsx_features_from_leaf7_ebx(1 << 11), CpuFeatures::RTM);
+    }
+
+    #[test]
+    fn family_model_maps_to_avx512_downclock_risk() {
+        // Xeon Gold 6148 (Skylake-SP) and 6248 (Cascade Lake): same model.
+        assert_eq!(avx512_downclock_risk(Vendor::Intel, 6, 0x55), Some(true));
+        // Xeon Platinum 8380 (Ice Lake-SP), Core i9-12900K (Alder Lake).
+        assert_eq!(avx512_downclock_risk(Vendor::Intel, 6, 0x6A), Some(false));
+        assert_eq!(avx512_downclock_risk(Vendor::Intel, 6, 0x97), Some(false));
+        // Ryzen 9 7950X (Zen 4, family 0x19).
+        assert_eq!(avx512_downclock_risk(Vendor::Amd, 0x19, 0x61), Some(false));
+        // Knights Landing is not in the table; other vendors never are.
+        assert_eq!(avx512_downclock_risk(Vendor::Intel, 6, 0x57), None);
+        assert_eq!(avx512_downclock_risk(Vendor::Other, 6, 0x55), None);
+    }
+
+    #[test]
+    fn cpuid_avx_without_os_state_is_cleared() {
+        // No OSXSAVE at all.
+        let mut features = all_vector_features();
+        mask_os_disabled_vector_features(&mut features, None);
+        assert_eq!(features, CpuFeatures::SSE4_2);
+
+        // XSAVE on, but only x87 + SSE state enabled (constrained VM).
+        let mut features = all_vector_features();
+        mask_os_disabled_vector_features(&mut features, Some(0b011));
+        assert_eq!(features, CpuFeatures::SSE4_2);
+    }
+
+    #[test]
+    fn avx512_needs_all_three_zmm_state_bits() {
+        // AVX state on, ZMM_Hi256 missing: AVX/AVX2 survive, AVX-512 does not.
+        let mut features = all_vector_features();
+        mask_os_disabled_vector_features(&mut features, Some(0b1010_0111));
+        assert!(features.contains(CpuFeatures::AVX | CpuFeatures::AVX2 | CpuFeatures::FMA3));
+        assert!(!features.intersects(CpuFeatures::AVX512F | CpuFeatures::AVX512VL));
+
+        // Full AVX-512 state keeps everything.
+        let mut features = all_vector_features();
+        mask_os_disabled_vector_features(&mut features, Some(0b1110_0111));
+        assert_eq!(features, all_vector_features());
+    }
+
+    #[test]
+    fn live_detection_agrees_with_std() {
+        let mut features = CpuFeatures::empty();
+        detect_features_via_cpuid(&mut features);
+
+        assert_eq!(
+            features.contains(CpuFeatures::AVX),
+            std::is_x86_feature_detected!("avx")
+        );
+        assert_eq!(
+            features.contains(CpuFeatures::AVX512F),
+            std::is_x86_feature_detected!("avx512f")
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[test]
+    fn cpuid_dump_starts_with_the_vendor_leaf() {
+        let leaves = dump_cpuid_leaves();
+        let &(leaf, subleaf, [max_basic, ebx, ecx, edx]) = leaves.first().unwrap();
+        assert_eq!((leaf, subleaf), (0, 0));
+        // Every x86_64 part has at least leaf 1; real maxima are small.
+        assert!(
+            (1..0x100).contains(&max_basic),
+            "max basic leaf {max_basic:#x}"
+        );
+
+        // The vendor string is EBX, EDX, ECX - "GenuineIntel", "AuthenticAMD".
+        let vendor: Vec<u8> = [ebx, edx, ecx]
+            .iter()
+            .flat_map(|reg| reg.to_le_bytes())
+            .collect();
+        assert!(vendor.iter().all(u8::is_ascii_graphic), "{vendor:?}");
+        assert_eq!(
+            std::str::from_utf8(&vendor).unwrap(),
+            raw_cpuid::CpuId::new().get_vendor_info().unwrap().as_str()
+        );
+
+        assert!(leaves.iter().any(|&(leaf, _, _)| leaf == 1));
+        assert!(leaves.iter().any(|&(leaf, _, _)| leaf == 0x8000_0000));
+        assert!(
+            leaves
+                .windows(2)
+                .all(|w| (w[0].0, w[0].1) < (w[1].0, w[1].1))
+        );
+    }
+
+    #[test]
+    fn invariant_tsc_decodes_leaf_8000_0007_edx() {
+        // Intel client parts report only the invariant-TSC bit.
+        assert!(invariant_tsc_from_edx(0x0000_0100));
+        // Zen: power-management bits around it, bit 8 set.
+        assert!(invariant_tsc_from_edx(0x0000_6799));
+        // Everything but bit 8.
+        assert!(!invariant_tsc_from_edx(0xFFFF_FEFF));
+    }
+
+    #[test]
+    fn tsc_frequency_decodes_leaf15() {
+        // 38.4 MHz crystal at ratio 166/2.
+        assert_eq!(
+            tsc_frequency_from_leaf15(2, 166, 38_400_000),
+            Some(3_187_200_000)
+        );
+        // 24 MHz crystal at ratio 250/2.
+        assert_eq!(
+            tsc_frequency_from_leaf15(2, 250, 24_000_000),
+            Some(3_000_000_000)
+        );
+        // Ratio reported, crystal not (Skylake client): unknown, not zero.
+        assert_eq!(tsc_frequency_from_leaf15(2, 300, 0), None);
+        assert_eq!(tsc_frequency_from_leaf15(0, 0, 0), None);
+    }
+
+    #[test]
+    fn clflush_line_size_decodes_leaf1_ebx() {
+        // EBX from an i7-6700: APIC id 0, 16 logical ids, CLFLUSH 8 units, brand 0.
+        assert_eq!(clflush_line_size_from_ebx(0x0010_0800), Some(64));
+        // Only bits 8-15 matter.
+        assert_eq!(clflush_line_size_from_ebx(0xFFFF_10FF), Some(128));
+        assert_eq!(clflush_line_size_from_ebx(0xFFFF_00FF), None);
+    }
+}
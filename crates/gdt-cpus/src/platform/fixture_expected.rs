@@ -64,6 +64,10 @@ fn kind_by_name(name: &str) -> CoreKind {
 
 /// Universal invariants every detection result must satisfy, fixture or live.
 pub(crate) fn assert_invariants(info: &CpuInfo) {
+    if let Err(err) = info.validate() {
+        panic!("{}", err);
+    }
+
     let smt0 = info.lps.iter().filter(|lp| lp.smt_index == 0).count();
     assert_eq!(smt0, info.core_count as usize, "one smt_index==0 per core");
 
@@ -175,9 +179,18 @@ fn check_key(info: &CpuInfo, key: &str, value: &str, fixture: &str) {
         ["core_count"] => info.core_count.to_string(),
         ["socket_count"] => info.socket_count.to_string(),
         ["numa_node_count"] => info.numa_node_count.to_string(),
+        ["smt_supported"] => info.smt_supported.to_string(),
+        ["smt_enabled"] => info.is_smt_enabled().to_string(),
+        ["complete_topology"] => info.has_complete_topology().to_string(),
         ["l3_domain_count"] => info.l3_domains.len().to_string(),
         ["l2_domain_count"] => info.l2_domains.len().to_string(),
+        ["total_capacity"] => info
+            .total_capacity()
+            .map_or("none".to_string(), |c| c.to_string()),
         ["kind", kind] => info.kind_core_counts[kind_by_name(kind).index()].to_string(),
+        ["socket", n, "kind", kind] => {
+            info.socket_kind_core_counts(n.parse().unwrap())[kind_by_name(kind).index()].to_string()
+        }
         ["l3", n, field] => {
             let d = &info.l3_domains[n.parse::<usize>().unwrap()];
 
@@ -274,6 +287,10 @@ fn check_key(info: &CpuInfo, key: &str, value: &str, fixture: &str) {
                 "numa_node" => lp.numa_node.to_string(),
                 "perf_hint" => lp.perf_hint.to_string(),
                 "cpu_part" => lp.cpu_part.to_string(),
+                "native_core_id" => lp
+                    .native_core_id
+                    .map_or("none".to_string(), |id| id.to_string()),
+                "capacity" => lp.capacity.map_or("none".to_string(), |c| c.to_string()),
                 "kind" => lp
                     .kind
                     .to_string()
@@ -10,15 +10,28 @@
 //!   hand-rolled D-Bus client ([`rtkit`], [`dbus`] - feature `rtkit`).
 //! - Real-time promotion/demotion for the consent API ([`realtime`]) and the
 //!   priority-outcome probe ([`capabilities`]).
+//! - AC / battery state for [`crate::is_on_battery`] ([`power`]).
+//! - The cgroup v2 CPU bandwidth limit for [`crate::system_report`]
+//!   ([`cgroup`]).
+//! - Turbo / boost state for [`crate::is_turbo_enabled`] ([`turbo`]).
 //! - Common utility functions in the [`utils`] submodule.
 
+// Thread control is feature `affinity`; detection (`cpu`, `utils`) is always built.
+#[cfg(feature = "affinity")]
 pub(crate) mod affinity;
+#[cfg(feature = "affinity")]
 pub(crate) mod capabilities;
+#[cfg(feature = "affinity")]
+pub(crate) mod cgroup;
 pub(crate) mod cpu;
 #[cfg(feature = "rtkit")]
 pub(crate) mod dbus;
+pub(crate) mod power;
+#[cfg(feature = "affinity")]
 pub(crate) mod realtime;
 #[cfg(feature = "rtkit")]
 pub(crate) mod rtkit;
+#[cfg(feature = "affinity")]
 pub(crate) mod scheduling_policy;
+pub(crate) mod turbo;
 pub(crate) mod utils;
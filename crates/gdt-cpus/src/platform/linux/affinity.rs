@@ -17,11 +17,13 @@
 //!
 //! The main functions provided are [`set_thread_affinity`] and [`set_thread_priority`].
 
+use std::time::Duration;
+
 use libc::{SYS_gettid, c_int, syscall};
 
 use crate::{
-    AffinityMask, AppliedPriority, BrokerError, Error, FallbackReason, Grant, Mechanism,
-    MechanismPolicy, Result, ThreadPriority,
+    AffinityMask, AppliedPriority, Error, FallbackReason, Grant, Mechanism, MechanismPolicy,
+    Result, ThreadPriority,
     platform::linux::scheduling_policy::{level_for_nice, nice_for},
 };
 
@@ -39,56 +41,75 @@ use crate::{
 /// # Returns
 ///
 /// - `Ok(())` if the affinity was successfully set.
-/// - `Error::Affinity` if the mask is empty, no valid cores could be added, or
-///   `sched_setaffinity` fails.
-/// - Errors from [`crate::CpuInfo::detect()`] if CPU information cannot be retrieved.
+/// - `Error::Affinity` if the mask is empty or `sched_setaffinity` fails.
+/// - `Error::InvalidParameter` if no core in the mask is usable (EINVAL).
 ///
 /// # Safety
 ///
-/// This function uses `unsafe` blocks for FFI calls to `libc::sched_setaffinity`,
-/// `libc::CPU_ZERO`, and `libc::CPU_SET`. These are standard Linux system calls
-/// and are safe when provided with valid arguments (a valid `cpu_set_t` and a TID of 0
-/// for the current thread).
+/// This function uses an `unsafe` block for the FFI call to
+/// `libc::sched_setaffinity`, passing a CPU set buffer this function owns and
+/// its exact byte length, and a TID of 0 for the current thread.
 pub(crate) fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
+    set_affinity_of(0, mask)
+}
+
+/// Bits per word of the kernel's CPU set (`unsigned long`).
+const CPU_SET_WORD_BITS: usize = libc::c_ulong::BITS as usize;
+
+/// A CPU set buffer covering every id an [`AffinityMask`] can hold.
+type CpuSetWords = [libc::c_ulong; AffinityMask::MAX_LP_COUNT / CPU_SET_WORD_BITS];
+
+/// Writes `mask` into `words` in the kernel's CPU set layout (bit
+/// `n % bits` of word `n / bits` = CPU `n`) and returns the number of words
+/// used - the `CPU_ALLOC_SIZE` of the highest core, in words. The kernel
+/// zero-extends a short set, so nothing past the highest core is passed.
+fn fill_cpu_set(mask: &AffinityMask, words: &mut CpuSetWords) -> usize {
+    words.fill(0);
+    for cpu in mask.iter() {
+        words[cpu / CPU_SET_WORD_BITS] |= 1 << (cpu % CPU_SET_WORD_BITS);
+    }
+    mask.highest_core()
+        .map_or(0, |highest| highest / CPU_SET_WORD_BITS + 1)
+}
+
+/// Reads a kernel CPU set back into a mask. CPUs at or above
+/// [`AffinityMask::MAX_LP_COUNT`] are dropped (the mask's fixed capacity).
+fn mask_from_cpu_set(words: &[libc::c_ulong]) -> AffinityMask {
+    let mut mask = AffinityMask::empty();
+    for (index, &word) in words.iter().enumerate() {
+        for bit in (0..CPU_SET_WORD_BITS).filter(|bit| word & (1 << bit) != 0) {
+            mask.add(index * CPU_SET_WORD_BITS + bit);
+        }
+    }
+    mask
+}
+
+/// [`set_thread_affinity`] for thread `tid` of this process (`0` = the
+/// calling thread) - the [`crate::RawThread`] path.
+pub(crate) fn set_affinity_of(tid: libc::pid_t, mask: &AffinityMask) -> Result<()> {
     if mask.is_empty() {
         return Err(Error::Affinity(
-            "Cannot set thread affinity with an empty mask".to_string(),
+            "Cannot set thread affinity with an empty mask".into(),
         ));
     }
 
     // NOTE: no topology lookup here - validating against detected LPs forced a
     // full (cached) detection inside an affinity call. The kernel validates
-    // membership itself and returns EINVAL for CPUs outside the allowed set;
-    // we only bound-check against cpu_set_t's capacity.
-    let max_cpus = libc::CPU_SETSIZE as usize;
-
-    // SAFETY: Zero-initializes the cpu_set_t structure.
-    // cpu_set_t is POD without non-zeroable invariants; zeroing yields a valid struct.
-    let mut cpuset: libc::cpu_set_t = unsafe { std::mem::zeroed() };
-    // SAFETY: CPU_ZERO is safe to call with a valid cpu_set_t pointer.
-    unsafe {
-        libc::CPU_ZERO(&mut cpuset);
-    }
-
-    // Set all cores in the mask
-    for core_idx in mask.iter() {
-        if core_idx < max_cpus {
-            // SAFETY: CPU_SET is safe with a valid cpu_set_t pointer and valid CPU index.
-            unsafe {
-                libc::CPU_SET(core_idx, &mut cpuset);
-            }
-        } else {
-            return Err(Error::Affinity(format!(
-                "OS core {} exceeds CPU_SETSIZE {}",
-                core_idx, max_cpus
-            )));
-        }
-    }
-
-    // SAFETY: sched_setaffinity is a system call that sets the CPU affinity for the calling thread.
-    // pid == 0 means the calling thread, and the size of the cpu_set_t is passed.
-    let res =
-        unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpuset) };
+    // membership itself and returns EINVAL for CPUs outside the allowed set.
+    // The set is sized to the mask's highest core rather than a fixed
+    // cpu_set_t, the CPU_ALLOC_SIZE contract; the buffer lives on the stack.
+    let mut words: CpuSetWords = [0; AffinityMask::MAX_LP_COUNT / CPU_SET_WORD_BITS];
+    let len = fill_cpu_set(mask, &mut words);
+
+    // SAFETY: sched_setaffinity reads `len` words of CPU set from `words`,
+    // which holds at least that many; tid 0 is the calling thread.
+    let res = unsafe {
+        libc::sched_setaffinity(
+            tid,
+            len * std::mem::size_of::<libc::c_ulong>(),
+            words.as_ptr().cast::<libc::cpu_set_t>(),
+        )
+    };
 
     if res == -1 {
         let err = std::io::Error::last_os_error();
@@ -98,45 +119,136 @@ pub(crate) fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
     }
 }
 
+/// Sets the affinity of EVERY thread of this process to `mask`.
+///
+/// `sched_setaffinity(getpid())` alone would only move the main thread (on
+/// Linux the pid is the main thread's tid), so this walks `/proc/self/task`
+/// and applies the mask per thread. Threads spawned afterwards inherit it from
+/// their creator. A thread that exits mid-walk is skipped; one spawned
+/// mid-walk by a not-yet-updated thread can keep the old mask.
+pub(crate) fn set_process_affinity(mask: &AffinityMask) -> Result<()> {
+    let tasks = std::fs::read_dir("/proc/self/task")
+        .map_err(|e| Error::Affinity(format!("Cannot list /proc/self/task: {}", e).into()))?;
+
+    for entry in tasks.flatten() {
+        let Some(tid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<libc::pid_t>().ok())
+        else {
+            continue;
+        };
+
+        if let Err(err) = set_affinity_of(tid, mask) {
+            // The thread exited between the listing and the call.
+            if !entry.path().exists() {
+                continue;
+            }
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
 fn map_sched_setaffinity_error(err: std::io::Error) -> Error {
     match err.raw_os_error() {
-        Some(libc::EINVAL) => Error::InvalidParameter(format!(
-            "Invalid affinity mask for sched_setaffinity: {}",
-            err
-        )),
-        _ => Error::Affinity(format!("sched_setaffinity failed: {}", err)),
+        Some(libc::EINVAL) => Error::InvalidParameter(
+            format!("Invalid affinity mask for sched_setaffinity: {}", err).into(),
+        ),
+        _ => Error::Affinity(format!("sched_setaffinity failed: {}", err).into()),
     }
 }
 
 /// Reads the current thread's CPU affinity into an [`AffinityMask`] via
 /// `sched_getaffinity(0)`.
 ///
+/// The kernel rejects (EINVAL) a buffer smaller than its `nr_cpu_ids`, so on
+/// a kernel configured for more than 1024 possible CPUs the set is re-read
+/// into a doubling heap buffer; CPUs past the mask's capacity are dropped.
+///
 /// # Returns
 ///
 /// - `Ok(mask)` with one bit set per OS LP the thread may run on.
 /// - `Error::Affinity` if `sched_getaffinity` fails.
 pub(crate) fn current_affinity() -> Result<AffinityMask> {
-    // SAFETY: cpu_set_t is POD; zeroing yields a valid (empty) set.
-    let mut cpuset: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    affinity_of(0)
+}
+
+/// [`current_affinity`] for thread `tid` (`0` = the calling thread). Any
+/// thread of the system may be read; an exited or unknown `tid` is
+/// `Error::NotFound`.
+pub(crate) fn affinity_of(tid: libc::pid_t) -> Result<AffinityMask> {
+    let mut words: CpuSetWords = [0; AffinityMask::MAX_LP_COUNT / CPU_SET_WORD_BITS];
+    let mut large: Vec<libc::c_ulong> = Vec::new();
+
+    loop {
+        let buffer: &mut [libc::c_ulong] = if large.is_empty() {
+            &mut words
+        } else {
+            &mut large
+        };
+
+        // SAFETY: sched_getaffinity writes at most the given byte length into
+        // `buffer`, which is exactly that long; tid 0 is the calling thread.
+        let res = unsafe {
+            libc::sched_getaffinity(
+                tid,
+                std::mem::size_of_val(buffer),
+                buffer.as_mut_ptr().cast::<libc::cpu_set_t>(),
+            )
+        };
+        if res != -1 {
+            return Ok(mask_from_cpu_set(buffer));
+        }
 
-    // SAFETY: sched_getaffinity fills `cpuset` for the calling thread (pid 0).
-    let res =
-        unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut cpuset) };
-    if res == -1 {
         let err = std::io::Error::last_os_error();
-        return Err(Error::Affinity(format!("sched_getaffinity failed: {err}")));
+        if err.raw_os_error() == Some(libc::ESRCH) {
+            return Err(Error::NotFound(format!("no thread with tid {tid}").into()));
+        }
+        // EINVAL = buffer shorter than nr_cpu_ids; give up past 64K CPUs.
+        if err.raw_os_error() != Some(libc::EINVAL) || buffer.len() * CPU_SET_WORD_BITS >= 1 << 16 {
+            return Err(Error::Affinity(
+                format!("sched_getaffinity failed: {err}").into(),
+            ));
+        }
+        let grown = buffer.len() * 2;
+        large = vec![0; grown];
     }
+}
 
-    let mut mask = AffinityMask::empty();
-    for cpu in 0..(libc::CPU_SETSIZE as usize) {
-        // SAFETY: CPU_ISSET is safe with a valid cpu_set_t pointer and an index
-        // below CPU_SETSIZE.
-        if unsafe { libc::CPU_ISSET(cpu, &cpuset) } {
-            mask.add(cpu);
-        }
+/// The OS LP id the calling thread is running on right now, via
+/// `sched_getcpu` (vDSO-backed, no syscall on x86_64/aarch64).
+pub(crate) fn current_logical_processor() -> Result<usize> {
+    // SAFETY: sched_getcpu has no preconditions; returns -1 with errno on failure.
+    let cpu = unsafe { libc::sched_getcpu() };
+    if cpu < 0 {
+        let err = std::io::Error::last_os_error();
+        return Err(Error::SystemCall(
+            format!("sched_getcpu failed: {err}").into(),
+        ));
     }
 
-    Ok(mask)
+    Ok(cpu as usize)
+}
+
+/// CPU time the calling thread has consumed (user + system), via
+/// `clock_gettime(CLOCK_THREAD_CPUTIME_ID)` - nanosecond resolution.
+pub(crate) fn current_thread_cpu_time() -> Result<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `ts` is a live timespec; the clock id is a constant.
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } != 0 {
+        let err = std::io::Error::last_os_error();
+        return Err(Error::SystemCall(
+            format!("clock_gettime(CLOCK_THREAD_CPUTIME_ID) failed: {err}").into(),
+        ));
+    }
+
+    Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
 }
 
 /// Returns the current thread's kernel TID (the id `setpriority` and rtkit
@@ -147,10 +259,9 @@ pub(crate) fn current_tid() -> Result<libc::id_t> {
     let tid = unsafe { syscall(SYS_gettid) };
     if tid <= 0 {
         let err = std::io::Error::last_os_error();
-        return Err(Error::SystemCall(format!(
-            "Failed to get thread ID via gettid(): {}",
-            err
-        )));
+        return Err(Error::SystemCall(
+            format!("Failed to get thread ID via gettid(): {}", err).into(),
+        ));
     }
 
     Ok(tid as libc::id_t)
@@ -166,22 +277,78 @@ pub(crate) fn current_tid() -> Result<libc::id_t> {
 /// [`Error::SystemCall`] if `getpriority` fails (only a missing TID, which
 /// cannot happen for the calling thread).
 pub(crate) fn current_nice() -> Result<c_int> {
-    let tid = current_tid()?;
+    nice_of(current_tid()?)
+}
 
+/// [`current_nice`] for thread `tid`.
+fn nice_of(tid: libc::id_t) -> Result<c_int> {
     // SAFETY: raw getpriority for the current thread's TID; returns 20 - nice on
     // success, -1 (with errno) on failure.
     let rc = unsafe { libc::syscall(libc::SYS_getpriority, libc::PRIO_PROCESS, tid as c_int) };
     if rc < 0 {
         let err = std::io::Error::last_os_error();
-        return Err(Error::SystemCall(format!(
-            "getpriority for TID {}: {}",
-            tid, err
-        )));
+        return Err(Error::SystemCall(
+            format!("getpriority for TID {}: {}", tid, err).into(),
+        ));
     }
 
     Ok(20 - rc as c_int)
 }
 
+/// The calling thread's priority as [`set_thread_priority`] changes it - its
+/// `nice` value (the policy always stays as it was) - for save/restore.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SavedPriority(c_int);
+
+/// Snapshots the calling thread's priority.
+pub(crate) fn save_thread_priority() -> Result<SavedPriority> {
+    current_nice().map(SavedPriority)
+}
+
+/// The calling thread's scheduling policy and level as the kernel reports
+/// them (`"SCHED_OTHER nice 0"`, `"SCHED_RR priority 85"`), for reports.
+pub(crate) fn describe_thread_priority() -> Result<String> {
+    // SAFETY: pid 0 is the calling thread; a plain getter.
+    let policy = unsafe { libc::sched_getscheduler(0) };
+    if policy < 0 {
+        return Err(Error::SystemCall(
+            format!(
+                "sched_getscheduler failed: {}",
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        ));
+    }
+
+    let name = match policy & !libc::SCHED_RESET_ON_FORK {
+        libc::SCHED_OTHER => "SCHED_OTHER",
+        libc::SCHED_BATCH => "SCHED_BATCH",
+        libc::SCHED_IDLE => "SCHED_IDLE",
+        libc::SCHED_FIFO => "SCHED_FIFO",
+        libc::SCHED_RR => "SCHED_RR",
+        p if p == super::realtime::SCHED_DEADLINE as c_int => return Ok("SCHED_DEADLINE".into()),
+        _ => "unknown policy",
+    };
+
+    if matches!(name, "SCHED_FIFO" | "SCHED_RR") {
+        // SAFETY: sched_param is POD; zeroing it is safe.
+        let mut param: libc::sched_param = unsafe { std::mem::zeroed() };
+        // SAFETY: pid 0 is the calling thread; `param` is a live local.
+        if unsafe { libc::sched_getparam(0, &mut param) } == 0 {
+            return Ok(format!("{} priority {}", name, param.sched_priority));
+        }
+    }
+    Ok(format!("{} nice {}", name, current_nice()?))
+}
+
+/// Puts the calling thread back at a [`save_thread_priority`] snapshot.
+///
+/// Undoing a boost (raising nice again) always succeeds; undoing a drop
+/// needs the same privilege a boost would (the one-way ratchet).
+pub(crate) fn restore_thread_priority(saved: &SavedPriority) -> Result<()> {
+    set_thread_nice_value(current_tid()?, saved.0)
+}
+
 /// Sets the `nice` value for the current thread on Linux - one direct
 /// `setpriority` call, no fallback (the cascade lives in
 /// [`set_thread_priority`]).
@@ -198,9 +365,7 @@ pub(crate) fn current_nice() -> Result<c_int> {
 ///
 /// Uses `unsafe` for `syscall(SYS_gettid)` and `libc::setpriority` - standard
 /// Linux system calls, safe with a valid TID and an in-range nice value.
-fn set_thread_nice_value(nice_value: c_int) -> Result<()> {
-    let tid = current_tid()?;
-
+fn set_thread_nice_value(tid: libc::id_t, nice_value: c_int) -> Result<()> {
     // SAFETY: setpriority is used to set the nice value for a specific thread (using tid).
     // tid is a valid thread ID, and nice_value is the expected c_int value.
     let res = unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice_value) };
@@ -208,22 +373,22 @@ fn set_thread_nice_value(nice_value: c_int) -> Result<()> {
     if res == -1 {
         let err = std::io::Error::last_os_error();
         match err.raw_os_error() {
-            Some(libc::EACCES | libc::EPERM) => Err(Error::PermissionDenied(format!(
-                "Setting nice value {} for TID {}: {}",
-                nice_value, tid, err
-            ))),
-            Some(libc::ESRCH) => Err(Error::NotFound(format!(
-                "Thread with TID {} not found for setpriority: {}",
-                tid, err
-            ))),
-            Some(libc::EINVAL) => Err(Error::InvalidParameter(format!(
-                "Invalid nice value {} for setpriority",
-                nice_value
-            ))),
-            _ => Err(Error::SystemCall(format!(
-                "setpriority failed for nice value {} for TID {}: {}",
-                nice_value, tid, err
-            ))),
+            Some(libc::EACCES | libc::EPERM) => Err(Error::PermissionDenied(
+                format!("Setting nice value {} for TID {}: {}", nice_value, tid, err).into(),
+            )),
+            Some(libc::ESRCH) => Err(Error::NotFound(
+                format!("Thread with TID {} not found for setpriority: {}", tid, err).into(),
+            )),
+            Some(libc::EINVAL) => Err(Error::InvalidParameter(
+                format!("Invalid nice value {} for setpriority", nice_value).into(),
+            )),
+            _ => Err(Error::SystemCall(
+                format!(
+                    "setpriority failed for nice value {} for TID {}: {}",
+                    nice_value, tid, err
+                )
+                .into(),
+            )),
         }
     } else {
         Ok(())
@@ -259,9 +424,18 @@ fn set_thread_nice_value(nice_value: c_int) -> Result<()> {
 /// denial is reported in the returned [`AppliedPriority`] (degraded to the
 /// current level), not as an error.
 pub(crate) fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority> {
+    set_priority_of(current_tid()?, priority)
+}
+
+/// [`set_thread_priority`] for thread `tid` of this process - the same
+/// cascade, addressed by TID (the [`crate::RawThread`] path).
+pub(crate) fn set_priority_of(
+    tid: libc::id_t,
+    priority: ThreadPriority,
+) -> Result<AppliedPriority> {
     // Map the portable level to its nice value (table in scheduling_policy.rs).
     let value = nice_for(priority);
-    match set_thread_nice_value(value) {
+    match set_thread_nice_value(tid, value) {
         Ok(()) => Ok(AppliedPriority::new(
             priority,
             priority,
@@ -272,58 +446,51 @@ pub(crate) fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPri
             },
         )),
         Err(Error::PermissionDenied(_)) => {
-            // Why the stronger request failed - refined by the rtkit attempt.
-            // Defaults to NoBroker for the feature-off / no-tid paths.
-            #[cfg_attr(not(feature = "rtkit"), allow(unused_mut))]
-            let mut reason = FallbackReason::NoBroker;
-
-            // The typed reason the broker REFUSED, when it answered with a D-Bus
-            // ERROR - carried as data (NOT free text) so a caller can branch on it.
-            #[cfg_attr(not(feature = "rtkit"), allow(unused_mut))]
-            let mut broker_error: Option<BrokerError> = None;
+            // Why the stronger request failed (`reason`), and the typed reason
+            // the broker REFUSED when it answered with a D-Bus ERROR
+            // (`broker_error`) - carried as data (NOT free text) so a caller can
+            // branch on it. NoBroker when the `rtkit` feature is off.
+            #[cfg(not(feature = "rtkit"))]
+            let (reason, broker_error): (FallbackReason, Option<crate::BrokerError>) =
+                (FallbackReason::NoBroker, None);
 
             #[cfg(feature = "rtkit")]
-            {
-                if let Ok(tid) = current_tid() {
-                    match crate::platform::linux::rtkit::try_high_priority(tid as u64, value) {
-                        Ok(granted) if granted == value => {
-                            return Ok(AppliedPriority::new(
-                                priority,
-                                priority,
-                                Grant::Brokered,
-                                Mechanism {
-                                    policy: MechanismPolicy::Nice,
-                                    value: granted as i8,
-                                },
-                            ));
-                        }
-                        // Granted, but weaker than asked (the broker's ceiling):
-                        // keep the level, flag the clamp as a fall-short reason.
-                        Ok(granted) => {
-                            return Ok(AppliedPriority::new(
-                                priority,
-                                priority,
-                                Grant::Brokered,
-                                Mechanism {
-                                    policy: MechanismPolicy::Nice,
-                                    value: granted as i8,
-                                },
-                            )
-                            .with_reason(FallbackReason::Clamped));
-                        }
-                        Err((r, be)) => {
-                            reason = r;
-                            broker_error = be;
-                        }
+            let (reason, broker_error) = {
+                match crate::platform::linux::rtkit::try_high_priority(tid as u64, value) {
+                    Ok(granted) if granted == value => {
+                        return Ok(AppliedPriority::new(
+                            priority,
+                            priority,
+                            Grant::Brokered,
+                            Mechanism {
+                                policy: MechanismPolicy::Nice,
+                                value: granted as i8,
+                            },
+                        ));
                     }
+                    // Granted, but weaker than asked (the broker's ceiling):
+                    // keep the level, flag the clamp as a fall-short reason.
+                    Ok(granted) => {
+                        return Ok(AppliedPriority::new(
+                            priority,
+                            priority,
+                            Grant::Brokered,
+                            Mechanism {
+                                policy: MechanismPolicy::Nice,
+                                value: granted as i8,
+                            },
+                        )
+                        .with_reason(FallbackReason::Clamped));
+                    }
+                    Err((r, be)) => (r, be),
                 }
-            }
+            };
 
             // No broker delivered it. Best-effort: a denied setpriority left the
             // thread's nice untouched, so report the level it ACTUALLY sits at
             // (NOT a hardcoded Normal - that could even DEMOTE a thread already
             // above normal) with WHY, as data. Never an error on a mere denial.
-            let current = current_nice().unwrap_or(nice_for(ThreadPriority::Normal));
+            let current = nice_of(tid).unwrap_or(nice_for(ThreadPriority::Normal));
 
             // The mechanism is the nice the thread actually KEEPS; the structured
             // `reason` + `broker_error` carry the classification.
@@ -364,17 +531,19 @@ pub(crate) fn set_thread_realtime_rr(priority: c_int) -> Result<()> {
 
     if rt_min == -1 || rt_max == -1 {
         let err = std::io::Error::last_os_error();
-        return Err(Error::SystemCall(format!(
-            "Failed to get SCHED_RR priority range: {}",
-            err
-        )));
+        return Err(Error::SystemCall(
+            format!("Failed to get SCHED_RR priority range: {}", err).into(),
+        ));
     }
 
     if priority < rt_min || priority > rt_max {
-        return Err(Error::InvalidParameter(format!(
-            "Absolute priority {} is out of range [{}, {}] for SCHED_RR",
-            priority, rt_min, rt_max
-        )));
+        return Err(Error::InvalidParameter(
+            format!(
+                "Absolute priority {} is out of range [{}, {}] for SCHED_RR",
+                priority, rt_min, rt_max
+            )
+            .into(),
+        ));
     }
 
     // SAFETY: pthread_self() always returns a valid handle to the current thread.
@@ -394,22 +563,26 @@ pub(crate) fn set_thread_realtime_rr(priority: c_int) -> Result<()> {
     if res != 0 {
         let err = std::io::Error::from_raw_os_error(res); // res is errno in this case
         match res {
-            libc::EPERM => Err(Error::PermissionDenied(format!(
-                "Setting SCHED_RR with priority {}: {}",
-                priority, err
-            ))),
-            libc::EINVAL => Err(Error::InvalidParameter(format!(
-                "Invalid parameters for SCHED_RR: priority={}. Error: {}",
-                priority, err
-            ))),
-            libc::ESRCH => Err(Error::NotFound(format!(
-                "Thread not found for pthread_setschedparam. Error: {}",
-                err
-            ))),
-            _ => Err(Error::SystemCall(format!(
-                "pthread_setschedparam failed for SCHED_RR with priority {}. Error: {}",
-                priority, err
-            ))),
+            libc::EPERM => Err(Error::PermissionDenied(
+                format!("Setting SCHED_RR with priority {}: {}", priority, err).into(),
+            )),
+            libc::EINVAL => Err(Error::InvalidParameter(
+                format!(
+                    "Invalid parameters for SCHED_RR: priority={}. Error: {}",
+                    priority, err
+                )
+                .into(),
+            )),
+            libc::ESRCH => Err(Error::NotFound(
+                format!("Thread not found for pthread_setschedparam. Error: {}", err).into(),
+            )),
+            _ => Err(Error::SystemCall(
+                format!(
+                    "pthread_setschedparam failed for SCHED_RR with priority {}. Error: {}",
+                    priority, err
+                )
+                .into(),
+            )),
         }
     } else {
         Ok(())
@@ -420,6 +593,40 @@ pub(crate) fn set_thread_realtime_rr(priority: c_int) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn cpu_set_is_sized_to_the_highest_core() {
+        let mut words: CpuSetWords = [0; AffinityMask::MAX_LP_COUNT / CPU_SET_WORD_BITS];
+
+        let low = AffinityMask::from_cores(&[0, 3]);
+        assert_eq!(fill_cpu_set(&low, &mut words), 1);
+        assert_eq!(mask_from_cpu_set(&words[..1]), low);
+
+        // Past the 128-LP mark and at the mask's last id: every bit lands in
+        // the set, none is dropped.
+        let high = AffinityMask::from_cores(&[1, 130, 1023]);
+        let len = fill_cpu_set(&high, &mut words);
+        assert_eq!(len * CPU_SET_WORD_BITS, AffinityMask::MAX_LP_COUNT);
+        assert_eq!(mask_from_cpu_set(&words[..len]), high);
+        assert_eq!(
+            words[130 / CPU_SET_WORD_BITS],
+            1 << (130 % CPU_SET_WORD_BITS)
+        );
+    }
+
+    #[test]
+    fn oversized_kernel_set_keeps_the_representable_cpus() {
+        // A kernel with nr_cpu_ids > 1024 hands back a longer set.
+        let mut words =
+            vec![0 as libc::c_ulong; 2 * AffinityMask::MAX_LP_COUNT / CPU_SET_WORD_BITS];
+        for cpu in [2, 1023, 1024, 2047] {
+            words[cpu / CPU_SET_WORD_BITS] |= 1 << (cpu % CPU_SET_WORD_BITS);
+        }
+        assert_eq!(
+            mask_from_cpu_set(&words).iter().collect::<Vec<_>>(),
+            [2, 1023]
+        );
+    }
+
     #[test]
     fn sched_setaffinity_einval_maps_to_invalid_parameter() {
         let err = std::io::Error::from_raw_os_error(libc::EINVAL);
@@ -428,4 +635,89 @@ mod tests {
             Error::InvalidParameter(_)
         ));
     }
+
+    // Rewrites every thread's mask, which would clobber pins held by tests
+    // running in parallel - so the real check runs in a child process.
+    #[test]
+    fn process_affinity_is_inherited_by_new_threads() {
+        const CHILD: &str = "GDT_CPUS_PROCESS_AFFINITY_CHILD";
+
+        if std::env::var_os(CHILD).is_none() {
+            let child = std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "--exact",
+                    "platform::linux::affinity::tests::process_affinity_is_inherited_by_new_threads",
+                    "--test-threads=1",
+                ])
+                .env(CHILD, "1")
+                .output()
+                .unwrap();
+            assert!(
+                child.status.success(),
+                "{}",
+                String::from_utf8_lossy(&child.stdout)
+            );
+            return;
+        }
+
+        let target = current_affinity().unwrap().iter().last().unwrap();
+        let mask = AffinityMask::single(target);
+
+        set_process_affinity(&mask).unwrap();
+
+        assert_eq!(current_affinity().unwrap(), mask);
+        let spawned = std::thread::spawn(current_affinity).join().unwrap();
+        assert_eq!(spawned.unwrap(), mask);
+    }
+
+    #[test]
+    fn best_effort_affinity_skips_offline_and_out_of_range_ids() {
+        let info = crate::CpuInfo::detect().unwrap();
+        let target = current_affinity().unwrap().iter().next().unwrap();
+        let offline = (0..AffinityMask::MAX_LP_COUNT)
+            .find(|&id| info.library_index(id).is_none())
+            .unwrap();
+
+        std::thread::spawn(move || {
+            let mixed = AffinityMask::from_cores(&[target, offline]);
+            assert_eq!(crate::set_thread_affinity_best_effort(&mixed).unwrap(), 1);
+            assert_eq!(current_affinity().unwrap(), AffinityMask::single(target));
+
+            let none = AffinityMask::single(offline);
+            assert!(matches!(
+                crate::set_thread_affinity_best_effort(&none),
+                Err(Error::InvalidParameter(_))
+            ));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn current_core_matches_topology_when_pinned() {
+        let info = crate::CpuInfo::detect().unwrap();
+        // Highest LP the sandbox lets us run on - exercises a non-zero id
+        // where possible.
+        let target = current_affinity().unwrap().iter().last().unwrap();
+
+        std::thread::spawn(move || {
+            set_thread_affinity(&AffinityMask::single(target)).unwrap();
+
+            let (socket, core, lp_id) = crate::current_core().unwrap();
+            assert_eq!(lp_id, target);
+            assert_eq!(current_logical_processor().unwrap(), target);
+
+            let lp = info
+                .lps
+                .iter()
+                .find(|lp| lp.os_id as usize == target)
+                .unwrap();
+            assert_eq!(socket, lp.socket as usize);
+            assert_eq!(core, lp.core as usize);
+            assert!(socket < info.socket_count as usize);
+            assert!(core < info.core_count as usize);
+        })
+        .join()
+        .unwrap();
+    }
 }
@@ -0,0 +1,94 @@
+//! CPU bandwidth limit from the unified (v2) cgroup hierarchy.
+//!
+//! A cgroup's `cpu.max` is `"$QUOTA $PERIOD"` (`"max $PERIOD"` when
+//! unlimited): its threads together get at most QUOTA µs of CPU time every
+//! PERIOD µs. Every ancestor's limit applies too, so the effective one is the
+//! tightest on the path to the root. cgroup v1 (`cpu.cfs_quota_us`) is not
+//! read.
+
+use std::path::Path;
+
+/// [`cpu_limit_at`] on the live `/sys` and `/proc`.
+pub(crate) fn cpu_limit() -> Option<f64> {
+    cpu_limit_at(Path::new("/sys"), Path::new("/proc"))
+}
+
+/// The calling process's CPU bandwidth limit in CPUs (`quota / period`, so
+/// `1.5` = one and a half CPUs' worth of time), or `None` when no cgroup on
+/// its path sets one or the v2 hierarchy cannot be read.
+pub(crate) fn cpu_limit_at(sysfs: &Path, procfs: &Path) -> Option<f64> {
+    let cgroups = std::fs::read_to_string(procfs.join("self/cgroup")).ok()?;
+    // The v2 entry is "0::/path"; v1 controllers get their own lines.
+    let path = cgroups.lines().find_map(|line| line.strip_prefix("0::"))?;
+
+    let root = sysfs.join("fs/cgroup");
+    let mut dir = root.join(path.trim_start_matches('/'));
+    let mut limit: Option<f64> = None;
+    loop {
+        let cpus = std::fs::read_to_string(dir.join("cpu.max"))
+            .ok()
+            .and_then(|content| parse_cpu_max(&content));
+        if let Some(cpus) = cpus {
+            limit = Some(limit.map_or(cpus, |tighter| tighter.min(cpus)));
+        }
+
+        if dir == root || !dir.pop() {
+            break;
+        }
+    }
+    limit
+}
+
+/// CPUs' worth of time one `cpu.max` grants; `None` for `max` (unlimited)
+/// or a malformed line.
+fn parse_cpu_max(content: &str) -> Option<f64> {
+    let mut fields = content.split_whitespace();
+    let quota: u64 = fields.next()?.parse().ok()?;
+    let period: u64 = fields.next()?.parse().ok()?;
+
+    (period > 0).then(|| quota as f64 / period as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let file = root.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, format!("{contents}\n")).unwrap();
+    }
+
+    #[test]
+    fn cpu_max_parses() {
+        assert_eq!(parse_cpu_max("150000 100000\n"), Some(1.5));
+        assert_eq!(parse_cpu_max("max 100000"), None);
+        assert_eq!(parse_cpu_max("50000"), None);
+        assert_eq!(parse_cpu_max("50000 0"), None);
+    }
+
+    #[test]
+    fn tightest_ancestor_limit_wins() {
+        let root = std::env::temp_dir().join(format!("gdt-cpus-cgroup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // No /proc/self/cgroup: unknown.
+        assert_eq!(cpu_limit_at(&root, &root), None);
+
+        write(
+            &root,
+            "self/cgroup",
+            "1:cpu:/v1/ignored\n0::/app.slice/game",
+        );
+        write(&root, "fs/cgroup/app.slice/game/cpu.max", "max 100000");
+        assert_eq!(cpu_limit_at(&root, &root), None);
+
+        // A parent's quota applies to the leaf; the tighter of two wins.
+        write(&root, "fs/cgroup/app.slice/cpu.max", "400000 100000");
+        assert_eq!(cpu_limit_at(&root, &root), Some(4.0));
+        write(&root, "fs/cgroup/app.slice/game/cpu.max", "200000 100000");
+        assert_eq!(cpu_limit_at(&root, &root), Some(2.0));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -3,15 +3,26 @@
 //! Pipeline (order is load-bearing - kinds must be final before per-kind cache
 //! bucketing):
 //! 1. online LP list (`devices/system/cpu/online`)
-//! 2. per-LP topology: package/core ids -> dense core+socket indices, SMT order,
-//!    explicit `core_type` when the kernel provides it (Intel hybrid)
-//! 3. kind classification: `core_type` -> capacity thresholds -> all-Performance
+//! 2. per-LP topology: package/core ids (else the package/thread sibling
+//!    lists) -> dense core+socket indices, SMT order, explicit `core_type`
+//!    when the kernel provides it (Intel hybrid)
+//! 3. kind classification: `core_type` -> capacity thresholds -> all-Performance;
+//!    perf_hint from `cpu_capacity`, else ACPI CPPC `highest_perf`
 //! 4. L3 domains: content-keyed by the lowest LP of each cache's
 //!    `shared_cpu_list` - NEVER attributed per socket (chiplet CPUs have
-//!    several L3 instances per socket) and never deduplicated by size
+//!    several L3 instances per socket) and never deduplicated by size;
+//!    L3-less Efficiency LPs next to L3-backed ones become LpEfficiency;
+//!    L2 domains likewise, read once per physical core and inherited by its
+//!    SMT siblings, whose own L1/L2 entries are only checked for agreement
 //! 5. per-kind L1/L2 from the first LP of each (now final) kind
+//!    (steps 4-5 are skipped entirely when `DetectOptions::caches` is off)
 //! 6. NUMA node ids from `devices/system/node/node*/cpulist`
 //! 7. vendor/model/features (cpuid on x86_64, `/proc/cpuinfo` fallback)
+//!    and clocks (cpufreq, cpuid leaf 0x16 fallback; `DetectOptions::frequencies`)
+//!
+//! Topology comes from sysfs alone; `/proc/cpuinfo` only supplements identity
+//! (step 7) and the ARM part numbers, so a kernel whose cpuinfo lacks
+//! `physical id` / `core id` still groups correctly.
 //!
 //! `detect_at()` takes the sysfs/procfs roots explicitly so recorded fixture
 //! trees can drive the whole pipeline in tests.
@@ -20,11 +31,12 @@ use std::fs;
 use std::path::Path;
 
 use crate::{
-    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, Error, L2Domain, L3Domain, Lp, Result,
-    Vendor,
+    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, DetectOptions, DetectionPhase, Error,
+    L2Domain, L3Domain, Lp, Result, Vendor, WritePolicy,
 };
 
 use super::utils::{parse_range_list_str, parse_range_list_with};
+use crate::platform::timing::PhaseTimer;
 
 pub(crate) mod features;
 pub(crate) mod proc;
@@ -32,9 +44,13 @@ pub(crate) mod proc;
 #[cfg(test)]
 mod fixture_tests;
 
-/// Detects CPU information from the live system.
-pub fn detect_cpu_info() -> Result<CpuInfo> {
-    detect_at(Path::new("/sys"), Path::new("/proc"))
+/// Detects CPU information from the live system, running the optional
+/// steps `options` enables.
+pub fn detect_cpu_info(
+    options: &DetectOptions,
+    progress: &mut dyn FnMut(DetectionPhase, f32),
+) -> Result<CpuInfo> {
+    detect_at_with_progress(Path::new("/sys"), Path::new("/proc"), options, progress)
 }
 
 /// Reads a sysfs file as a trimmed string; `None` if absent/unreadable.
@@ -47,19 +63,142 @@ fn read_u64(path: &Path) -> Option<u64> {
     read_str(path)?.parse().ok()
 }
 
-/// Parses sysfs cache sizes: "32768K", "32M", bare bytes.
+/// Identity of a package or core while grouping LPs: the kernel's id, or -
+/// when the id file is missing - the lowest LP of the sysfs sibling list that
+/// shares it. Distinct variants, so the two sources can never collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupKey {
+    Id(u64),
+    FirstSibling(usize),
+}
+
+/// The lowest LP of the first readable sibling list among `names` under
+/// `topo` (newer kernels: `core_cpus_list` / `package_cpus_list`; older:
+/// `thread_siblings_list` / `core_siblings_list`).
+fn first_sibling(topo: &Path, names: &[&str]) -> Option<usize> {
+    let list = names.iter().find_map(|name| read_str(&topo.join(name)))?;
+
+    parse_range_list_str(&list).ok()?.into_iter().min()
+}
+
+/// Parses sysfs cache sizes: "32768K" (the kernel's own format), "2M",
+/// bare bytes, and the "KiB"/"MiB" spellings some vendor kernels and
+/// fixture tools emit - case-insensitive, optional space before the unit.
+/// 0 on anything else, including overflow.
 fn parse_cache_size(s: &str) -> u64 {
-    if s.is_empty() {
-        return 0;
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (digits, unit) = s.split_at(split);
+
+    let mult: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return 0,
+    };
+
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|v| v.checked_mul(mult))
+        .unwrap_or(0)
+}
+
+/// What one LP's sysfs says about its core-private caches - the part SMT
+/// siblings must agree on. Sizes are 0 where a cache is not listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PrivateCaches {
+    l1d: u64,
+    l1i: u64,
+    l2: u64,
+    /// Lowest LP of the L2's `shared_cpu_list`.
+    l2_first: Option<usize>,
+}
+
+/// Reads the L1d/L1i/L2 entries under `cpu<os_id>/cache`.
+fn read_private_caches(cpu_base: &Path, os_id: u16) -> PrivateCaches {
+    let mut caches = PrivateCaches::default();
+
+    for index in 0..10u32 {
+        let idx_base = cpu_base.join(format!("cpu{}/cache/index{}", os_id, index));
+        let Some(level) = read_u64(&idx_base.join("level")) else {
+            break;
+        };
+
+        let size = read_str(&idx_base.join("size"))
+            .map(|s| parse_cache_size(&s))
+            .unwrap_or(0);
+
+        match (level, read_str(&idx_base.join("type")).as_deref()) {
+            (1, Some("Data")) => caches.l1d = size,
+            (1, Some("Instruction")) => caches.l1i = size,
+            (1, _) => {
+                caches.l1d = size;
+                caches.l1i = size;
+            }
+            (2, _) => {
+                caches.l2 = size;
+                caches.l2_first = read_str(&idx_base.join("shared_cpu_list"))
+                    .and_then(|list| parse_range_list_str(&list).ok())
+                    .and_then(|ids| ids.into_iter().min());
+            }
+            _ => {}
+        }
     }
 
-    let (digits, mult) = match s.as_bytes()[s.len() - 1] {
-        b'K' => (&s[..s.len() - 1], 1024u64),
-        b'M' => (&s[..s.len() - 1], 1024 * 1024),
-        _ => (s, 1),
-    };
+    caches
+}
 
-    digits.parse::<u64>().map(|v| v * mult).unwrap_or(0)
+/// Decodes a cache's sysfs `write_policy` (`WriteBack` / `WriteThrough`).
+fn parse_write_policy(s: &str) -> Option<WritePolicy> {
+    match s.trim() {
+        "WriteBack" => Some(WritePolicy::WriteBack),
+        "WriteThrough" => Some(WritePolicy::WriteThrough),
+        _ => None,
+    }
+}
+
+/// Package 0's RAPL long-term power limit (PL1, the TDP-derived sustained
+/// budget) in whole watts, from `class/powercap/intel-rapl:0`. The AMD Zen
+/// RAPL driver registers under the same name.
+fn read_rapl_tdp_watts(sysfs_root: &Path) -> Option<u32> {
+    let zone = sysfs_root.join("class/powercap/intel-rapl:0");
+
+    // constraint_0 is long_term on every RAPL driver to date; trust the name
+    // over the index where the kernel provides one.
+    if read_str(&zone.join("constraint_0_name")).is_some_and(|name| name != "long_term") {
+        return None;
+    }
+
+    parse_power_uw(&read_str(&zone.join("constraint_0_power_limit_uw"))?)
+}
+
+/// Parses a powercap `*_uw` value into whole watts, rounded to nearest.
+/// `None` for unparseable input and for 0 (limit not programmed).
+fn parse_power_uw(s: &str) -> Option<u32> {
+    let uw: u64 = s.parse().ok()?;
+    let watts = uw.saturating_add(500_000) / 1_000_000;
+
+    u32::try_from(watts).ok().filter(|&w| w != 0)
+}
+
+/// Parses `devices/system/cpu/smt/control` into "hardware supports SMT".
+///
+/// `on`/`off`/`forceoff` all describe SMT-capable hardware; `notsupported`
+/// means none. `notimplemented` (no SMT control on this arch) and anything
+/// unrecognised yield `None` - no signal either way.
+fn parse_smt_control(s: &str) -> Option<bool> {
+    match s {
+        "on" | "off" | "forceoff" => Some(true),
+        "notsupported" => Some(false),
+        _ => None,
+    }
+}
+
+/// Converts a cpufreq kHz value to MHz; `None` for 0 (an unset limit).
+fn khz_to_mhz(khz: u64) -> Option<u32> {
+    u32::try_from(khz / 1000).ok().filter(|&mhz| mhz != 0)
 }
 
 /// Detection against explicit filesystem roots - the fixture-test seam.
@@ -67,48 +206,88 @@ fn parse_cache_size(s: &str) -> u64 {
 /// `sysfs_root`/`procfs_root` replace `/sys` and `/proc`; recorded fixture
 /// trees from the shared fixture corpus drive the full pipeline through this
 /// function.
-pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo> {
+#[cfg(test)]
+pub(crate) fn detect_at(
+    sysfs_root: &Path,
+    procfs_root: &Path,
+    options: &DetectOptions,
+) -> Result<CpuInfo> {
+    detect_at_with_progress(sysfs_root, procfs_root, options, &mut |_, _| {})
+}
+
+/// [`detect_at`] reporting each phase, and the cache walk per LP, to
+/// `progress`.
+pub(crate) fn detect_at_with_progress(
+    sysfs_root: &Path,
+    procfs_root: &Path,
+    options: &DetectOptions,
+    progress: &mut dyn FnMut(DetectionPhase, f32),
+) -> Result<CpuInfo> {
     let cpu_base = sysfs_root.join("devices/system/cpu");
     if !cpu_base.exists() {
-        return Err(Error::Detection(format!(
-            "CPU sysfs path not found: {:?}",
-            cpu_base
-        )));
+        return Err(Error::Detection(
+            format!("CPU sysfs path not found: {:?}", cpu_base).into(),
+        ));
     }
 
+    let mut timer = PhaseTimer::start("linux", progress);
+
     // --- 1. Online LPs ---
     let online_str = read_str(&cpu_base.join("online"))
-        .ok_or_else(|| Error::Detection("Failed to read cpu/online".to_string()))?;
+        .ok_or_else(|| Error::Detection("Failed to read cpu/online".into()))?;
     let mut online = parse_range_list_str(&online_str)?;
 
     online.sort_unstable();
     online.dedup();
 
     if online.is_empty() {
-        return Err(Error::Detection("No online CPUs reported".to_string()));
+        return Err(Error::Detection("No online CPUs reported".into()));
     }
 
     if *online.last().unwrap() > u16::MAX as usize {
-        return Err(Error::Detection(format!(
-            "Logical processor id {} exceeds the supported range",
-            online.last().unwrap()
-        )));
+        return Err(Error::Detection(
+            format!(
+                "Logical processor id {} exceeds the supported range",
+                online.last().unwrap()
+            )
+            .into(),
+        ));
     }
 
+    // Gaps papered over below, surfaced as CpuInfo::detection_warnings.
+    let mut detection_warnings: Vec<String> = Vec::new();
+
     // --- 2. Per-LP topology ---
     let mut lps: Vec<Lp> = Vec::with_capacity(online.len());
-    let mut core_keys: Vec<u32> = Vec::new(); // (package << 16) | core_id, dense by position
-    let mut socket_ids: Vec<u16> = Vec::new();
+    let mut missing_ids: Vec<usize> = Vec::new();
+    let mut core_keys: Vec<(GroupKey, GroupKey)> = Vec::new(); // (package, core), dense by position
+    let mut socket_ids: Vec<GroupKey> = Vec::new();
     let mut capacities: Vec<Option<u64>> = Vec::with_capacity(online.len());
 
     for &os_id in &online {
         let topo = cpu_base.join(format!("cpu{}/topology", os_id));
 
-        // NOTE: missing package/core ids default to 0 (partial sysfs can merge
-        // distinct cores into key (0,0) - known and accepted, exotic hardware only).
-        let pkg = read_u64(&topo.join("physical_package_id")).unwrap_or(0) as u16;
-        let core_id = read_u64(&topo.join("core_id")).unwrap_or(0) as u16;
-        let key = (u32::from(pkg) << 16) | u32::from(core_id);
+        // Minimal kernels can omit the id files while still exporting the
+        // sibling lists; those group just as well. Only with neither do ids
+        // default to 0 (merging distinct cores into one - surfaced as a
+        // detection warning, exotic hardware only).
+        let pkg_id = read_u64(&topo.join("physical_package_id"));
+        let native_core_id = read_u64(&topo.join("core_id")).map(|id| id as u32);
+        let pkg_key = pkg_id.map(GroupKey::Id).or_else(|| {
+            first_sibling(&topo, &["package_cpus_list", "core_siblings_list"])
+                .map(GroupKey::FirstSibling)
+        });
+        let core_key = native_core_id
+            .map(|id| GroupKey::Id(u64::from(id)))
+            .or_else(|| {
+                first_sibling(&topo, &["core_cpus_list", "thread_siblings_list"])
+                    .map(GroupKey::FirstSibling)
+            });
+        if pkg_key.is_none() || core_key.is_none() {
+            missing_ids.push(os_id);
+        }
+        let pkg = pkg_key.unwrap_or(GroupKey::Id(0));
+        let key = (pkg, core_key.unwrap_or(GroupKey::Id(0)));
 
         let (core_idx, smt_index) = match core_keys.iter().position(|&k| k == key) {
             Some(idx) => {
@@ -156,12 +335,34 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
             perf_hint: 0,
             // cpu_part stamped below from /proc/cpuinfo (0 when absent, x86).
             cpu_part: 0,
+            native_core_id,
+            // capacity stamped below from cpu_capacity (None when absent).
+            capacity: None,
+            freq_min_mhz: None,
+            freq_max_mhz: None,
         });
     }
 
     let core_count = core_keys.len() as u16;
     let socket_count = socket_ids.len() as u8;
 
+    if !missing_ids.is_empty() {
+        detection_warnings.push(format!(
+            "topology ids missing for {} of {} LPs: cores and sockets may be merged",
+            missing_ids.len(),
+            lps.len()
+        ));
+    }
+
+    // SMT capability vs state: `smt/control` is the one source that survives
+    // SMT being switched off (`off`/`forceoff` still mean capable hardware).
+    // NOTE(x86): cpuid cannot stand in - leaf 0xB reports the width of the
+    // CURRENT configuration, and the HTT bit is set on any multi-core package.
+    let smt_enabled = lps.iter().any(|lp| lp.smt_index > 0);
+    let smt_supported = read_str(&cpu_base.join("smt/control"))
+        .and_then(|control| parse_smt_control(&control))
+        .unwrap_or(smt_enabled);
+
     // --- 3. Kind pass 2: capacity thresholds for LPs without core_type ---
     // Classification is by THRESHOLD relative to max, never exact-equality
     // tiers (Intel ITMT favored cores give per-core jitter WITHIN the P tier).
@@ -174,10 +375,27 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
     let cap_min = present.iter().copied().min().unwrap_or(0);
     let capacity_applies = !present.is_empty() && cap_min < cap_max;
 
+    // Some LPs expose cpu_capacity and others do not (and have no core_type):
+    // the silent ones fall through to Performance below, which may be wrong.
+    let unclassified = lps
+        .iter()
+        .zip(&capacities)
+        .filter(|(lp, cap)| lp.kind == CoreKind::Unknown && cap.is_none())
+        .count();
+    if !present.is_empty() && unclassified > 0 {
+        detection_warnings.push(format!(
+            "P/E distinction assumed: cpu_capacity missing for {} LPs",
+            unclassified
+        ));
+    }
+
     for (lp, capacity) in lps.iter_mut().zip(capacities.iter()) {
         // perf_hint = raw kernel capacity regardless of how the kind was
         // decided (core_type machines may expose capacity too); 0 = absent.
         lp.perf_hint = capacity.unwrap_or(0).min(u16::MAX as u64) as u16;
+        // The kernel already normalizes cpu_capacity to 1024 for the biggest
+        // core (SCHED_CAPACITY_SCALE); clamp only against bogus values.
+        lp.capacity = capacity.map(|cap| cap.min(u64::from(Lp::MAX_CAPACITY)) as u16);
 
         if lp.kind != CoreKind::Unknown {
             continue;
@@ -197,11 +415,269 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
         };
     }
 
+    // x86 kernels expose no cpu_capacity; there the per-core max-turbo rank
+    // (Turbo Boost Max 3.0 / ITMT favored cores, AMD preferred cores) is ACPI
+    // CPPC `highest_perf`. Used for perf_hint only - kinds stay as decided.
+    if present.is_empty() {
+        for lp in lps.iter_mut() {
+            let highest_perf =
+                read_u64(&cpu_base.join(format!("cpu{}/acpi_cppc/highest_perf", lp.os_id)));
+            lp.perf_hint = highest_perf.unwrap_or(0).min(u16::MAX as u64) as u16;
+        }
+    }
+
+    timer.phase(DetectionPhase::Topology);
+
+    // --- 4-5. Caches (optional) ---
+    let DetectedCaches {
+        l3_domains,
+        l2_domains,
+        l1d,
+        l1i,
+        l2,
+    } = if options.caches {
+        detect_caches(
+            &cpu_base,
+            &mut lps,
+            core_count,
+            &mut detection_warnings,
+            &mut timer,
+        )
+    } else {
+        DetectedCaches::default()
+    };
+
+    timer.phase(DetectionPhase::Caches);
+
+    // --- 6. NUMA nodes ---
+    // Enumerate the nodes that actually exist. Prefer `node/online` (a
+    // cpulist-style range, e.g. "0-1" or "0,2-3") so SPARSE node ids - a
+    // depopulated socket, CXL/heterogeneous-memory node-id gaps - are handled.
+    // Fall back to scanning a bounded range and SKIPPING gaps; never `break` on
+    // the first gap (that truncated the count and left distant LPs on node 0).
+    let online_nodes: Vec<usize> = read_str(&sysfs_root.join("devices/system/node/online"))
+        .and_then(|s| parse_range_list_str(&s).ok())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| (0..=u8::MAX as usize).collect());
+    let mut present_nodes: u32 = 0;
+    let mut claimed = vec![false; lps.len()];
+    let mut degenerate = false;
+
+    for node in online_nodes {
+        let cpulist = sysfs_root.join(format!("devices/system/node/node{}/cpulist", node));
+        let Some(list) = read_str(&cpulist) else {
+            continue;
+        };
+
+        present_nodes += 1;
+
+        let _ = parse_range_list_with(&list, |id| {
+            if let Some((i, lp)) = lps
+                .iter_mut()
+                .enumerate()
+                .find(|(_, lp)| lp.os_id as usize == id)
+            {
+                // A CPU listed under more than one node is not a real NUMA
+                // partition (nodes must be disjoint) - degenerate/fake NUMA.
+                if claimed[i] {
+                    degenerate = true;
+                }
+
+                claimed[i] = true;
+                lp.numa_node = node as u8;
+            }
+        });
+    }
+
+    let mut numa_node_count = present_nodes.min(u8::MAX as u32) as u8;
+
+    if numa_node_count == 0 {
+        numa_node_count = 1;
+    }
+
+    // Degenerate NUMA (a CPU appeared in multiple nodes): collapse to one domain.
+    // Trigger seen in the wild is the Pi 5 / BCM2712: it splits RAM into 8 NUMA
+    // nodes (~1 GB each) but EVERY CPU is in EVERY node and the whole distance
+    // matrix is uniform (numactl: all 10) - i.e. zero access locality, one
+    // effective domain. Confirmed by lscpu + numactl, no `numa=` override, 3 units;
+    // not a parsing artifact. `numa_node_count` is a memory-domain contract, so N
+    // phantom domains would mislead NUMA-aware placement. Real NUMA partitions CPUs
+    // into disjoint nodes, so this never trips there. (A stricter signal would be
+    // "all node distances equal"; the CPU-overlap test already covers this case.)
+    if degenerate {
+        numa_node_count = 1;
+
+        for lp in lps.iter_mut() {
+            lp.numa_node = 0;
+        }
+    }
+
+    timer.phase(DetectionPhase::Numa);
+
+    // --- 7. Vendor / model / features ---
+    let mut vendor = Vendor::Unknown;
+    let mut model_name = "Unknown".to_string();
+    let mut cpu_features = CpuFeatures::default();
+
+    #[cfg(target_arch = "x86_64")]
+    crate::platform::common_x86_64::detect_via_cpuid(
+        &mut vendor,
+        &mut model_name,
+        &mut cpu_features,
+    );
+
+    if vendor == Vendor::Unknown || model_name == "Unknown" || cpu_features.is_empty() {
+        proc::detect_via_proc_cpuinfo(procfs_root, &mut vendor, &mut model_name, &mut cpu_features);
+    }
+
+    // --- 7b. Per-core microarch (ARM MIDR part) from /proc/cpuinfo ---
+    // Runs UNCONDITIONALLY, independent of the identity fallback above: the
+    // `CPU part` is per-core on heterogeneous ARM (big cores report a different
+    // part than little cores), so it cannot ride the first-block identity read.
+    // On x86 the file has no such field, so every LP stays 0.
+    let cpuinfo = std::fs::read_to_string(procfs_root.join("cpuinfo")).ok();
+    if let Some(content) = &cpuinfo {
+        for (os_id, part) in proc::parse_cpu_parts(content) {
+            if let Some(lp) = lps.iter_mut().find(|lp| lp.os_id == os_id) {
+                lp.cpu_part = part;
+            }
+        }
+    }
+
+    // Microcode: the x86 microcode driver's sysfs file, else the cpuinfo
+    // field (present even when the driver is not loaded).
+    let microcode = lps
+        .first()
+        .and_then(|lp| read_str(&cpu_base.join(format!("cpu{}/microcode/version", lp.os_id))))
+        .filter(|v| !v.is_empty())
+        .or_else(|| cpuinfo.as_deref().and_then(proc::parse_microcode));
+
+    let tdp_watts = read_rapl_tdp_watts(sysfs_root);
+
+    // --- 7c. Clock frequencies ---
+    // cpufreq reports kHz per policy; take the highest across LPs so a hybrid
+    // part reports its P-core clocks. `base_frequency` is intel_pstate /
+    // amd-pstate only; cpuid leaf 0x16 fills what sysfs lacks on x86.
+    let freq_mhz = |file: &str| {
+        lps.iter()
+            .filter_map(|lp| read_u64(&cpu_base.join(format!("cpu{}/cpufreq/{}", lp.os_id, file))))
+            .max()
+            .map(|khz| (khz / 1000) as u32)
+            .filter(|&mhz| mhz != 0)
+    };
+    let (base_frequency_mhz, max_frequency_mhz) = if options.frequencies {
+        (freq_mhz("base_frequency"), freq_mhz("cpuinfo_max_freq"))
+    } else {
+        (None, None)
+    };
+
+    // Governor limits, per LP: what the active policy allows right now.
+    if options.frequencies {
+        for lp in &mut lps {
+            let policy = cpu_base.join(format!("cpu{}/cpufreq", lp.os_id));
+            lp.freq_min_mhz = read_u64(&policy.join("scaling_min_freq")).and_then(khz_to_mhz);
+            lp.freq_max_mhz = read_u64(&policy.join("scaling_max_freq")).and_then(khz_to_mhz);
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    let (base_frequency_mhz, max_frequency_mhz) = if options.frequencies {
+        let (base, max) = crate::platform::common_x86_64::detect_frequency_via_cpuid();
+        (base_frequency_mhz.or(base), max_frequency_mhz.or(max))
+    } else {
+        (base_frequency_mhz, max_frequency_mhz)
+    };
+
+    #[cfg(target_arch = "x86_64")]
+    let clflush_line_size = crate::platform::common_x86_64::detect_clflush_line_size();
+    #[cfg(not(target_arch = "x86_64"))]
+    let clflush_line_size = None;
+
+    let (has_invariant_tsc, tsc_frequency_hz) = crate::platform::detect_timestamp_counter();
+    let x86_signature = crate::platform::detect_x86_signature();
+    let avx512_downclock_risk = crate::platform::detect_avx512_downclock_risk(vendor);
+
+    timer.phase(DetectionPhase::Identity);
+
+    // --- 8. Kind core counts ---
+    let mut kind_core_counts = [0u16; CoreKind::COUNT];
+    for lp in &lps {
+        if lp.smt_index == 0 {
+            kind_core_counts[lp.kind.index()] += 1;
+        }
+    }
+
+    let mut info = CpuInfo {
+        lps,
+        core_count,
+        socket_count,
+        numa_node_count,
+        smt_supported,
+        kind_core_counts,
+        l3_domains,
+        l2_domains,
+        l1d,
+        l1i,
+        l2,
+        vendor,
+        model_name,
+        features: cpu_features,
+        base_frequency_mhz,
+        max_frequency_mhz,
+        clflush_line_size,
+        has_invariant_tsc,
+        tsc_frequency_hz,
+        microcode,
+        tdp_watts,
+        x86_signature,
+        avx512_downclock_risk,
+        detection_warnings,
+        logical_processor_ids: Default::default(),
+        schema_version: CpuInfo::SCHEMA_VERSION,
+    };
+    crate::platform::fill_cache_inclusiveness(&mut info);
+    info.finalize(options);
+    timer.finish();
+    Ok(info)
+}
+
+/// The cache part of detection (steps 4-5), skipped wholesale by
+/// [`DetectOptions::caches`] - the per-LP `cache/index*` walk is what
+/// dominates detection time on large machines.
+#[derive(Default)]
+struct DetectedCaches {
+    l3_domains: Vec<L3Domain>,
+    l2_domains: Vec<L2Domain>,
+    l1d: [CacheInfo; CoreKind::COUNT],
+    l1i: [CacheInfo; CoreKind::COUNT],
+    l2: [CacheInfo; CoreKind::COUNT],
+}
+
+/// Steps 4-5: stamps every LP's L3/L2 domain, demotes L3-less island
+/// E-cores (kind pass 3) and reads the per-kind L1/L2 descriptors.
+fn detect_caches(
+    cpu_base: &Path,
+    lps: &mut [Lp],
+    core_count: u16,
+    detection_warnings: &mut Vec<String>,
+    timer: &mut PhaseTimer,
+) -> DetectedCaches {
+    // Progress: one unit per LP read - all LPs for L3, each core's first LP
+    // for L2, all LPs again for the sibling check. Reported as each read
+    // starts; the phase's own 1.0 marks the end.
+    let steps = (2 * lps.len() + usize::from(core_count)) as f32;
+    let mut done = 0;
+    let mut step = |timer: &mut PhaseTimer| {
+        timer.progress(DetectionPhase::Caches, (done as f32 / steps).min(1.0));
+        done += 1;
+    };
+
     // --- 4. L3 domains, content-keyed ---
     let mut l3_domains: Vec<L3Domain> = Vec::new();
     let mut domain_first_lp: Vec<usize> = Vec::new();
 
     for lp in lps.iter_mut() {
+        step(timer);
         for index in 0..10u32 {
             let idx_base = cpu_base.join(format!("cpu{}/cache/index{}", lp.os_id, index));
 
@@ -271,20 +747,41 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
         }
     }
 
-    for lp in &lps {
+    for lp in lps.iter() {
         if lp.smt_index == 0 && lp.l3_domain != Lp::NO_L3 {
             l3_domains[lp.l3_domain as usize].core_count += 1;
         }
     }
 
+    // Kind pass 3 (needs step 4): Intel's low-power island E-cores (Meteor /
+    // Lunar Lake SoC tile) report the same core_type as compute-tile E-cores
+    // and expose no cpu_capacity; what sets them apart is sitting outside
+    // every L3. Demote L3-less Efficiency LPs - but only when other Efficiency
+    // LPs DO have an L3, so a machine with no L3 info at all is left alone.
+    let efficiency_has_l3 = lps
+        .iter()
+        .any(|lp| lp.kind == CoreKind::Efficiency && lp.l3_domain != Lp::NO_L3);
+    if efficiency_has_l3 {
+        for lp in lps.iter_mut() {
+            if lp.kind == CoreKind::Efficiency && lp.l3_domain == Lp::NO_L3 {
+                lp.kind = CoreKind::LpEfficiency;
+            }
+        }
+    }
+
     // --- 4b. L2 domains, content-keyed (the step-4 loop at level 2) ---
     // NOTE(lifecycle): the ascending-lowest-LP order of `l2_domains` comes from
     // iterating `lps` in ascending os_id order (the online enumeration); a domain
     // is first seen at its lowest member, so new domains append in that order.
+    // The L2 is core-private, so only each core's first LP (smt_index 0) is
+    // read; its SMT siblings inherit that domain below rather than keying
+    // their own (a sibling whose sysfs disagrees would otherwise spawn a
+    // second domain for the same physical cache).
     let mut l2_domains: Vec<L2Domain> = Vec::new();
     let mut l2_first_lp: Vec<usize> = Vec::new();
 
-    for lp in lps.iter_mut() {
+    for lp in lps.iter_mut().filter(|lp| lp.smt_index == 0) {
+        step(timer);
         for index in 0..10u32 {
             let idx_base = cpu_base.join(format!("cpu{}/cache/index{}", lp.os_id, index));
 
@@ -355,18 +852,51 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
         }
     }
 
-    for lp in &lps {
-        if lp.smt_index == 0 && lp.l2_domain != Lp::NO_L2 {
+    let mut core_l2 = vec![Lp::NO_L2; core_count as usize];
+    for lp in lps.iter().filter(|lp| lp.smt_index == 0) {
+        core_l2[lp.core as usize] = lp.l2_domain;
+
+        if lp.l2_domain != Lp::NO_L2 {
             l2_domains[lp.l2_domain as usize].core_count += 1;
         }
     }
 
+    for lp in lps.iter_mut().filter(|lp| lp.smt_index > 0) {
+        lp.l2_domain = core_l2[lp.core as usize];
+    }
+
+    // --- 4c. SMT sibling agreement ---
+    // Siblings share every core-private cache, so their sysfs entries must
+    // match the first LP's. A mismatch is a kernel/firmware inconsistency:
+    // the first LP's view was used above, and the disagreement is surfaced.
+    let mut core_caches: Vec<Option<PrivateCaches>> = vec![None; core_count as usize];
+    let mut disagrees = vec![false; core_count as usize];
+
+    for lp in lps.iter() {
+        step(timer);
+        let caches = read_private_caches(cpu_base, lp.os_id);
+        let primary = &mut core_caches[lp.core as usize];
+
+        match primary {
+            None => *primary = Some(caches),
+            Some(primary) => disagrees[lp.core as usize] |= *primary != caches,
+        }
+    }
+
+    let disagreeing = disagrees.iter().filter(|&&d| d).count();
+    if disagreeing > 0 {
+        detection_warnings.push(format!(
+            "SMT siblings disagree on L1/L2 caches for {} of {} cores: first LP's caches used",
+            disagreeing, core_count
+        ));
+    }
+
     // --- 5. Per-kind L1/L2 from the first LP of each (final) kind ---
     let mut l1d = [CacheInfo::default(); CoreKind::COUNT];
     let mut l1i = [CacheInfo::default(); CoreKind::COUNT];
     let mut l2 = [CacheInfo::default(); CoreKind::COUNT];
 
-    for lp in &lps {
+    for lp in lps.iter() {
         let k = lp.kind.index();
 
         if l1d[k].size_bytes != 0 && l2[k].size_bytes != 0 {
@@ -391,6 +921,10 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
                     .unwrap_or(0),
                 line_bytes: read_u64(&idx_base.join("coherency_line_size")).unwrap_or(0) as u16,
                 shared_by: 0,
+                write_policy: read_str(&idx_base.join("write_policy"))
+                    .and_then(|s| parse_write_policy(&s)),
+                inclusive: None,
+                estimated_latency_cycles: None,
             };
 
             if let Some(shared) = read_str(&idx_base.join("shared_cpu_list")) {
@@ -414,121 +948,11 @@ pub(crate) fn detect_at(sysfs_root: &Path, procfs_root: &Path) -> Result<CpuInfo
         }
     }
 
-    // --- 6. NUMA nodes ---
-    // Enumerate the nodes that actually exist. Prefer `node/online` (a
-    // cpulist-style range, e.g. "0-1" or "0,2-3") so SPARSE node ids - a
-    // depopulated socket, CXL/heterogeneous-memory node-id gaps - are handled.
-    // Fall back to scanning a bounded range and SKIPPING gaps; never `break` on
-    // the first gap (that truncated the count and left distant LPs on node 0).
-    let online_nodes: Vec<usize> = read_str(&sysfs_root.join("devices/system/node/online"))
-        .and_then(|s| parse_range_list_str(&s).ok())
-        .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| (0..=u8::MAX as usize).collect());
-    let mut present_nodes: u32 = 0;
-    let mut claimed = vec![false; lps.len()];
-    let mut degenerate = false;
-
-    for node in online_nodes {
-        let cpulist = sysfs_root.join(format!("devices/system/node/node{}/cpulist", node));
-        let Some(list) = read_str(&cpulist) else {
-            continue;
-        };
-
-        present_nodes += 1;
-
-        let _ = parse_range_list_with(&list, |id| {
-            if let Some((i, lp)) = lps
-                .iter_mut()
-                .enumerate()
-                .find(|(_, lp)| lp.os_id as usize == id)
-            {
-                // A CPU listed under more than one node is not a real NUMA
-                // partition (nodes must be disjoint) - degenerate/fake NUMA.
-                if claimed[i] {
-                    degenerate = true;
-                }
-
-                claimed[i] = true;
-                lp.numa_node = node as u8;
-            }
-        });
-    }
-
-    let mut numa_node_count = present_nodes.min(u8::MAX as u32) as u8;
-
-    if numa_node_count == 0 {
-        numa_node_count = 1;
-    }
-
-    // Degenerate NUMA (a CPU appeared in multiple nodes): collapse to one domain.
-    // Trigger seen in the wild is the Pi 5 / BCM2712: it splits RAM into 8 NUMA
-    // nodes (~1 GB each) but EVERY CPU is in EVERY node and the whole distance
-    // matrix is uniform (numactl: all 10) - i.e. zero access locality, one
-    // effective domain. Confirmed by lscpu + numactl, no `numa=` override, 3 units;
-    // not a parsing artifact. `numa_node_count` is a memory-domain contract, so N
-    // phantom domains would mislead NUMA-aware placement. Real NUMA partitions CPUs
-    // into disjoint nodes, so this never trips there. (A stricter signal would be
-    // "all node distances equal"; the CPU-overlap test already covers this case.)
-    if degenerate {
-        numa_node_count = 1;
-
-        for lp in lps.iter_mut() {
-            lp.numa_node = 0;
-        }
-    }
-
-    // --- 7. Vendor / model / features ---
-    let mut vendor = Vendor::Unknown;
-    let mut model_name = "Unknown".to_string();
-    let mut cpu_features = CpuFeatures::default();
-
-    #[cfg(target_arch = "x86_64")]
-    crate::platform::common_x86_64::detect_via_cpuid(
-        &mut vendor,
-        &mut model_name,
-        &mut cpu_features,
-    );
-
-    if vendor == Vendor::Unknown || model_name == "Unknown" || cpu_features.is_empty() {
-        proc::detect_via_proc_cpuinfo(procfs_root, &mut vendor, &mut model_name, &mut cpu_features);
-    }
-
-    // --- 7b. Per-core microarch (ARM MIDR part) from /proc/cpuinfo ---
-    // Runs UNCONDITIONALLY, independent of the identity fallback above: the
-    // `CPU part` is per-core on heterogeneous ARM (big cores report a different
-    // part than little cores), so it cannot ride the first-block identity read.
-    // On x86 the file has no such field, so every LP stays 0.
-    if let Ok(content) = std::fs::read_to_string(procfs_root.join("cpuinfo")) {
-        for (os_id, part) in proc::parse_cpu_parts(&content) {
-            if let Some(lp) = lps.iter_mut().find(|lp| lp.os_id == os_id) {
-                lp.cpu_part = part;
-            }
-        }
-    }
-
-    // --- 8. Kind core counts ---
-    let mut kind_core_counts = [0u16; CoreKind::COUNT];
-    for lp in &lps {
-        if lp.smt_index == 0 {
-            kind_core_counts[lp.kind.index()] += 1;
-        }
-    }
-
-    let mut info = CpuInfo {
-        lps,
-        core_count,
-        socket_count,
-        numa_node_count,
-        kind_core_counts,
+    DetectedCaches {
         l3_domains,
         l2_domains,
         l1d,
         l1i,
         l2,
-        vendor,
-        model_name,
-        features: cpu_features,
-    };
-    info.normalize_domain_order();
-    Ok(info)
+    }
 }
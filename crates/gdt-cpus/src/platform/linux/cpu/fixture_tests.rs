@@ -4,8 +4,13 @@
 //! plus an `expected.txt` of flat `key=value` assertions - the checker and
 //! the format contract live in `crate::platform::fixture_expected`.
 
-use super::detect_at;
-use crate::platform::fixture_expected::{check_expected, fixture_root};
+use std::path::PathBuf;
+
+use super::{
+    detect_at, detect_at_with_progress, parse_cache_size, parse_power_uw, parse_write_policy,
+};
+use crate::platform::fixture_expected::{assert_invariants, check_expected, fixture_root};
+use crate::{CoreKind, CpuInfo, DetectOptions, DetectionPhase, WritePolicy};
 
 /// Runs detection against a fixture tree and checks every `expected.txt` line.
 fn run_fixture(name: &str) {
@@ -23,7 +28,7 @@ fn run_fixture(name: &str) {
         return;
     }
 
-    let info = detect_at(&root.join("sys"), &root.join("proc"))
+    let info = detect_at(&root.join("sys"), &root.join("proc"), &DetectOptions::FULL)
         .unwrap_or_else(|e| panic!("detect_at failed for fixture {}: {}", name, e));
 
     check_expected(&info, name);
@@ -84,3 +89,578 @@ fn fixture_numa_sparse_node_ids() {
     // counted only contiguous nodes and stranded later-node LPs on node 0.
     run_fixture("sysfs-numa-sparse");
 }
+
+/// A minimal sysfs tree written to the temp dir, for edge cases too narrow to
+/// justify a recorded fixture in the shared corpus. Removed on drop.
+struct SyntheticTree {
+    root: PathBuf,
+}
+
+impl SyntheticTree {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "gdt-cpus-synthetic-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("proc")).unwrap();
+        Self { root }
+    }
+
+    /// Writes `contents` to `sys/<path>`, creating parent directories.
+    fn sys(&self, path: &str, contents: &str) -> &Self {
+        let file = self.root.join("sys").join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, contents).unwrap();
+        self
+    }
+
+    /// Adds `cpu<os_id>` with the given package/core ids.
+    fn cpu(&self, os_id: usize, package: u32, core_id: u32) -> &Self {
+        let topo = format!("devices/system/cpu/cpu{}/topology", os_id);
+        self.sys(
+            &format!("{}/physical_package_id", topo),
+            &package.to_string(),
+        )
+        .sys(&format!("{}/core_id", topo), &core_id.to_string())
+    }
+
+    fn detect(&self) -> CpuInfo {
+        self.detect_with(&DetectOptions::FULL)
+    }
+
+    fn detect_with(&self, options: &DetectOptions) -> CpuInfo {
+        let info = detect_at(&self.root.join("sys"), &self.root.join("proc"), options)
+            .unwrap_or_else(|e| panic!("detect_at failed for synthetic tree: {}", e));
+        assert_invariants(&info);
+        info
+    }
+}
+
+impl Drop for SyntheticTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn synthetic_smt_switched_off_is_supported_but_disabled() {
+    // SMT-capable part with SMT disabled: one LP per core, but the kernel's
+    // control knob still says the hardware has it.
+    let tree = SyntheticTree::new("smt-off");
+    tree.sys("devices/system/cpu/online", "0-1")
+        .sys("devices/system/cpu/smt/control", "off")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 1);
+
+    let info = tree.detect();
+    assert_eq!(info.core_count, 2);
+    assert!(info.smt_supported);
+    assert!(!info.is_smt_enabled());
+}
+
+#[test]
+fn synthetic_sibling_lists_group_without_topology_ids() {
+    // Two sockets x two SMT-2 cores, interleaved the usual Linux way, on a
+    // kernel exporting only the sibling lists (no physical_package_id /
+    // core_id) and no /proc/cpuinfo at all.
+    let tree = SyntheticTree::new("siblings-only");
+    tree.sys("devices/system/cpu/online", "0-7");
+    for os_id in 0..8usize {
+        let core = os_id % 4;
+        let package = if core < 2 { "0-1,4-5" } else { "2-3,6-7" };
+        let topo = format!("devices/system/cpu/cpu{}/topology", os_id);
+
+        tree.sys(
+            &format!("{}/thread_siblings_list", topo),
+            &format!("{},{}", core, core + 4),
+        )
+        .sys(&format!("{}/core_siblings_list", topo), package);
+    }
+
+    let info = tree.detect();
+    assert_eq!(info.core_count, 4);
+    assert_eq!(info.socket_count, 2);
+    assert!(info.is_smt_enabled());
+    assert_eq!(info.socket_mask(0).iter().collect::<Vec<_>>(), [0, 1, 4, 5]);
+    assert_eq!(info.socket_mask(1).iter().collect::<Vec<_>>(), [2, 3, 6, 7]);
+    for lp in &info.lps {
+        assert_eq!(lp.core as usize, lp.os_id as usize % 4);
+        assert_eq!(lp.smt_index, u8::from(lp.os_id >= 4));
+        assert_eq!(lp.native_core_id, None);
+    }
+    assert!(
+        !info
+            .detection_warnings
+            .iter()
+            .any(|w| w.starts_with("topology ids missing")),
+        "{:?}",
+        info.detection_warnings
+    );
+}
+
+#[test]
+fn synthetic_smt_without_control_follows_siblings() {
+    // No smt/control (older kernels, arches without SMT control): capability
+    // can only be inferred from siblings actually present.
+    let tree = SyntheticTree::new("smt-siblings");
+    tree.sys("devices/system/cpu/online", "0-3")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 1)
+        .cpu(2, 0, 0)
+        .cpu(3, 0, 1);
+
+    let info = tree.detect();
+    assert_eq!(info.core_count, 2);
+    assert!(info.smt_supported);
+    assert!(info.is_smt_enabled());
+}
+
+#[test]
+fn synthetic_native_core_id_keeps_sparse_kernel_numbering() {
+    // Kernel core ids are sparse and per-package; the dense `core` index is
+    // the library's, `native_core_id` must be the kernel's number verbatim.
+    let tree = SyntheticTree::new("native-core-id");
+    tree.sys("devices/system/cpu/online", "0-2")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 8)
+        .cpu(2, 1, 8);
+
+    let info = tree.detect();
+    let native: Vec<_> = info.lps.iter().map(|lp| lp.native_core_id).collect();
+    let dense: Vec<_> = info.lps.iter().map(|lp| lp.core).collect();
+    assert_eq!(native, [Some(0), Some(8), Some(8)]);
+    assert_eq!(dense, [0, 1, 2]);
+}
+
+#[test]
+fn synthetic_shuffled_native_ids_order_cores() {
+    // Firmware that enumerates cores out of order: the dense ids follow the
+    // LP ids, the native order follows the kernel's core_id.
+    let tree = SyntheticTree::new("native-core-order");
+    tree.sys("devices/system/cpu/online", "0-4")
+        .cpu(0, 0, 12)
+        .cpu(1, 0, 4)
+        .cpu(2, 0, 8)
+        .cpu(3, 1, 2)
+        .cpu(4, 1, 0);
+
+    let info = tree.detect();
+    assert_eq!(info.cores_by_native_id(0), [1, 2, 0]);
+    assert_eq!(info.cores_by_native_id(1), [4, 3]);
+    assert!(info.cores_by_native_id(2).is_empty());
+}
+
+#[test]
+fn synthetic_cacheless_container_reports_missing_caches() {
+    // Containers commonly mask `cpuN/cache`: topology reads fine, caches don't.
+    let tree = SyntheticTree::new("no-cache");
+    tree.sys("devices/system/cpu/online", "0-1")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 1);
+
+    let info = tree.detect();
+    assert_eq!(info.core_count, 2);
+    assert!(!info.has_complete_topology());
+    assert_eq!(info.detection_warnings, ["cache info unavailable"]);
+}
+
+#[test]
+fn synthetic_partial_capacity_flags_assumed_kinds() {
+    // cpu_capacity on only one LP: the other cannot be classified and is
+    // assumed Performance - detection must say so.
+    let tree = SyntheticTree::new("partial-capacity");
+    tree.sys("devices/system/cpu/online", "0-1")
+        .sys("devices/system/cpu/cpu0/cpu_capacity", "1024")
+        .sys("devices/system/cpu/cpu0/cache/index0/level", "1")
+        .sys("devices/system/cpu/cpu0/cache/index0/type", "Data")
+        .sys("devices/system/cpu/cpu0/cache/index0/size", "32K")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 1);
+
+    let info = tree.detect();
+    assert_eq!(info.detection_warnings.len(), 1);
+    assert!(info.detection_warnings[0].starts_with("P/E distinction assumed"));
+}
+
+#[test]
+fn synthetic_cppc_highest_perf_marks_favored_cores() {
+    // Turbo Boost Max 3.0 part: no cpu_capacity (x86), CPPC ranks two of the
+    // four cores above the rest.
+    let tree = SyntheticTree::new("cppc-favored");
+    tree.sys("devices/system/cpu/online", "0-3");
+    for (os_id, highest_perf) in [(0, "48"), (1, "53"), (2, "48"), (3, "53")] {
+        tree.sys(
+            &format!("devices/system/cpu/cpu{os_id}/acpi_cppc/highest_perf"),
+            highest_perf,
+        )
+        .cpu(os_id, 0, os_id as u32);
+    }
+
+    let info = tree.detect();
+    assert_eq!(info.num_performance_cores(), 4);
+    let favored: Vec<u16> = info.favored_cores().iter().map(|lp| lp.os_id).collect();
+    assert_eq!(favored, [1, 3]);
+    assert_eq!(info.cores_by_preference()[0].perf_hint, 53);
+}
+
+#[test]
+fn synthetic_biglittle_capacities_sum() {
+    // RK3588 shape: four Cortex-A76 at 1024 and four Cortex-A55 at 414,
+    // exactly as the kernel reports them.
+    let tree = SyntheticTree::new("capacity-sum");
+    tree.sys("devices/system/cpu/online", "0-7");
+    for os_id in 0..8usize {
+        let capacity = if os_id < 4 { "414" } else { "1024" };
+        tree.sys(
+            &format!("devices/system/cpu/cpu{os_id}/cpu_capacity"),
+            capacity,
+        )
+        .cpu(os_id, 0, os_id as u32);
+    }
+
+    let info = tree.detect();
+    let capacities: Vec<_> = info.lps.iter().map(|lp| lp.capacity).collect();
+    assert_eq!(capacities[..4], [Some(414); 4]);
+    assert_eq!(capacities[4..], [Some(1024); 4]);
+    assert_eq!(info.num_efficiency_cores(), 4);
+    assert_eq!(info.total_capacity(), Some(4 * 414 + 4 * 1024));
+}
+
+#[test]
+fn synthetic_biglittle_prime_core_is_its_own_cluster() {
+    // Snapdragon 8-series shape: one prime core, three big cores, four
+    // little ones, each with a private L2 - clusters follow capacity.
+    let tree = SyntheticTree::new("capacity-clusters");
+    tree.sys("devices/system/cpu/online", "0-7");
+    for os_id in 0..8usize {
+        let capacity = match os_id {
+            0..4 => "512",
+            4..7 => "871",
+            _ => "1024",
+        };
+        tree.sys(
+            &format!("devices/system/cpu/cpu{os_id}/cpu_capacity"),
+            capacity,
+        )
+        .cpu(os_id, 0, os_id as u32);
+    }
+
+    let info = tree.detect();
+    assert_eq!(info.num_performance_cores(), 4);
+    assert_eq!(info.performance_cluster_count(), 2);
+    assert_eq!(info.efficiency_cluster_count(), 1);
+}
+
+#[test]
+fn synthetic_l3_less_e_cores_are_low_power_island() {
+    // Meteor Lake shape: one P-core and one E-core behind the L3, one SoC-tile
+    // E-core (same core_type) with no L3 at all.
+    let tree = SyntheticTree::new("lp-e-island");
+    tree.sys("devices/system/cpu/online", "0-2");
+    for (os_id, core_type) in [(0, "performance"), (1, "efficiency"), (2, "efficiency")] {
+        let cpu = format!("devices/system/cpu/cpu{os_id}");
+        tree.sys(&format!("{cpu}/topology/core_type"), core_type)
+            .sys(&format!("{cpu}/cache/index0/level"), "1")
+            .sys(&format!("{cpu}/cache/index0/type"), "Data")
+            .sys(&format!("{cpu}/cache/index0/size"), "32K")
+            .cpu(os_id, 0, os_id as u32);
+        if os_id < 2 {
+            tree.sys(&format!("{cpu}/cache/index1/level"), "3")
+                .sys(&format!("{cpu}/cache/index1/type"), "Unified")
+                .sys(&format!("{cpu}/cache/index1/size"), "24576K")
+                .sys(&format!("{cpu}/cache/index1/shared_cpu_list"), "0-1");
+        }
+    }
+
+    let info = tree.detect();
+    let kinds: Vec<CoreKind> = info.lps.iter().map(|lp| lp.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            CoreKind::Performance,
+            CoreKind::Efficiency,
+            CoreKind::LpEfficiency
+        ]
+    );
+    assert_eq!(info.num_lp_efficiency_cores(), 1);
+    assert!(info.is_hybrid());
+}
+
+/// Two SMT-2 cores (LPs 0+2, 1+3), each LP listing an L1d and an L2 shared
+/// with its sibling.
+fn smt_pair_tree(name: &str) -> SyntheticTree {
+    let tree = SyntheticTree::new(name);
+    tree.sys("devices/system/cpu/online", "0-3");
+    for os_id in 0..4usize {
+        let cpu = format!("devices/system/cpu/cpu{os_id}");
+        let siblings = format!("{},{}", os_id % 2, os_id % 2 + 2);
+        tree.sys(&format!("{cpu}/cache/index0/level"), "1")
+            .sys(&format!("{cpu}/cache/index0/type"), "Data")
+            .sys(&format!("{cpu}/cache/index0/size"), "48K")
+            .sys(&format!("{cpu}/cache/index0/shared_cpu_list"), &siblings)
+            .sys(&format!("{cpu}/cache/index1/level"), "2")
+            .sys(&format!("{cpu}/cache/index1/type"), "Unified")
+            .sys(&format!("{cpu}/cache/index1/size"), "1280K")
+            .sys(&format!("{cpu}/cache/index1/shared_cpu_list"), &siblings)
+            .cpu(os_id, 0, (os_id % 2) as u32);
+    }
+    tree
+}
+
+#[test]
+fn synthetic_smt_siblings_share_one_l2() {
+    let tree = smt_pair_tree("smt-l2");
+
+    let info = tree.detect();
+    assert_eq!(info.l2_domains.len(), 2);
+    for (d, domain) in info.l2_domains.iter().enumerate() {
+        assert_eq!(domain.core_count, 1);
+        assert_eq!(domain.mask.iter().collect::<Vec<_>>(), [d, d + 2]);
+    }
+    for lp in &info.lps {
+        assert_eq!(lp.l2_domain as usize, lp.os_id as usize % 2);
+    }
+    let p = CoreKind::Performance.index();
+    assert_eq!(info.l2[p].size_bytes, 1280 * 1024);
+    assert_eq!(info.l2[p].shared_by, 2);
+    assert!(
+        !info
+            .detection_warnings
+            .iter()
+            .any(|w| w.starts_with("SMT siblings disagree")),
+        "{:?}",
+        info.detection_warnings
+    );
+}
+
+#[test]
+fn synthetic_smt_sibling_cache_mismatch_keeps_first_lp() {
+    // LP 2 (core 0's second thread) claims a private, smaller L2: the core
+    // keeps LP 0's domain, no phantom domain appears, and it is reported.
+    let tree = smt_pair_tree("smt-l2-mismatch");
+    tree.sys("devices/system/cpu/cpu2/cache/index1/size", "1024K")
+        .sys("devices/system/cpu/cpu2/cache/index1/shared_cpu_list", "2");
+
+    let info = tree.detect();
+    assert_eq!(info.l2_domains.len(), 2);
+    assert_eq!(info.lps[2].l2_domain, info.lps[0].l2_domain);
+    assert_eq!(info.l2_domains[0].size_bytes, 1280 * 1024);
+    assert!(
+        info.detection_warnings.iter().any(|w| w
+            == "SMT siblings disagree on L1/L2 caches for 1 of 2 cores: first LP's caches used"),
+        "{:?}",
+        info.detection_warnings
+    );
+}
+
+#[test]
+fn synthetic_l1_and_l2_line_sizes_differ() {
+    // Each cache index reports its own coherency_line_size - an L2 with a
+    // wider line than the L1 keeps it.
+    let tree = smt_pair_tree("line-sizes");
+    for os_id in 0..4 {
+        let cache = format!("devices/system/cpu/cpu{os_id}/cache");
+        tree.sys(&format!("{cache}/index0/coherency_line_size"), "64")
+            .sys(&format!("{cache}/index1/coherency_line_size"), "128");
+    }
+
+    let info = tree.detect();
+    let p = CoreKind::Performance.index();
+    assert_eq!(info.l1d[p].line_bytes, 64);
+    assert_eq!(info.l2[p].line_bytes, 128);
+}
+
+#[test]
+fn cache_size_units_parse() {
+    assert_eq!(parse_cache_size("512K"), 512 << 10);
+    assert_eq!(parse_cache_size("32768"), 32768);
+    assert_eq!(parse_cache_size("2M"), 2 << 20);
+    assert_eq!(parse_cache_size("48k"), 48 << 10);
+    assert_eq!(parse_cache_size("1280KiB"), 1280 << 10);
+    assert_eq!(parse_cache_size("36 MiB\n"), 36 << 20);
+
+    for garbage in ["", "K", "12Q", "-1K", "1.5M", "99999999999999999999G"] {
+        assert_eq!(parse_cache_size(garbage), 0, "{garbage:?}");
+    }
+}
+
+#[test]
+fn cache_write_policy_parses() {
+    assert_eq!(
+        parse_write_policy("WriteBack\n"),
+        Some(WritePolicy::WriteBack)
+    );
+    assert_eq!(
+        parse_write_policy("WriteThrough"),
+        Some(WritePolicy::WriteThrough)
+    );
+    assert_eq!(parse_write_policy(""), None);
+}
+
+#[test]
+fn synthetic_write_policy_is_read_per_level() {
+    // ARM kernels export write_policy per cache index; absent means unknown.
+    let tree = smt_pair_tree("write-policy");
+    for os_id in 0..4 {
+        tree.sys(
+            &format!("devices/system/cpu/cpu{os_id}/cache/index0/write_policy"),
+            "WriteBack",
+        );
+    }
+
+    let info = tree.detect();
+    let p = CoreKind::Performance.index();
+    assert_eq!(info.l1d[p].write_policy, Some(WritePolicy::WriteBack));
+    assert_eq!(info.l2[p].write_policy, None);
+}
+
+#[test]
+fn synthetic_topology_only_skips_caches_and_clocks() {
+    let tree = smt_pair_tree("topology-only");
+    for os_id in 0..4 {
+        tree.sys(
+            &format!("devices/system/cpu/cpu{os_id}/cpufreq/cpuinfo_max_freq"),
+            "4000000",
+        );
+    }
+
+    let full = tree.detect();
+    let fast = tree.detect_with(&DetectOptions::TOPOLOGY_ONLY);
+
+    assert_eq!(fast.core_count, full.core_count);
+    assert_eq!(fast.socket_count, full.socket_count);
+    assert_eq!(fast.kind_core_counts, full.kind_core_counts);
+    assert!(!full.l2_domains.is_empty());
+    assert_eq!(full.max_frequency_mhz, Some(4000));
+
+    assert!(fast.l3_domains.is_empty() && fast.l2_domains.is_empty());
+    assert!(fast.caches().is_empty());
+    assert!(fast.lps.iter().all(|lp| lp.l2_domain == crate::Lp::NO_L2));
+    assert_eq!(fast.max_frequency_mhz, None);
+    // Skipped on request is not a detection gap.
+    assert!(
+        fast.detection_warnings.is_empty(),
+        "{:?}",
+        fast.detection_warnings
+    );
+}
+
+#[test]
+fn powercap_microwatts_round_to_watts() {
+    assert_eq!(parse_power_uw("125000000"), Some(125));
+    assert_eq!(parse_power_uw("15500000"), Some(16));
+    assert_eq!(parse_power_uw("28499999"), Some(28));
+    assert_eq!(parse_power_uw("0"), None);
+    assert_eq!(parse_power_uw("n/a"), None);
+}
+
+#[test]
+fn synthetic_rapl_long_term_limit_is_tdp() {
+    let tree = SyntheticTree::new("rapl");
+    tree.sys("devices/system/cpu/online", "0")
+        .cpu(0, 0, 0)
+        .sys("class/powercap/intel-rapl:0/constraint_0_name", "long_term")
+        .sys(
+            "class/powercap/intel-rapl:0/constraint_0_power_limit_uw",
+            "65000000",
+        );
+    assert_eq!(tree.detect().tdp_watts, Some(65));
+
+    // A zone whose first constraint is not PL1 is not trusted.
+    tree.sys(
+        "class/powercap/intel-rapl:0/constraint_0_name",
+        "short_term",
+    );
+    assert_eq!(tree.detect().tdp_watts, None);
+}
+
+#[test]
+fn synthetic_progress_reports_phases_in_order() {
+    let tree = SyntheticTree::new("progress");
+    tree.sys("devices/system/cpu/online", "0-3");
+    for os_id in 0..4 {
+        tree.cpu(os_id, 0, os_id as u32);
+    }
+
+    let mut events = Vec::new();
+    let info = detect_at_with_progress(
+        &tree.root.join("sys"),
+        &tree.root.join("proc"),
+        &DetectOptions::FULL,
+        &mut |phase, fraction| events.push((phase, fraction)),
+    )
+    .unwrap();
+    assert_eq!(info.num_logical_cores(), 4);
+
+    // Each phase completes exactly once, in the backend's order.
+    let completed: Vec<_> = events
+        .iter()
+        .filter(|&&(_, fraction)| fraction == 1.0)
+        .map(|&(phase, _)| phase)
+        .collect();
+    assert_eq!(
+        completed,
+        [
+            DetectionPhase::Topology,
+            DetectionPhase::Caches,
+            DetectionPhase::Numa,
+            DetectionPhase::Identity,
+        ]
+    );
+    assert_eq!(events.last(), Some(&(DetectionPhase::Identity, 1.0)));
+
+    // The cache walk advances strictly: 2 x 4 LPs + 4 cores, then done.
+    let caches: Vec<f32> = events
+        .iter()
+        .filter(|&&(phase, _)| phase == DetectionPhase::Caches)
+        .map(|&(_, fraction)| fraction)
+        .collect();
+    assert_eq!(caches.len(), 13);
+    assert_eq!(caches[0], 0.0);
+    assert!(caches.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn synthetic_scaling_limits_are_per_lp() {
+    // LP 1 capped by a power-save profile; LP 2 without cpufreq (a VM-style
+    // gap) reports nothing.
+    let tree = SyntheticTree::new("scaling-limits");
+    tree.sys("devices/system/cpu/online", "0-2")
+        .cpu(0, 0, 0)
+        .cpu(1, 0, 1)
+        .cpu(2, 0, 2);
+    for os_id in 0..2 {
+        let policy = format!("devices/system/cpu/cpu{os_id}/cpufreq");
+        tree.sys(&format!("{policy}/cpuinfo_max_freq"), "4700000\n")
+            .sys(&format!("{policy}/scaling_min_freq"), "800000\n");
+    }
+    tree.sys(
+        "devices/system/cpu/cpu0/cpufreq/scaling_max_freq",
+        "4700000\n",
+    )
+    .sys(
+        "devices/system/cpu/cpu1/cpufreq/scaling_max_freq",
+        "2000000\n",
+    );
+
+    let info = tree.detect();
+    let limits: Vec<_> = info
+        .lps
+        .iter()
+        .map(|lp| (lp.freq_min_mhz, lp.freq_max_mhz))
+        .collect();
+    assert_eq!(
+        limits,
+        [
+            (Some(800), Some(4700)),
+            (Some(800), Some(2000)),
+            (None, None)
+        ]
+    );
+    assert_eq!(info.max_frequency_mhz, Some(4700));
+
+    let skipped = tree.detect_with(&DetectOptions::TOPOLOGY_ONLY);
+    assert!(skipped.lps.iter().all(|lp| lp.freq_max_mhz.is_none()));
+}
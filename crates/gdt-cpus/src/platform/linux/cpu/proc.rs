@@ -89,20 +89,14 @@ pub(crate) fn parse_proc_cpuinfo(
 
         if parts.len() == 2 {
             match parts[0] {
-                "vendor_id" => {
-                    if parsed_vendor_id_proc.is_none() {
-                        parsed_vendor_id_proc = Some(parts[1].to_string());
-                    }
+                "vendor_id" if parsed_vendor_id_proc.is_none() => {
+                    parsed_vendor_id_proc = Some(parts[1].to_string());
                 }
-                "CPU implementer" => {
-                    if parsed_cpu_implementer_proc.is_none() {
-                        parsed_cpu_implementer_proc = Some(parts[1].to_string());
-                    }
+                "CPU implementer" if parsed_cpu_implementer_proc.is_none() => {
+                    parsed_cpu_implementer_proc = Some(parts[1].to_string());
                 }
-                "model name" | "Processor" => {
-                    if parsed_model_name_proc.is_none() {
-                        parsed_model_name_proc = Some(parts[1].to_string());
-                    }
+                "model name" | "Processor" if parsed_model_name_proc.is_none() => {
+                    parsed_model_name_proc = Some(parts[1].to_string());
                 }
                 "flags" | "Features" if parsed_features_line_proc.is_none() => {
                     parsed_features_line_proc = Some(parts[1].to_string());
@@ -240,6 +234,20 @@ pub(crate) fn parse_cpu_parts(content: &str) -> Vec<(u16, u16)> {
     out
 }
 
+/// The `microcode` field of the first processor block (`"0xf4"`), or `None`
+/// when the kernel prints none (ARM, most VMs). Uniform across LPs after
+/// boot-time loading, so the first block is enough.
+pub(crate) fn parse_microcode(content: &str) -> Option<String> {
+    content
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .find_map(|line| {
+            let (key, val) = line.split_once(':')?;
+            (key.trim() == "microcode").then(|| val.trim().to_string())
+        })
+        .filter(|val| !val.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +276,24 @@ CPU part\t: 0xd80
         );
     }
 
+    #[test]
+    fn microcode_read_from_first_block() {
+        let cpuinfo = "\
+processor\t: 0
+vendor_id\t: GenuineIntel
+model name\t: Intel(R) Core(TM) i7-6700 CPU @ 3.40GHz
+microcode\t: 0xf4
+cpu MHz\t\t: 3400.000
+
+processor\t: 1
+microcode\t: 0xf0
+";
+        assert_eq!(parse_microcode(cpuinfo).as_deref(), Some("0xf4"));
+
+        let arm = "processor\t: 0\nCPU part\t: 0xd81\n";
+        assert_eq!(parse_microcode(arm), None);
+    }
+
     #[test]
     fn cpu_parts_empty_when_no_part_field() {
         // x86 /proc/cpuinfo carries no "CPU part" line at all.
@@ -0,0 +1,85 @@
+//! AC / battery state from `/sys/class/power_supply`.
+//!
+//! Each supply is a directory with a `type` (`Mains`, `USB`, `Battery`, ...)
+//! and, depending on type, `online` (adapters) or `status` (batteries).
+//! Batteries with `scope` = `Device` belong to peripherals (mice, headsets)
+//! and say nothing about what powers the machine.
+
+use std::path::Path;
+
+/// [`on_battery_at`] on the live `/sys`.
+pub(crate) fn on_battery() -> Option<bool> {
+    on_battery_at(Path::new("/sys"))
+}
+
+/// `Some(true)` when a system battery is discharging and no adapter is
+/// online, `Some(false)` on external power or without a system battery,
+/// `None` when `sysfs/class/power_supply` cannot be read.
+pub(crate) fn on_battery_at(sysfs: &Path) -> Option<bool> {
+    let entries = std::fs::read_dir(sysfs.join("class/power_supply")).ok()?;
+
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let supply = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(supply.join(name))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+
+        match read("type").as_deref() {
+            Some("Mains" | "USB" | "USB_C" | "USB_PD")
+                if read("online").as_deref() == Some("1") =>
+            {
+                return Some(false);
+            }
+            Some("Battery") if read("scope").as_deref() != Some("Device") => {
+                discharging |= read("status").as_deref() == Some("Discharging");
+            }
+            _ => {}
+        }
+    }
+    Some(discharging)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let file = root.join("class/power_supply").join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, format!("{contents}\n")).unwrap();
+    }
+
+    #[test]
+    fn power_supply_status_parses() {
+        let root = std::env::temp_dir().join(format!("gdt-cpus-power-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // No power_supply class at all (some containers): unknown.
+        assert_eq!(on_battery_at(&root), None);
+
+        // Laptop, unplugged; a discharging Bluetooth mouse is not the system.
+        write(&root, "AC/type", "Mains");
+        write(&root, "AC/online", "0");
+        write(&root, "hid-mouse/type", "Battery");
+        write(&root, "hid-mouse/scope", "Device");
+        write(&root, "hid-mouse/status", "Discharging");
+        assert_eq!(on_battery_at(&root), Some(false));
+        write(&root, "BAT0/type", "Battery");
+        write(&root, "BAT0/status", "Discharging");
+        assert_eq!(on_battery_at(&root), Some(true));
+
+        // Plugged in.
+        write(&root, "AC/online", "1");
+        assert_eq!(on_battery_at(&root), Some(false));
+
+        // Full battery, adapter reported offline by a flaky firmware.
+        write(&root, "AC/online", "0");
+        write(&root, "BAT0/status", "Full");
+        assert_eq!(on_battery_at(&root), Some(false));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -18,6 +18,9 @@
 //! being late is fine, spinning forever is not. Lowering the hard limit is
 //! IRREVERSIBLE without `CAP_SYS_RESOURCE` and process-wide; that is the
 //! consent the caller signs by invoking promotion.
+//!
+//! `SCHED_DEADLINE` ([`crate::set_thread_deadline`]) is direct-only: no broker
+//! grants it, so a denial keeps the timeshare level like the unbrokered chain.
 
 use std::time::Duration;
 
@@ -29,6 +32,36 @@ use crate::{
 use super::affinity::{current_nice, set_thread_realtime_rr};
 use super::scheduling_policy::{RT_PRIORITY, level_for_nice, nice_for};
 
+/// Capability number of `CAP_SYS_NICE` (`linux/capability.h`).
+const CAP_SYS_NICE: u32 = 23;
+
+/// Whether step 1 of [`promote`] - direct `SCHED_RR` at [`RT_PRIORITY`] -
+/// is permitted: the calling thread holds `CAP_SYS_NICE` (root does) or its
+/// soft `RLIMIT_RTPRIO` reaches [`RT_PRIORITY`]. Brokers are not asked.
+pub(crate) fn can_promote_directly() -> bool {
+    let cap_sys_nice = std::fs::read_to_string("/proc/thread-self/status")
+        .is_ok_and(|status| has_cap_sys_nice(&status));
+
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit with a valid resource constant and a valid out-pointer.
+    let rtprio_ok = unsafe { libc::getrlimit(libc::RLIMIT_RTPRIO, &mut limit) } == 0
+        && limit.rlim_cur >= RT_PRIORITY as libc::rlim_t;
+
+    cap_sys_nice || rtprio_ok
+}
+
+/// `CAP_SYS_NICE` in the `CapEff:` mask of a `/proc/<tid>/status` dump.
+fn has_cap_sys_nice(status: &str) -> bool {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|hex| u64::from_str_radix(hex.trim(), 16).ok())
+        .is_some_and(|caps| caps & (1 << CAP_SYS_NICE) != 0)
+}
+
 /// Real-time promotion is conceptually "above" the named ladder; report it as
 /// the strongest named level so [`AppliedPriority`] stays uniform.
 fn rt_applied(mechanism: Mechanism) -> AppliedPriority {
@@ -64,7 +97,7 @@ fn kept_timeshare(reason: FallbackReason, broker_error: Option<BrokerError>) ->
 pub(crate) fn promote(budget: Duration) -> Result<AppliedPriority> {
     if budget.is_zero() {
         return Err(Error::InvalidParameter(
-            "real-time budget must be greater than zero".to_string(),
+            "real-time budget must be greater than zero".into(),
         ));
     }
 
@@ -179,10 +212,13 @@ fn set_rttime_rlimit(budget: Duration, rttime_usec_max: i64) -> Result<()> {
 
     // SAFETY: getrlimit with a valid resource constant and a valid out-pointer.
     if unsafe { libc::getrlimit(libc::RLIMIT_RTTIME, &mut current) } != 0 {
-        return Err(Error::SystemCall(format!(
-            "getrlimit(RLIMIT_RTTIME) failed: {}",
-            std::io::Error::last_os_error()
-        )));
+        return Err(Error::SystemCall(
+            format!(
+                "getrlimit(RLIMIT_RTTIME) failed: {}",
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        ));
     }
 
     let hard = current.rlim_max.min(daemon_max);
@@ -194,17 +230,130 @@ fn set_rttime_rlimit(budget: Duration, rttime_usec_max: i64) -> Result<()> {
 
     // SAFETY: setrlimit with a valid resource constant and a valid limit struct.
     if unsafe { libc::setrlimit(libc::RLIMIT_RTTIME, &wanted) } != 0 {
-        return Err(Error::SystemCall(format!(
-            "setrlimit(RLIMIT_RTTIME, soft={}, hard={}) failed: {}",
-            soft,
-            hard,
-            std::io::Error::last_os_error()
-        )));
+        return Err(Error::SystemCall(
+            format!(
+                "setrlimit(RLIMIT_RTTIME, soft={}, hard={}) failed: {}",
+                soft,
+                hard,
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        ));
     }
 
     Ok(())
 }
 
+/// `SCHED_DEADLINE` from `include/uapi/linux/sched.h` (not in libc's
+/// portable set).
+pub(crate) const SCHED_DEADLINE: u32 = 6;
+
+/// The kernel's `struct sched_attr` (`SCHED_ATTR_SIZE_VER0` layout, 48 bytes -
+/// the fields every deadline-capable kernel accepts).
+#[repr(C)]
+#[derive(Default)]
+struct SchedAttr {
+    size: u32,
+    sched_policy: u32,
+    sched_flags: u64,
+    sched_nice: i32,
+    sched_priority: u32,
+    sched_runtime: u64,
+    sched_deadline: u64,
+    sched_period: u64,
+}
+
+pub(crate) fn set_deadline(
+    runtime: Duration,
+    deadline: Duration,
+    period: Duration,
+) -> Result<AppliedPriority> {
+    let (runtime_ns, deadline_ns, period_ns) = (
+        runtime.as_nanos() as u64,
+        deadline.as_nanos() as u64,
+        period.as_nanos() as u64,
+    );
+
+    // The kernel's own checks (sched_dl_param_ok), surfaced with a reason
+    // instead of a bare EINVAL.
+    if runtime_ns < 1024 || runtime_ns > deadline_ns || deadline_ns > period_ns {
+        return Err(Error::InvalidParameter(
+            format!(
+                "SCHED_DEADLINE needs 1024 ns <= runtime <= deadline <= period \
+             (got {runtime:?} / {deadline:?} / {period:?})"
+            )
+            .into(),
+        ));
+    }
+
+    let attr = SchedAttr {
+        size: std::mem::size_of::<SchedAttr>() as u32,
+        sched_policy: SCHED_DEADLINE,
+        sched_flags: libc::SCHED_FLAG_RESET_ON_FORK as u64,
+        sched_runtime: runtime_ns,
+        sched_deadline: deadline_ns,
+        sched_period: period_ns,
+        ..SchedAttr::default()
+    };
+
+    // SAFETY: sched_setattr for the calling thread (pid 0) with a fully
+    // initialized VER0 attr whose `size` matches its layout; flags 0.
+    let res = unsafe { libc::syscall(libc::SYS_sched_setattr, 0, &attr, 0) };
+
+    if res == 0 {
+        return Ok(rt_applied(Mechanism {
+            policy: MechanismPolicy::SchedDeadline,
+            value: 0,
+        }));
+    }
+
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        // No broker grants SCHED_DEADLINE: keep the level the thread has.
+        Some(libc::EPERM) => Ok(kept_timeshare(FallbackReason::NoBroker, None)),
+        Some(libc::EBUSY) => Err(Error::SystemCall(
+            format!("SCHED_DEADLINE admission control refused {runtime:?} every {period:?}: {err}")
+                .into(),
+        )),
+        Some(libc::EINVAL) => Err(Error::InvalidParameter(
+            format!("sched_setattr(SCHED_DEADLINE) rejected the parameters: {err}").into(),
+        )),
+        _ => Err(Error::SystemCall(
+            format!("sched_setattr(SCHED_DEADLINE) failed: {err}").into(),
+        )),
+    }
+}
+
+/// The calling thread's deadline reservation in nanoseconds
+/// (`runtime, deadline, period`), or `None` when it is not on `SCHED_DEADLINE`.
+#[cfg(test)]
+fn current_deadline() -> Result<Option<(u64, u64, u64)>> {
+    let mut attr = SchedAttr::default();
+
+    // SAFETY: sched_getattr for the calling thread into a VER0-sized buffer;
+    // the kernel writes at most `size` bytes.
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_sched_getattr,
+            0,
+            &mut attr,
+            std::mem::size_of::<SchedAttr>() as libc::c_uint,
+            0,
+        )
+    };
+    if res != 0 {
+        return Err(Error::SystemCall(
+            format!("sched_getattr failed: {}", std::io::Error::last_os_error()).into(),
+        ));
+    }
+
+    Ok((attr.sched_policy == SCHED_DEADLINE).then_some((
+        attr.sched_runtime,
+        attr.sched_deadline,
+        attr.sched_period,
+    )))
+}
+
 /// Returns the current thread to `SCHED_OTHER` at nice-neutral priority.
 /// This is the self-demotion helper - call it from your own watchdog (or a
 /// `SIGXCPU` handler's flag check) instead of letting the hard limit kill the
@@ -240,11 +389,95 @@ pub(crate) fn demote() -> Result<()> {
 
     if res != 0 {
         let err = std::io::Error::last_os_error();
-        return Err(Error::SystemCall(format!(
-            "sched_setscheduler(SCHED_OTHER) failed: {}",
-            err
-        )));
+        return Err(Error::SystemCall(
+            format!("sched_setscheduler(SCHED_OTHER) failed: {}", err).into(),
+        ));
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadline_rejects_inverted_parameters() {
+        let ms = Duration::from_millis;
+
+        assert!(matches!(
+            set_deadline(ms(5), ms(2), ms(10)),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            set_deadline(ms(1), ms(10), ms(5)),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            set_deadline(Duration::from_nanos(100), ms(1), ms(1)),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn cap_eff_mask_parses() {
+        let status = |cap_eff: &str| format!("Name:\tgame\nCapInh:\t0\nCapEff:\t{cap_eff}\n");
+
+        assert!(has_cap_sys_nice(&status("000001ffffffffff"))); // root
+        assert!(has_cap_sys_nice(&status("0000000000800000"))); // setcap cap_sys_nice
+        assert!(!has_cap_sys_nice(&status("0000000000000000")));
+        assert!(!has_cap_sys_nice("Name:\tgame\n"));
+    }
+
+    // The prediction must match what the kernel then does with a direct
+    // SCHED_RR request - granted or refused, on any runner.
+    #[test]
+    fn direct_promotion_prediction_matches_the_kernel() {
+        std::thread::spawn(|| {
+            let predicted = can_promote_directly();
+            let granted = set_thread_realtime_rr(RT_PRIORITY).is_ok();
+            if granted {
+                demote().unwrap();
+            }
+
+            assert_eq!(predicted, granted);
+        })
+        .join()
+        .unwrap();
+    }
+
+    // Privilege-gated: without CAP_SYS_NICE the call must degrade, never error;
+    // with it the kernel must hold exactly the reservation we asked for.
+    #[test]
+    fn deadline_round_trips_or_degrades() {
+        std::thread::spawn(|| {
+            let (runtime, deadline, period) = (
+                Duration::from_millis(1),
+                Duration::from_millis(8),
+                Duration::from_millis(10),
+            );
+
+            let applied = match set_deadline(runtime, deadline, period) {
+                Ok(applied) => applied,
+                // Admission control on a box whose RT bandwidth is spoken for.
+                Err(Error::SystemCall(msg)) if msg.contains("admission") => return,
+                Err(e) => panic!("set_deadline failed: {e}"),
+            };
+
+            if applied.degraded() {
+                assert_eq!(current_deadline().unwrap(), None);
+                return;
+            }
+
+            assert_eq!(applied.mechanism().policy, MechanismPolicy::SchedDeadline);
+            assert_eq!(
+                current_deadline().unwrap(),
+                Some((1_000_000, 8_000_000, 10_000_000))
+            );
+            demote().unwrap();
+            assert_eq!(current_deadline().unwrap(), None);
+        })
+        .join()
+        .unwrap();
+    }
+}
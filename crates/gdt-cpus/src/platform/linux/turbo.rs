@@ -0,0 +1,73 @@
+//! Turbo / boost state from the cpufreq sysfs switches.
+//!
+//! `intel_pstate` exposes `devices/system/cpu/intel_pstate/no_turbo` (`1` =
+//! turbo disabled); `acpi-cpufreq` and `amd-pstate` expose
+//! `devices/system/cpu/cpufreq/boost` (`1` = boost enabled). Both are
+//! machine-wide and writable by root, which is how benchmark setups pin the
+//! clock.
+
+use std::path::Path;
+
+/// [`turbo_enabled_at`] on the live `/sys`.
+pub(crate) fn turbo_enabled() -> Option<bool> {
+    turbo_enabled_at(Path::new("/sys"))
+}
+
+/// Whether turbo is enabled per `intel_pstate/no_turbo`, falling back to
+/// `cpufreq/boost`; `None` when neither switch exists or parses (VMs,
+/// drivers without boost control).
+pub(crate) fn turbo_enabled_at(sysfs: &Path) -> Option<bool> {
+    let cpu = sysfs.join("devices/system/cpu");
+    let read = |path: &str| parse_switch(&std::fs::read_to_string(cpu.join(path)).ok()?);
+
+    read("intel_pstate/no_turbo")
+        .map(|no_turbo| !no_turbo)
+        .or_else(|| read("cpufreq/boost"))
+}
+
+/// A sysfs boolean: `0` or `1`, newline-terminated.
+fn parse_switch(contents: &str) -> Option<bool> {
+    match contents.trim() {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let file = root.join("devices/system/cpu").join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, format!("{contents}\n")).unwrap();
+    }
+
+    #[test]
+    fn boost_switches_parse() {
+        let root = std::env::temp_dir().join(format!("gdt-cpus-turbo-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // Neither driver switch (a VM): unknown.
+        assert_eq!(turbo_enabled_at(&root), None);
+
+        // acpi-cpufreq / amd-pstate: `boost` reads the right way round.
+        write(&root, "cpufreq/boost", "1");
+        assert_eq!(turbo_enabled_at(&root), Some(true));
+        write(&root, "cpufreq/boost", "0");
+        assert_eq!(turbo_enabled_at(&root), Some(false));
+
+        // intel_pstate: `no_turbo` is inverted, and wins over `boost`.
+        write(&root, "intel_pstate/no_turbo", "0");
+        assert_eq!(turbo_enabled_at(&root), Some(true));
+        write(&root, "intel_pstate/no_turbo", "1");
+        assert_eq!(turbo_enabled_at(&root), Some(false));
+
+        // Garbage in the intel_pstate file falls through to `boost`.
+        write(&root, "intel_pstate/no_turbo", "maybe");
+        assert_eq!(turbo_enabled_at(&root), Some(false));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
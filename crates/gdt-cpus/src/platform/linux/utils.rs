@@ -45,17 +45,9 @@ pub(crate) fn parse_vendor_from_string(
     }
 
     if let Some(imp_str) = parsed_cpu_implementer_proc {
-        return match imp_str.as_str() {
-            "0x41" => Vendor::Arm,
-            "0x61" => Vendor::Apple,
-            "0x42" => Vendor::Broadcom,
-            // Cavium was acquired by Marvell; both implementer codes map there.
-            "0x43" => Vendor::Marvell,
-            "0x4e" => Vendor::Nvidia,
-            "0x51" => Vendor::Qualcomm,
-            "0x56" => Vendor::Marvell,
-            _ => Vendor::Other,
-        };
+        return parse_implementer(imp_str)
+            .map(Vendor::from_arm_implementer)
+            .unwrap_or(Vendor::Other);
     }
 
     if parsed_vendor_id_proc.is_some() {
@@ -65,6 +57,13 @@ pub(crate) fn parse_vendor_from_string(
     }
 }
 
+/// Decodes the `CPU implementer` value: `0x`-prefixed hex, either case.
+fn parse_implementer(s: &str) -> Option<u8> {
+    let s = s.trim();
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))?;
+    u8::from_str_radix(hex, 16).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +77,26 @@ mod tests {
         assert_eq!(v, Vendor::Apple);
     }
 
+    #[test]
+    fn implementer_bytes_map_to_vendors() {
+        for (implementer, vendor) in [
+            ("0x41", Vendor::Arm),
+            ("0x51", Vendor::Qualcomm),
+            ("0xc0", Vendor::Ampere),
+            ("0xC0", Vendor::Ampere),
+            ("0x4e", Vendor::Nvidia),
+            ("0x43", Vendor::Marvell),
+            ("0x00", Vendor::Other),
+            ("garbage", Vendor::Other),
+        ] {
+            assert_eq!(
+                parse_vendor_from_string(&None, &Some(implementer.to_string())),
+                vendor,
+                "{implementer}"
+            );
+        }
+    }
+
     #[test]
     fn vendor_id_takes_priority() {
         let v =
@@ -14,6 +14,8 @@
 //! - [`cpu`]: Contains the core logic for detecting CPU features, topology (sockets,
 //!   physical cores, logical processors), cache information, and distinguishing
 //!   between Performance and Efficiency cores using macOS-specific APIs like `sysctl`.
+//! - [`ioregistry`]: Counts P/E cores from the IORegistry device tree, the
+//!   cross-check for the `sysctl` per-level counts.
 //! - [`scheduling_policy`]: Defines how abstract thread priorities map to
 //!   macOS-specific scheduling policies (e.g., QoS classes).
 //! - [`utils`]: Provides utility functions used across the macOS platform-specific modules,
@@ -28,10 +30,12 @@ compile_error!(
 // Thread control + the live sysctl reader are genuinely macOS-only. The
 // detection pipeline (`cpu`) is pure logic behind the SysctlSource seam and
 // also compiles for tests on other platforms (fixture-driven CI coverage).
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "macos", feature = "affinity"))]
 pub(crate) mod affinity;
 pub(crate) mod cpu;
 #[cfg(target_os = "macos")]
+pub(crate) mod ioregistry;
+#[cfg(all(target_os = "macos", feature = "affinity"))]
 pub(crate) mod scheduling_policy;
 #[cfg(target_os = "macos")]
 pub(crate) mod utils;
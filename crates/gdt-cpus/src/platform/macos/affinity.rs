@@ -51,11 +51,79 @@ pub(crate) fn promote_thread_to_realtime() -> Result<AppliedPriority> {
             set_sched_rr(ThreadPriority::TimeCritical, priority)
         }
         SchedulingPolicy::QoS { .. } => Err(Error::Unsupported(
-            "TimeCritical does not map to SCHED_RR on this macOS target".to_string(),
+            "TimeCritical does not map to SCHED_RR on this macOS target".into(),
         )),
     }
 }
 
+/// Puts the calling thread on Mach `THREAD_TIME_CONSTRAINT_POLICY`, the policy
+/// Core Audio runs its own I/O threads under: every `period` the thread needs
+/// `computation` of CPU, done within the same `period`, preemptible.
+///
+/// No privileges needed. The kernel demotes a thread that repeatedly overruns
+/// `computation` back to timeshare, so the promise has to be honest.
+#[allow(deprecated)] // mach_timebase_info: libc points at the mach2 crate
+pub(crate) fn set_time_constraint(
+    period: std::time::Duration,
+    computation: std::time::Duration,
+) -> Result<AppliedPriority> {
+    let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+
+    // SAFETY: valid out-pointer to a mach_timebase_info.
+    let kr = unsafe { libc::mach_timebase_info(&mut timebase) };
+
+    if kr != libc::KERN_SUCCESS || timebase.numer == 0 {
+        return Err(Error::SystemCall(
+            format!("mach_timebase_info failed: kern_return {}", kr).into(),
+        ));
+    }
+
+    // Policy fields are in Mach absolute-time ticks (24 MHz on Apple Silicon).
+    let ticks = |d: std::time::Duration| -> u32 {
+        let t = d.as_nanos() * u128::from(timebase.denom) / u128::from(timebase.numer);
+
+        t.min(u128::from(u32::MAX)) as u32
+    };
+
+    let mut policy = libc::thread_time_constraint_policy {
+        period: ticks(period),
+        computation: ticks(computation),
+        constraint: ticks(period),
+        preemptible: 1,
+    };
+
+    // SAFETY: the calling thread's own port; `policy` is a live
+    // THREAD_TIME_CONSTRAINT_POLICY-shaped buffer of the stated count.
+    let kr = unsafe {
+        libc::thread_policy_set(
+            libc::pthread_mach_thread_np(libc::pthread_self()),
+            libc::THREAD_TIME_CONSTRAINT_POLICY as libc::thread_policy_flavor_t,
+            &mut policy as *mut libc::thread_time_constraint_policy as libc::thread_policy_t,
+            libc::THREAD_TIME_CONSTRAINT_POLICY_COUNT,
+        )
+    };
+
+    if kr != libc::KERN_SUCCESS {
+        return Err(Error::SystemCall(
+            format!(
+                "thread_policy_set(THREAD_TIME_CONSTRAINT_POLICY) failed: kern_return {}",
+                kr
+            )
+            .into(),
+        ));
+    }
+
+    Ok(AppliedPriority::new(
+        ThreadPriority::TimeCritical,
+        ThreadPriority::TimeCritical,
+        Grant::Realtime,
+        Mechanism {
+            policy: MechanismPolicy::TimeConstraint,
+            value: 0,
+        },
+    ))
+}
+
 fn set_sched_rr(requested: ThreadPriority, priority: libc::c_int) -> Result<AppliedPriority> {
     let current_thread = unsafe { libc::pthread_self() };
 
@@ -76,16 +144,18 @@ fn set_sched_rr(requested: ThreadPriority, priority: libc::c_int) -> Result<Appl
 
     if err != 0 {
         if err == libc::EPERM {
-            return Err(Error::PermissionDenied(format!(
-                "SCHED_RR priority {} denied",
-                priority
-            )));
+            return Err(Error::PermissionDenied(
+                format!("SCHED_RR priority {} denied", priority).into(),
+            ));
         }
 
-        return Err(Error::Affinity(format!(
-            "pthread_setschedparam failed with error code: {}",
-            std::io::Error::from_raw_os_error(err)
-        )));
+        return Err(Error::Affinity(
+            format!(
+                "pthread_setschedparam failed with error code: {}",
+                std::io::Error::from_raw_os_error(err)
+            )
+            .into(),
+        ));
     }
 
     Ok(AppliedPriority::new(
@@ -199,16 +269,22 @@ pub fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority>
                         ));
                     }
 
-                    return Err(Error::PermissionDenied(format!(
-                        "QoS class {} denied and SCHED_OTHER fallback failed (error {})",
-                        qos_class_str, fb
-                    )));
+                    return Err(Error::PermissionDenied(
+                        format!(
+                            "QoS class {} denied and SCHED_OTHER fallback failed (error {})",
+                            qos_class_str, fb
+                        )
+                        .into(),
+                    ));
                 }
 
-                Err(Error::Affinity(format!(
-                    "pthread_set_qos_class_self_np failed with error code: {}",
-                    std::io::Error::from_raw_os_error(err)
-                )))
+                Err(Error::Affinity(
+                    format!(
+                        "pthread_set_qos_class_self_np failed with error code: {}",
+                        std::io::Error::from_raw_os_error(err)
+                    )
+                    .into(),
+                ))
             } else {
                 Ok(AppliedPriority::new(
                     priority,
@@ -280,16 +356,22 @@ pub fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority>
                         ));
                     }
 
-                    return Err(Error::PermissionDenied(format!(
-                        "SCHED_RR priority {} denied and QoS fallback failed",
-                        priority
-                    )));
+                    return Err(Error::PermissionDenied(
+                        format!(
+                            "SCHED_RR priority {} denied and QoS fallback failed",
+                            priority
+                        )
+                        .into(),
+                    ));
                 }
 
-                Err(Error::Affinity(format!(
-                    "pthread_setschedparam failed with error code: {}",
-                    std::io::Error::from_raw_os_error(err)
-                )))
+                Err(Error::Affinity(
+                    format!(
+                        "pthread_setschedparam failed with error code: {}",
+                        std::io::Error::from_raw_os_error(err)
+                    )
+                    .into(),
+                ))
             } else {
                 Ok(AppliedPriority::new(
                     requested,
@@ -304,3 +386,142 @@ pub fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority>
         }
     }
 }
+
+/// CPU time the calling thread has consumed (user + system), via
+/// `clock_gettime(CLOCK_THREAD_CPUTIME_ID)`.
+pub(crate) fn current_thread_cpu_time() -> Result<std::time::Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `ts` is a live timespec; the clock id is a constant.
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } != 0 {
+        return Err(Error::SystemCall(
+            format!(
+                "clock_gettime(CLOCK_THREAD_CPUTIME_ID) failed: {}",
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        ));
+    }
+
+    Ok(std::time::Duration::new(
+        ts.tv_sec as u64,
+        ts.tv_nsec as u32,
+    ))
+}
+
+/// The calling thread's system-wide 64-bit id (`pthread_threadid_np`).
+pub(crate) fn current_thread_id() -> Result<u64> {
+    let mut id: u64 = 0;
+
+    // SAFETY: a null thread means the calling thread; `id` is a live out-pointer.
+    let rc = unsafe { libc::pthread_threadid_np(0 as libc::pthread_t, &mut id) };
+    if rc != 0 {
+        return Err(Error::SystemCall(
+            format!(
+                "pthread_threadid_np failed: {}",
+                std::io::Error::from_raw_os_error(rc)
+            )
+            .into(),
+        ));
+    }
+
+    Ok(id)
+}
+
+/// The calling thread's priority as [`set_thread_priority`] changes it - its
+/// QoS class (if it has one) and POSIX policy + priority - for save/restore.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SavedPriority {
+    qos: Option<(libc::qos_class_t, libc::c_int)>,
+    policy: libc::c_int,
+    param: libc::sched_param,
+}
+
+/// Snapshots the calling thread's priority.
+pub(crate) fn save_thread_priority() -> Result<SavedPriority> {
+    let thread = unsafe { libc::pthread_self() };
+
+    let mut class = libc::qos_class_t::QOS_CLASS_UNSPECIFIED;
+    let mut relative_priority: libc::c_int = 0;
+    // SAFETY: both out-pointers are live locals.
+    let err = unsafe { libc::pthread_get_qos_class_np(thread, &mut class, &mut relative_priority) };
+    let qos = (err == 0 && !matches!(class, libc::qos_class_t::QOS_CLASS_UNSPECIFIED))
+        .then_some((class, relative_priority));
+
+    let mut policy: libc::c_int = 0;
+    let mut param: libc::sched_param = unsafe { std::mem::zeroed() };
+    // SAFETY: both out-pointers are live locals.
+    let err = unsafe { libc::pthread_getschedparam(thread, &mut policy, &mut param) };
+    if err != 0 {
+        return Err(Error::SystemCall(
+            format!(
+                "pthread_getschedparam failed: {}",
+                std::io::Error::from_raw_os_error(err)
+            )
+            .into(),
+        ));
+    }
+
+    Ok(SavedPriority { qos, policy, param })
+}
+
+/// The calling thread's QoS class (if it has one) and POSIX policy +
+/// priority (`"QOS_CLASS_USER_INTERACTIVE +0, SCHED_OTHER priority 31"`),
+/// for reports.
+pub(crate) fn describe_thread_priority() -> Result<String> {
+    let saved = save_thread_priority()?;
+    let policy = match saved.policy {
+        libc::SCHED_OTHER => "SCHED_OTHER",
+        libc::SCHED_RR => "SCHED_RR",
+        libc::SCHED_FIFO => "SCHED_FIFO",
+        _ => "unknown policy",
+    };
+    let posix = format!("{} priority {}", policy, saved.param.sched_priority);
+
+    Ok(match saved.qos {
+        Some((class, relative_priority)) => {
+            format!("{:?} {:+}, {}", class, relative_priority, posix)
+        }
+        None => posix,
+    })
+}
+
+/// Puts the calling thread back at a [`save_thread_priority`] snapshot: the
+/// POSIX policy first, then the QoS class, which overrides it.
+///
+/// A thread that went `SCHED_RR` in between has left the QoS system for good,
+/// so restoring its class fails; the policy restore still applies.
+pub(crate) fn restore_thread_priority(saved: &SavedPriority) -> Result<()> {
+    let thread = unsafe { libc::pthread_self() };
+
+    // SAFETY: `saved.param` is a live sched_param read from this thread.
+    let err = unsafe { libc::pthread_setschedparam(thread, saved.policy, &saved.param) };
+    if err != 0 {
+        return Err(Error::SystemCall(
+            format!(
+                "pthread_setschedparam failed: {}",
+                std::io::Error::from_raw_os_error(err)
+            )
+            .into(),
+        ));
+    }
+
+    if let Some((class, relative_priority)) = saved.qos {
+        // SAFETY: the class and relative priority were read from this thread.
+        let err = unsafe { libc::pthread_set_qos_class_self_np(class, relative_priority) };
+        if err != 0 {
+            return Err(Error::SystemCall(
+                format!(
+                    "pthread_set_qos_class_self_np failed: {}",
+                    std::io::Error::from_raw_os_error(err)
+                )
+                .into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
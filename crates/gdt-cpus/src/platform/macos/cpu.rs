@@ -10,18 +10,38 @@
 //! general). The ids give masks/counts a consistent shape; they are NOT OS
 //! thread-placement ids - there is nothing to place against.
 //!
-//! Caches map 1:1 onto the per-kind model (`hw.perflevelN.*`). L3 domains are
+//! Caches map 1:1 onto the per-kind model (`hw.perflevelN.*`). L2 is shared
+//! per cluster, not per core: `hw.perflevelN.cpusperl2` consecutive cores of a
+//! kind form one [`L2Domain`] with one `l2cachesize` instance, and the
+//! per-kind `shared_by` counts the whole cluster. L3 domains are
 //! synthesized from `hw.perflevelN.{l3cachesize,cpusperl3}` when present -
 //! every current Apple Silicon chip reports neither (the SLC is not exposed)
 //! ⇒ zero domains in practice, but the interface defines the keys
 //! (Optimization Guide, Appendix B.2), so we query instead of hardcoding.
+//! Line sizes are per level: `hw.cachelinesize` is the coherency granule,
+//! which on Apple Silicon is the 128-byte L2 line, while the L1s use smaller
+//! lines that only `CTR_EL0` reports; without it every level falls back to
+//! `hw.cachelinesize`.
 //! Every key is defaulted on absence - no panic paths.
+//!
+//! The per-level core counts are cross-checked against the IORegistry, which
+//! lists every core with its cluster type. On a mismatch the IORegistry
+//! counts win (they enumerate cores instead of summarizing them) and the
+//! disagreement lands in `detection_warnings`; per-kind caches still come
+//! from sysctl.
 
+use crate::platform::timing::PhaseTimer;
 use crate::{
-    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, Error, L2Domain, L3Domain, Lp, Result,
-    Vendor,
+    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, DetectionPhase, Error, L2Domain,
+    L3Domain, Lp, Result, Vendor,
 };
 
+/// [`Lp::capacity`] assigned to Apple Efficiency cores. Apple publishes no
+/// per-core performance figure; an E core sustains roughly 3/8 of a P core's
+/// single-thread throughput across the M1-M4 generations (lower clock and
+/// narrower core), which is the estimate used here.
+const E_CORE_CAPACITY: u16 = Lp::MAX_CAPACITY * 3 / 8;
+
 /// The detection pipeline's read seam.
 ///
 /// The live implementation wraps `sysctlbyname`; the fixture implementation
@@ -34,6 +54,13 @@ pub(crate) trait SysctlSource {
     fn int(&self, key: &str) -> Option<u64>;
     /// String key.
     fn string(&self, key: &str) -> Option<String>;
+    /// Raw `CTR_EL0` (cache type register) - not a sysctl, but the only
+    /// source of the L1 line sizes.
+    fn cache_type_register(&self) -> Option<u64>;
+    /// `(performance, efficiency)` core counts from the IORegistry device
+    /// tree - not a sysctl either, but the per-core cross-check for the
+    /// `hw.perflevelN` counts.
+    fn ioregistry_core_counts(&self) -> Option<(usize, usize)>;
 }
 
 /// Live `sysctlbyname`-backed source.
@@ -49,6 +76,37 @@ impl SysctlSource for LiveSysctl {
     fn string(&self, key: &str) -> Option<String> {
         super::utils::sysctlbyname_string(key).ok()
     }
+
+    fn cache_type_register(&self) -> Option<u64> {
+        Some(crate::platform::common_aarch64::read_cache_type_register())
+    }
+
+    fn ioregistry_core_counts(&self) -> Option<(usize, usize)> {
+        super::ioregistry::cluster_core_counts()
+    }
+}
+
+/// Cache line size per level, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineSizes {
+    l1d: u16,
+    l1i: u16,
+    l2: u16,
+}
+
+impl LineSizes {
+    /// L1 lines from `CTR_EL0` (`DminLine`/`IminLine`, log2 of 4-byte
+    /// words), the L2 line from `hw.cachelinesize`; a missing register
+    /// leaves every level at the L2 line.
+    fn read(src: &impl SysctlSource) -> Self {
+        let l2 = src.int("hw.cachelinesize").unwrap_or(64) as u16;
+        let (l1d, l1i) = match src.cache_type_register() {
+            Some(ctr) => (4 << ((ctr >> 16) & 0xF), 4 << (ctr & 0xF)),
+            None => (l2, l2),
+        };
+
+        Self { l1d, l1i, l2 }
+    }
 }
 
 /// Detects CPU vendor, model name, and features using `sysctl`.
@@ -199,7 +257,7 @@ fn read_perflevel(
     src: &impl SysctlSource,
     level: u32,
     kind: CoreKind,
-    line: u16,
+    line: LineSizes,
 ) -> Option<KindGroup> {
     let cores = src
         .int(&format!("hw.perflevel{}.physicalcpu", level))
@@ -232,18 +290,27 @@ fn read_perflevel(
         lps,
         l1d: CacheInfo {
             size_bytes: cache("l1dcachesize"),
-            line_bytes: line,
+            line_bytes: line.l1d,
             shared_by: smt,
+            write_policy: None,
+            inclusive: None,
+            estimated_latency_cycles: None,
         },
         l1i: CacheInfo {
             size_bytes: cache("l1icachesize"),
-            line_bytes: line,
+            line_bytes: line.l1i,
             shared_by: smt,
+            write_policy: None,
+            inclusive: None,
+            estimated_latency_cycles: None,
         },
         l2: CacheInfo {
             size_bytes: cache("l2cachesize"),
-            line_bytes: line,
+            line_bytes: line.l2,
             shared_by: cpus_per_l2 * smt,
+            write_policy: None,
+            inclusive: None,
+            estimated_latency_cycles: None,
         },
         l3_size,
         cpus_per_l3,
@@ -251,15 +318,88 @@ fn read_perflevel(
     })
 }
 
+/// Checks the perflevel core counts against the IORegistry's per-core
+/// `(performance, efficiency)` counts. On a mismatch the IORegistry counts
+/// replace the group sizes (SMT ratio kept) and the disagreement is noted in
+/// `warnings`; a kind sysctl missed entirely joins without cache data. A
+/// reading with no P-cores cannot be a real topology and is ignored.
+fn reconcile_with_ioregistry(
+    groups: &mut Vec<KindGroup>,
+    (performance, efficiency): (usize, usize),
+    warnings: &mut Vec<String>,
+) {
+    let count = |kind| {
+        groups
+            .iter()
+            .find(|g: &&KindGroup| g.kind == kind)
+            .map_or(0, |g| g.cores)
+    };
+    let sysctl = (count(CoreKind::Performance), count(CoreKind::Efficiency));
+
+    if performance == 0 || sysctl == (performance, efficiency) {
+        return;
+    }
+
+    warnings.push(format!(
+        "hw.perflevel core counts ({}P+{}E) disagree with IORegistry ({}P+{}E); using IORegistry",
+        sysctl.0, sysctl.1, performance, efficiency
+    ));
+
+    for (kind, cores) in [
+        (CoreKind::Performance, performance),
+        (CoreKind::Efficiency, efficiency),
+    ] {
+        match groups.iter_mut().find(|g| g.kind == kind) {
+            Some(group) => {
+                let smt = (group.lps / group.cores).max(1);
+                group.cores = cores;
+                group.lps = cores * smt;
+            }
+            None if cores > 0 => groups.push(KindGroup {
+                kind,
+                cores,
+                lps: cores,
+                l1d: CacheInfo::default(),
+                l1i: CacheInfo::default(),
+                l2: CacheInfo::default(),
+                l3_size: 0,
+                cpus_per_l3: cores,
+                cpus_per_l2: cores,
+            }),
+            None => {}
+        }
+    }
+    groups.retain(|g| g.cores > 0);
+}
+
 /// Detects CPU information on macOS (live sysctl).
 #[cfg(target_os = "macos")]
-pub fn detect_cpu_info() -> Result<CpuInfo> {
-    detect_at(&LiveSysctl)
+pub fn detect_cpu_info(progress: &mut dyn FnMut(DetectionPhase, f32)) -> Result<CpuInfo> {
+    let mut info = detect_at_with_progress(&LiveSysctl, progress)?;
+
+    (info.has_invariant_tsc, info.tsc_frequency_hz) = crate::platform::detect_timestamp_counter();
+    info.x86_signature = crate::platform::detect_x86_signature();
+    info.avx512_downclock_risk = crate::platform::detect_avx512_downclock_risk(info.vendor);
+    // The signature names Intel Macs' microarchitecture: refresh the cache
+    // latency estimates assembly made without it.
+    info.estimate_cache_latencies();
+
+    Ok(info)
 }
 
 /// The detection pipeline against any [`SysctlSource`] - pure logic, compiled
 /// (and fixture-tested) on every platform.
 pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
+    detect_at_with_progress(src, &mut |_, _| {})
+}
+
+/// [`detect_at`] reporting each phase to `progress`.
+pub(crate) fn detect_at_with_progress(
+    src: &impl SysctlSource,
+    progress: &mut dyn FnMut(DetectionPhase, f32),
+) -> Result<CpuInfo> {
+    let mut timer = PhaseTimer::start("macos", progress);
+
     // --- Identity ---
     let mut vendor = Vendor::Unknown;
     let mut model_name = "Unknown".to_string();
@@ -267,6 +407,8 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
 
     detect_cpu_via_sysctl(src, &mut vendor, &mut model_name, &mut features);
 
+    timer.phase(DetectionPhase::Identity);
+
     // --- Counts ---
     let socket_count = src.int("hw.packages").unwrap_or(1).max(1) as usize;
     let physical = src.int("hw.physicalcpu").unwrap_or(0) as usize;
@@ -274,11 +416,11 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
 
     if physical == 0 || logical == 0 {
         return Err(Error::Detection(
-            "hw.physicalcpu / hw.logicalcpu unavailable".to_string(),
+            "hw.physicalcpu / hw.logicalcpu unavailable".into(),
         ));
     }
 
-    let line = src.int("hw.cachelinesize").unwrap_or(64) as u16;
+    let line = LineSizes::read(src);
 
     // --- Per-kind groups: perflevel0 = P, perflevel1 = E; absence => homogeneous all-P ---
     let mut groups: Vec<KindGroup> = Vec::new();
@@ -303,18 +445,29 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
             lps: logical,
             l1d: CacheInfo {
                 size_bytes: direct("hw.l1dcachesize"),
-                line_bytes: line,
+                line_bytes: line.l1d,
                 shared_by: smt,
+                write_policy: None,
+                inclusive: None,
+                estimated_latency_cycles: None,
             },
             l1i: CacheInfo {
                 size_bytes: direct("hw.l1icachesize"),
-                line_bytes: line,
+                line_bytes: line.l1i,
                 shared_by: smt,
+                write_policy: None,
+                inclusive: None,
+                estimated_latency_cycles: None,
             },
+            // One cluster spanning the group (cpus_per_l2 below), so the
+            // single L2 is shared by every LP - not a private copy per core.
             l2: CacheInfo {
                 size_bytes: direct("hw.l2cachesize"),
-                line_bytes: line,
-                shared_by: smt,
+                line_bytes: line.l2,
+                shared_by: logical as u16,
+                write_policy: None,
+                inclusive: None,
+                estimated_latency_cycles: None,
             },
             l3_size: direct("hw.l3cachesize"),
             cpus_per_l3: physical,
@@ -322,6 +475,11 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
         }];
     }
 
+    let mut detection_warnings = Vec::new();
+    if let Some(counts) = src.ioregistry_core_counts() {
+        reconcile_with_ioregistry(&mut groups, counts, &mut detection_warnings);
+    }
+
     // --- Synthetic LP records (pinned layout, see module doc) ---
     // L3: no current Apple Silicon exposes one (the SLC is hidden ⇒ in
     // practice zero domains, every LP keeps Lp::NO_L3) - but the sysctl
@@ -345,6 +503,10 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
         // perf_hint: perflevel order, higher = faster (perflevel0 = best).
         // Coarse but honest - macOS exposes no finer per-core signal.
         let perf_hint = (groups.len() - group_idx) as u16;
+        let capacity = match group.kind {
+            CoreKind::Performance => Lp::MAX_CAPACITY,
+            _ => E_CORE_CAPACITY,
+        };
         let k = group.kind.index();
 
         l1d[k] = group.l1d;
@@ -421,6 +583,10 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
                     // NOTE(macos): sysctl exposes no per-core MIDR part on Apple
                     // Silicon; perflevel order already classifies P/E. Leave 0.
                     cpu_part: 0,
+                    native_core_id: None,
+                    capacity: Some(capacity),
+                    freq_min_mhz: None,
+                    freq_max_mhz: None,
                 });
 
                 next_lp += 1;
@@ -430,11 +596,16 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
         }
     }
 
+    // None on Apple Silicon; Intel Macs with Hyper-Threading have
+    // `hw.logicalcpu > hw.physicalcpu`, laid out as siblings above.
+    let smt_supported = lps.iter().any(|lp| lp.smt_index > 0);
+
     let mut info = CpuInfo {
         lps,
         core_count: next_core as u16,
         socket_count: socket_count as u8,
         numa_node_count: 1,
+        smt_supported,
         kind_core_counts,
         l3_domains,
         l2_domains,
@@ -444,8 +615,25 @@ pub(crate) fn detect_at(src: &impl SysctlSource) -> Result<CpuInfo> {
         vendor,
         model_name,
         features,
+        // NOTE(macos): Apple Silicon exposes no clock via sysctl
+        // (`hw.cpufrequency*` exist on Intel Macs only).
+        base_frequency_mhz: None,
+        max_frequency_mhz: None,
+        clflush_line_size: None,
+        // Stamped by `detect_cpu_info` - live counter data, not sysctl.
+        has_invariant_tsc: false,
+        tsc_frequency_hz: None,
+        microcode: None,
+        tdp_watts: None,
+        x86_signature: None,
+        avx512_downclock_risk: None,
+        detection_warnings,
+        logical_processor_ids: Default::default(),
+        schema_version: CpuInfo::SCHEMA_VERSION,
     };
-    info.normalize_domain_order();
+    info.finalize(&crate::DetectOptions::FULL);
+    timer.phase(DetectionPhase::Topology);
+    timer.finish();
     Ok(info)
 }
 
@@ -458,6 +646,7 @@ mod fixture_tests {
 
     use super::{SysctlSource, detect_at};
     use crate::platform::fixture_expected::{check_expected, fixture_root};
+    use crate::{CacheLevel, CacheOwner};
 
     /// Replays a recorded `sysctl.txt` dump from the shared fixture corpus.
     ///
@@ -465,6 +654,8 @@ mod fixture_tests {
     /// the same dumps): `i4 <key> <value>` / `i8 <key> <value>` for integers
     /// (recorded kernel width - Darwin sysctl keys are MIXED-width),
     /// `s <key> <value...>` for strings. `#` comments and blanks ignored.
+    /// The pseudo-key `ctr_el0` carries the recorded cache type register;
+    /// `ioreg_p_cores` / `ioreg_e_cores` the recorded IORegistry counts.
     struct FixtureSysctl {
         ints: HashMap<String, u64>,
         strs: HashMap<String, String>,
@@ -530,6 +721,16 @@ mod fixture_tests {
         fn string(&self, key: &str) -> Option<String> {
             self.strs.get(key).cloned()
         }
+
+        fn cache_type_register(&self) -> Option<u64> {
+            self.ints.get("ctr_el0").copied()
+        }
+
+        fn ioregistry_core_counts(&self) -> Option<(usize, usize)> {
+            let performance = *self.ints.get("ioreg_p_cores")?;
+            let efficiency = self.ints.get("ioreg_e_cores").copied().unwrap_or(0);
+            Some((performance as usize, efficiency as usize))
+        }
     }
 
     fn run_fixture(name: &str) {
@@ -558,4 +759,237 @@ mod fixture_tests {
     fn fixture_m3_max_perflevels() {
         run_fixture("sysctl-m3-max");
     }
+
+    // 8 P-cores in two clusters of 4 (M1 Pro / M2 Pro style): two shared
+    // L2 instances, not eight private copies.
+    #[test]
+    fn synthetic_clustered_l2_is_shared() {
+        let ints = [
+            ("hw.physicalcpu", 8),
+            ("hw.logicalcpu", 8),
+            ("hw.perflevel0.physicalcpu", 8),
+            ("hw.perflevel0.logicalcpu", 8),
+            ("hw.perflevel0.l2cachesize", 16 << 20),
+            ("hw.perflevel0.cpusperl2", 4),
+        ];
+        let src = FixtureSysctl {
+            ints: ints.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+            strs: HashMap::new(),
+        };
+
+        let info = detect_at(&src).unwrap();
+        let p = crate::CoreKind::Performance.index();
+
+        assert_eq!(info.l2[p].shared_by, 4);
+        assert_eq!(info.l2_domains.len(), 2);
+        for (i, domain) in info.l2_domains.iter().enumerate() {
+            assert_eq!(domain.core_count, 4);
+            assert_eq!(domain.size_bytes, 16 << 20);
+            assert_eq!(
+                domain.mask.iter().collect::<Vec<_>>(),
+                (i * 4..i * 4 + 4).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(info.total_l2_bytes(), 2 * (16 << 20));
+    }
+
+    // Apple Silicon: 64-byte L1 lines (CTR_EL0 DminLine = IminLine = 4
+    // words log2) under a 128-byte L2 line - each level keeps its own.
+    #[test]
+    fn synthetic_l1_and_l2_line_sizes_differ() {
+        let ints = [
+            ("hw.physicalcpu", 4),
+            ("hw.logicalcpu", 4),
+            ("hw.cachelinesize", 128),
+            ("ctr_el0", (4 << 16) | 4),
+            ("hw.perflevel0.physicalcpu", 4),
+            ("hw.perflevel0.logicalcpu", 4),
+            ("hw.perflevel0.l1dcachesize", 128 << 10),
+            ("hw.perflevel0.l1icachesize", 192 << 10),
+            ("hw.perflevel0.l2cachesize", 16 << 20),
+            ("hw.perflevel0.cpusperl2", 4),
+        ];
+        let mut src = FixtureSysctl {
+            ints: ints.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+            strs: HashMap::new(),
+        };
+        let p = crate::CoreKind::Performance.index();
+
+        let info = detect_at(&src).unwrap();
+        assert_eq!(info.l1d[p].line_bytes, 64);
+        assert_eq!(info.l1i[p].line_bytes, 64);
+        assert_eq!(info.l2[p].line_bytes, 128);
+
+        // Without the register every level falls back to hw.cachelinesize.
+        src.ints.remove("ctr_el0");
+        let info = detect_at(&src).unwrap();
+        assert_eq!(info.l1d[p].line_bytes, 128);
+        assert_eq!(info.l2[p].line_bytes, 128);
+    }
+
+    // M1-style 4P + 4E: each kind is one cluster behind one shared L2.
+    #[test]
+    fn synthetic_l2_clusters_split_p_and_e() {
+        let ints = [
+            ("hw.physicalcpu", 8),
+            ("hw.logicalcpu", 8),
+            ("hw.perflevel0.physicalcpu", 4),
+            ("hw.perflevel0.logicalcpu", 4),
+            ("hw.perflevel0.l2cachesize", 12 << 20),
+            ("hw.perflevel0.cpusperl2", 4),
+            ("hw.perflevel1.physicalcpu", 4),
+            ("hw.perflevel1.logicalcpu", 4),
+            ("hw.perflevel1.l2cachesize", 4 << 20),
+            ("hw.perflevel1.cpusperl2", 4),
+        ];
+        let src = FixtureSysctl {
+            ints: ints.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+            strs: HashMap::new(),
+        };
+
+        let info = detect_at(&src).unwrap();
+        assert_eq!(
+            info.l2_clusters(),
+            [info.performance_core_mask(), info.efficiency_core_mask()]
+        );
+        assert_eq!(
+            info.performance_core_mask().iter().collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(info.performance_cluster_count(), 1);
+        assert_eq!(info.efficiency_cluster_count(), 1);
+    }
+
+    // The cluster L2s are the socket's shared caches; an L3 joins them only
+    // when sysctl reports one.
+    #[test]
+    fn synthetic_socket_shared_caches_list_clusters_and_l3() {
+        let mut ints: HashMap<String, u64> = [
+            ("hw.physicalcpu", 8),
+            ("hw.logicalcpu", 8),
+            ("hw.perflevel0.physicalcpu", 4),
+            ("hw.perflevel0.logicalcpu", 4),
+            ("hw.perflevel0.l2cachesize", 12 << 20),
+            ("hw.perflevel0.cpusperl2", 4),
+            ("hw.perflevel1.physicalcpu", 4),
+            ("hw.perflevel1.logicalcpu", 4),
+            ("hw.perflevel1.l2cachesize", 4 << 20),
+            ("hw.perflevel1.cpusperl2", 4),
+        ]
+        .iter()
+        .map(|&(k, v)| (k.to_string(), v))
+        .collect();
+        let detect = |ints: &HashMap<String, u64>| {
+            detect_at(&FixtureSysctl {
+                ints: ints.clone(),
+                strs: HashMap::new(),
+            })
+            .unwrap()
+        };
+
+        let info = detect(&ints);
+        let shared = info.socket_shared_caches(0);
+        let levels: Vec<_> = shared
+            .iter()
+            .map(|c| (c.level, c.info.size_bytes))
+            .collect();
+        assert_eq!(
+            levels,
+            [(CacheLevel::L2, 12 << 20), (CacheLevel::L2, 4 << 20)]
+        );
+        assert_eq!(shared[0].owner, CacheOwner::CoreGroup(vec![0, 1, 2, 3]));
+        assert!(info.socket_shared_caches(1).is_empty());
+
+        ints.insert("hw.perflevel0.l3cachesize".to_string(), 8 << 20);
+        let with_l3 = detect(&ints);
+        let levels: Vec<_> = with_l3
+            .socket_shared_caches(0)
+            .iter()
+            .map(|c| c.level)
+            .collect();
+        assert_eq!(levels, [CacheLevel::L2, CacheLevel::L2, CacheLevel::L3]);
+    }
+
+    // A perflevel summary that disagrees with the per-core IORegistry
+    // listing: the IORegistry counts win, sysctl's caches stay, and the
+    // mismatch is reported. Agreement changes nothing.
+    #[test]
+    fn synthetic_ioregistry_counts_override_perflevels() {
+        let mut ints: HashMap<String, u64> = [
+            ("hw.physicalcpu", 8),
+            ("hw.logicalcpu", 8),
+            ("hw.perflevel0.physicalcpu", 4),
+            ("hw.perflevel0.logicalcpu", 4),
+            ("hw.perflevel0.l2cachesize", 16 << 20),
+            ("hw.perflevel0.cpusperl2", 6),
+            ("hw.perflevel1.physicalcpu", 4),
+            ("hw.perflevel1.logicalcpu", 4),
+            ("hw.perflevel1.l2cachesize", 4 << 20),
+            ("hw.perflevel1.cpusperl2", 4),
+            ("ioreg_p_cores", 4),
+            ("ioreg_e_cores", 4),
+        ]
+        .iter()
+        .map(|&(k, v)| (k.to_string(), v))
+        .collect();
+        let detect = |ints: &HashMap<String, u64>| {
+            detect_at(&FixtureSysctl {
+                ints: ints.clone(),
+                strs: HashMap::new(),
+            })
+            .unwrap()
+        };
+        let (p, e) = (
+            crate::CoreKind::Performance.index(),
+            crate::CoreKind::Efficiency.index(),
+        );
+
+        let agreed = detect(&ints);
+        assert!(agreed.detection_warnings.is_empty());
+        assert_eq!(agreed.kind_core_counts[p], 4);
+
+        ints.insert("ioreg_p_cores".to_string(), 6);
+        ints.insert("ioreg_e_cores".to_string(), 2);
+        let info = detect(&ints);
+        assert_eq!((info.kind_core_counts[p], info.kind_core_counts[e]), (6, 2));
+        assert_eq!(info.core_count, 8);
+        assert_eq!(
+            info.performance_core_mask().iter().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(info.l2[e].size_bytes, 4 << 20);
+        assert_eq!(info.detection_warnings.len(), 1);
+        assert!(
+            info.detection_warnings[0].contains("IORegistry (6P+2E)"),
+            "{:?}",
+            info.detection_warnings
+        );
+        assert!(info.validate().is_ok());
+
+        // A reading without P-cores is not a topology; sysctl stands.
+        ints.insert("ioreg_p_cores".to_string(), 0);
+        assert!(detect(&ints).detection_warnings.is_empty());
+    }
+
+    // Intel Mac with Hyper-Threading: no perflevels, two LPs per core.
+    #[test]
+    fn synthetic_intel_mac_reports_smt() {
+        let ints = [
+            ("hw.physicalcpu", 4),
+            ("hw.logicalcpu", 8),
+            ("hw.l1dcachesize", 32 << 10),
+            ("hw.l2cachesize", 256 << 10),
+        ];
+        let src = FixtureSysctl {
+            ints: ints.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+            strs: HashMap::new(),
+        };
+
+        let info = detect_at(&src).unwrap();
+        assert!(info.smt_supported);
+        assert!(info.is_smt_enabled());
+        assert_eq!(info.core_count, 4);
+        assert_eq!(info.num_logical_cores(), 8);
+        assert_eq!(info.smt_width(), 2);
+    }
 }
@@ -0,0 +1,163 @@
+//! Per-core cluster types from the IORegistry device tree - the cross-check
+//! for the `hw.perflevelN.physicalcpu` counts.
+//!
+//! Every core is an `IOPlatformDevice` node under `IODeviceTree:/cpus`
+//! (`cpu0`, `cpu1`, ...) whose `cluster-type` property is the data `"P\0"`
+//! or `"E\0"`. Counting the nodes enumerates the cores one by one instead of
+//! trusting a per-level summary. IOKit and CoreFoundation are declared
+//! directly here rather than pulling in binding crates for a handful of
+//! calls.
+
+use std::ffi::{c_char, c_void};
+
+/// `io_object_t` - a Mach port name; 0 is `IO_OBJECT_NULL`.
+type IoObject = u32;
+type CfTypeRef = *const c_void;
+
+const KERN_SUCCESS: i32 = 0;
+/// `kIOMainPortDefault`: `MACH_PORT_NULL` selects the default main port.
+const MAIN_PORT_DEFAULT: u32 = 0;
+/// `kCFStringEncodingUTF8`.
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    fn IORegistryEntryFromPath(main_port: u32, path: *const c_char) -> IoObject;
+    fn IORegistryEntryGetChildIterator(
+        entry: IoObject,
+        plane: *const c_char,
+        iterator: *mut IoObject,
+    ) -> i32;
+    fn IOIteratorNext(iterator: IoObject) -> IoObject;
+    fn IORegistryEntryCreateCFProperty(
+        entry: IoObject,
+        key: CfTypeRef,
+        allocator: CfTypeRef,
+        options: u32,
+    ) -> CfTypeRef;
+    fn IOObjectRelease(object: IoObject) -> i32;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    fn CFStringCreateWithCString(
+        allocator: CfTypeRef,
+        c_str: *const c_char,
+        encoding: u32,
+    ) -> CfTypeRef;
+    fn CFGetTypeID(cf: CfTypeRef) -> usize;
+    fn CFDataGetTypeID() -> usize;
+    fn CFDataGetLength(data: CfTypeRef) -> isize;
+    fn CFDataGetBytePtr(data: CfTypeRef) -> *const u8;
+    fn CFRelease(cf: CfTypeRef);
+}
+
+/// `(performance, efficiency)` core counts from the `cluster-type` of every
+/// `IODeviceTree:/cpus` node; `None` when the node is missing or no core
+/// reports a cluster type.
+pub(crate) fn cluster_core_counts() -> Option<(usize, usize)> {
+    // SAFETY: NUL-terminated literal; IO_OBJECT_NULL is returned on failure.
+    let cpus =
+        unsafe { IORegistryEntryFromPath(MAIN_PORT_DEFAULT, c"IODeviceTree:/cpus".as_ptr()) };
+    if cpus == 0 {
+        return None;
+    }
+
+    let mut iterator: IoObject = 0;
+    // SAFETY: `cpus` is a live entry; the iterator is a new reference,
+    // released below. The entry itself is no longer needed once iterated.
+    let kr = unsafe {
+        let kr = IORegistryEntryGetChildIterator(cpus, c"IODeviceTree".as_ptr(), &mut iterator);
+        IOObjectRelease(cpus);
+        kr
+    };
+    if kr != KERN_SUCCESS {
+        return None;
+    }
+
+    // SAFETY: NUL-terminated literal; the string is released below.
+    let key = unsafe {
+        CFStringCreateWithCString(
+            std::ptr::null(),
+            c"cluster-type".as_ptr(),
+            CF_STRING_ENCODING_UTF8,
+        )
+    };
+
+    let (mut performance, mut efficiency) = (0, 0);
+    if !key.is_null() {
+        loop {
+            // SAFETY: `iterator` is live; each entry is released after use.
+            let cpu = unsafe { IOIteratorNext(iterator) };
+            if cpu == 0 {
+                break;
+            }
+
+            match cluster_type(cpu, key) {
+                Some(b'P') => performance += 1,
+                Some(b'E') => efficiency += 1,
+                _ => {}
+            }
+
+            // SAFETY: releasing the reference IOIteratorNext returned.
+            unsafe { IOObjectRelease(cpu) };
+        }
+
+        // SAFETY: created above, released once.
+        unsafe { CFRelease(key) };
+    }
+
+    // SAFETY: releasing the reference the child iterator call returned.
+    unsafe { IOObjectRelease(iterator) };
+
+    (performance + efficiency > 0).then_some((performance, efficiency))
+}
+
+/// First byte of `cpu`'s `cluster-type` data (`b'P'` / `b'E'`).
+fn cluster_type(cpu: IoObject, key: CfTypeRef) -> Option<u8> {
+    // SAFETY: `cpu` and `key` are live; the property is a new reference
+    // (or null), checked to be CFData before its bytes are read and
+    // released before returning.
+    unsafe {
+        let value = IORegistryEntryCreateCFProperty(cpu, key, std::ptr::null(), 0);
+        if value.is_null() {
+            return None;
+        }
+
+        let first = (CFGetTypeID(value) == CFDataGetTypeID() && CFDataGetLength(value) > 0)
+            .then(|| *CFDataGetBytePtr(value));
+        CFRelease(value);
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoreKind;
+
+    // Whichever source won, live detection must agree with itself - and,
+    // where the IORegistry answers, with it.
+    #[test]
+    fn detected_counts_are_consistent() {
+        let info = crate::CpuInfo::detect().unwrap();
+        let counts = info.kind_core_counts;
+
+        assert_eq!(counts.iter().sum::<u16>(), info.core_count);
+        assert_eq!(
+            info.lps.iter().filter(|lp| lp.smt_index == 0).count(),
+            usize::from(info.core_count)
+        );
+
+        if let Some((performance, efficiency)) = cluster_core_counts() {
+            assert_eq!(
+                usize::from(counts[CoreKind::Performance.index()]),
+                performance
+            );
+            assert_eq!(
+                usize::from(counts[CoreKind::Efficiency.index()]),
+                efficiency
+            );
+        }
+    }
+}
@@ -29,7 +29,7 @@ pub(crate) fn sysctlbyname_int<T: TryFrom<u64>>(name: &str) -> Result<T> {
     use std::ffi::CString;
 
     let c_name = CString::new(name)
-        .map_err(|e| Error::Detection(format!("Invalid sysctl name {}: {}", name, e)))?;
+        .map_err(|e| Error::Detection(format!("Invalid sysctl name {}: {}", name, e).into()))?;
 
     let mut raw: u64 = 0;
     let mut size = std::mem::size_of::<u64>();
@@ -48,13 +48,14 @@ pub(crate) fn sysctlbyname_int<T: TryFrom<u64>>(name: &str) -> Result<T> {
         let os_err = std::io::Error::last_os_error();
 
         if os_err.raw_os_error() == Some(libc::ENOENT) {
-            return Err(Error::Detection(format!("Sysctl key {} not found", name)));
+            return Err(Error::Detection(
+                format!("Sysctl key {} not found", name).into(),
+            ));
         }
 
-        return Err(Error::SystemCall(format!(
-            "sysctlbyname for {} failed: {}",
-            name, os_err
-        )));
+        return Err(Error::SystemCall(
+            format!("sysctlbyname for {} failed: {}", name, os_err).into(),
+        ));
     }
 
     // aarch64 is little-endian: a 4-byte value occupies the low bytes of `raw`.
@@ -62,18 +63,24 @@ pub(crate) fn sysctlbyname_int<T: TryFrom<u64>>(name: &str) -> Result<T> {
         4 => raw & 0xFFFF_FFFF,
         8 => raw,
         n => {
-            return Err(Error::Detection(format!(
-                "sysctlbyname for {} returned unexpected size: {} (expected 4 or 8)",
-                name, n
-            )));
+            return Err(Error::Detection(
+                format!(
+                    "sysctlbyname for {} returned unexpected size: {} (expected 4 or 8)",
+                    name, n
+                )
+                .into(),
+            ));
         }
     };
 
     T::try_from(value).map_err(|_| {
-        Error::Detection(format!(
-            "sysctl {} value {} does not fit the requested integer type",
-            name, value
-        ))
+        Error::Detection(
+            format!(
+                "sysctl {} value {} does not fit the requested integer type",
+                name, value
+            )
+            .into(),
+        )
     })
 }
 
@@ -100,7 +107,7 @@ pub(crate) fn sysctlbyname_string(name: &str) -> Result<String> {
     use std::ffi::CString;
 
     let c_name = CString::new(name)
-        .map_err(|e| Error::Detection(format!("Invalid sysctl name {}: {}", name, e)))?;
+        .map_err(|e| Error::Detection(format!("Invalid sysctl name {}: {}", name, e).into()))?;
 
     let mut size: libc::size_t = 0;
 
@@ -118,13 +125,14 @@ pub(crate) fn sysctlbyname_string(name: &str) -> Result<String> {
         let os_err = std::io::Error::last_os_error();
 
         if os_err.raw_os_error() == Some(libc::ENOENT) {
-            return Err(Error::Detection(format!("Sysctl key {} not found", name)));
+            return Err(Error::Detection(
+                format!("Sysctl key {} not found", name).into(),
+            ));
         }
 
-        return Err(Error::SystemCall(format!(
-            "sysctlbyname for {} (size query) failed: {}",
-            name, os_err
-        )));
+        return Err(Error::SystemCall(
+            format!("sysctlbyname for {} (size query) failed: {}", name, os_err).into(),
+        ));
     }
 
     if size == 0 {
@@ -144,11 +152,14 @@ pub(crate) fn sysctlbyname_string(name: &str) -> Result<String> {
     };
 
     if ret == -1 {
-        return Err(Error::SystemCall(format!(
-            "sysctlbyname for {} (data query) failed: {}",
-            name,
-            std::io::Error::last_os_error()
-        )));
+        return Err(Error::SystemCall(
+            format!(
+                "sysctlbyname for {} (data query) failed: {}",
+                name,
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        ));
     }
 
     // Trim null bytes from the end if any, as size might include it.
@@ -157,5 +168,5 @@ pub(crate) fn sysctlbyname_string(name: &str) -> Result<String> {
     }
 
     String::from_utf8(buf)
-        .map_err(|e| Error::Detection(format!("UTF-8 conversion error for {}: {}", name, e)))
+        .map_err(|e| Error::Detection(format!("UTF-8 conversion error for {}: {}", name, e).into()))
 }
@@ -26,34 +26,33 @@ pub(crate) fn parse_range_list_with<F: FnMut(usize)>(range_str: &str, mut sink:
         if part.contains('-') {
             let mut iter = part.splitn(2, '-');
 
-            let start_str = iter
-                .next()
-                .ok_or_else(|| Error::Detection(format!("Invalid CPU range format: {}", part)))?;
-            let end_str = iter
-                .next()
-                .ok_or_else(|| Error::Detection(format!("Invalid CPU range format: {}", part)))?;
+            let start_str = iter.next().ok_or_else(|| {
+                Error::Detection(format!("Invalid CPU range format: {}", part).into())
+            })?;
+            let end_str = iter.next().ok_or_else(|| {
+                Error::Detection(format!("Invalid CPU range format: {}", part).into())
+            })?;
 
-            let start = start_str
-                .parse::<usize>()
-                .map_err(|_| Error::Detection(format!("Invalid CPU range start: {}", start_str)))?;
-            let end = end_str
-                .parse::<usize>()
-                .map_err(|_| Error::Detection(format!("Invalid CPU range end: {}", end_str)))?;
+            let start = start_str.parse::<usize>().map_err(|_| {
+                Error::Detection(format!("Invalid CPU range start: {}", start_str).into())
+            })?;
+            let end = end_str.parse::<usize>().map_err(|_| {
+                Error::Detection(format!("Invalid CPU range end: {}", end_str).into())
+            })?;
 
             if start > end {
-                return Err(Error::Detection(format!(
-                    "Invalid CPU range order: {}-{}",
-                    start, end
-                )));
+                return Err(Error::Detection(
+                    format!("Invalid CPU range order: {}-{}", start, end).into(),
+                ));
             }
 
             for id in start..=end {
                 sink(id);
             }
         } else {
-            let cpu_id = part
-                .parse::<usize>()
-                .map_err(|_| Error::Detection(format!("Invalid CPU ID in range list: {}", part)))?;
+            let cpu_id = part.parse::<usize>().map_err(|_| {
+                Error::Detection(format!("Invalid CPU ID in range list: {}", part).into())
+            })?;
 
             sink(cpu_id);
         }
@@ -0,0 +1,125 @@
+//! Detection phase timing for performance triage, and progress reporting.
+//!
+//! With the `log` feature each [`PhaseTimer::phase`] emits one `debug!` line
+//! with the time since the previous mark and [`PhaseTimer::finish`] the total.
+//! Without it only the progress callback remains, so detection code marks
+//! phases unconditionally.
+
+#[cfg(feature = "log")]
+use std::time::Instant;
+
+use crate::DetectionPhase;
+
+/// Marks the phases of one detection run and forwards them to the caller's
+/// progress callback ([`crate::CpuInfo::detect_with_progress`]).
+pub(crate) struct PhaseTimer<'a> {
+    progress: &'a mut dyn FnMut(DetectionPhase, f32),
+    #[cfg(feature = "log")]
+    platform: &'static str,
+    #[cfg(feature = "log")]
+    start: Instant,
+    #[cfg(feature = "log")]
+    last: Instant,
+}
+
+impl<'a> PhaseTimer<'a> {
+    /// Starts timing a detection run on `platform` (used as the log prefix),
+    /// reporting to `progress`.
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    pub(crate) fn start(
+        platform: &'static str,
+        progress: &'a mut dyn FnMut(DetectionPhase, f32),
+    ) -> Self {
+        #[cfg(feature = "log")]
+        {
+            let now = Instant::now();
+
+            Self {
+                progress,
+                platform,
+                start: now,
+                last: now,
+            }
+        }
+        #[cfg(not(feature = "log"))]
+        {
+            Self { progress }
+        }
+    }
+
+    /// Reports `fraction` (0.0-1.0) of `phase` done, without marking it.
+    pub(crate) fn progress(&mut self, phase: DetectionPhase, fraction: f32) {
+        (self.progress)(phase, fraction);
+    }
+
+    /// Marks `phase` complete: logs the time spent since the previous mark
+    /// and reports the phase at 1.0.
+    pub(crate) fn phase(&mut self, phase: DetectionPhase) {
+        #[cfg(feature = "log")]
+        {
+            let now = Instant::now();
+
+            log::debug!(
+                "{} detection: {} took {:?}",
+                self.platform,
+                phase,
+                now - self.last
+            );
+            self.last = now;
+        }
+        self.progress(phase, 1.0);
+    }
+
+    /// Logs the total time of the run.
+    pub(crate) fn finish(self) {
+        #[cfg(feature = "log")]
+        log::debug!(
+            "{} detection: total {:?}",
+            self.platform,
+            self.start.elapsed()
+        );
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use std::sync::Mutex;
+
+    static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Debug
+        }
+
+        fn log(&self, record: &log::Record) {
+            LINES.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    // The only test in the crate that installs a logger: `set_logger` is
+    // process-global and one-shot.
+    #[test]
+    fn detection_emits_phase_timing() {
+        log::set_logger(&CapturingLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let info = crate::CpuInfo::detect();
+
+        if cfg!(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "windows"
+        )) {
+            assert!(info.is_ok());
+
+            let lines = LINES.lock().unwrap();
+            assert!(lines.iter().any(|l| l.contains("detection: topology took")));
+            assert!(lines.iter().any(|l| l.contains("detection: total")));
+        }
+    }
+}
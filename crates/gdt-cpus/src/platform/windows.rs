@@ -8,6 +8,7 @@
 //! - Managing thread affinity via the [`affinity`] submodule.
 //! - Handling thread scheduling policies and priorities, exposed through the
 //!   [`scheduling_policy`] submodule.
+//! - Reporting AC / battery state for [`crate::is_on_battery`] ([`power`]).
 //! - Common utility functions specific to Windows platform code, available in
 //!   the [`utils`] submodule.
 //!
@@ -15,8 +16,12 @@
 //! Affinity and priority functions in the crate root use implementations from
 //! this module.
 
+// Thread control is feature `affinity`; detection is always built.
+#[cfg(feature = "affinity")]
 pub(crate) mod affinity;
 pub(crate) mod cpu;
+pub(crate) mod power;
 pub(crate) mod registry;
+#[cfg(feature = "affinity")]
 pub(crate) mod scheduling_policy;
 pub(crate) mod utils;
@@ -1,4 +1,5 @@
-//! Windows thread affinity (hard + soft) and priority for the current thread.
+//! Windows thread affinity (hard + soft) and priority for the current thread
+//! (hard affinity and priority also for any thread by handle).
 //!
 //! Hard affinity uses `SetThreadGroupAffinity`: a thread's hard affinity is
 //! single-group by OS design, so masks spanning multiple 64-LP processor
@@ -7,24 +8,39 @@
 //! Sets API (`SetThreadSelectedCpuSets`), the scheduling mode Intel's game
 //! guidance recommends: the scheduler PREFERS the given LPs but may still
 //! migrate under contention, cooperating with Thread Director / parking.
+//! The ideal processor (`SetThreadIdealProcessorEx`) is the lightest hint of
+//! all: one preferred LP, no restriction.
 
-use windows::Win32::Foundation::{HANDLE, NTSTATUS};
-use windows::Win32::System::SystemInformation::{GROUP_AFFINITY, GetSystemCpuSetInformation};
+use std::time::Duration;
+
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_INVALID_PARAMETER, FILETIME, HANDLE, NTSTATUS,
+};
+use windows::Win32::System::Kernel::PROCESSOR_NUMBER;
+use windows::Win32::System::SystemInformation::GROUP_AFFINITY;
 use windows::Win32::System::Threading::{
-    GetCurrentProcess, GetCurrentThread, SetThreadGroupAffinity, SetThreadPriority,
-    SetThreadSelectedCpuSets, THREAD_PRIORITY,
+    AvRevertMmThreadCharacteristics, AvSetMmThreadCharacteristicsW, GetCurrentProcess,
+    GetCurrentProcessorNumberEx, GetCurrentThread, GetCurrentThreadId, GetThreadGroupAffinity,
+    GetThreadIdealProcessorEx, GetThreadInformation, GetThreadPriority, GetThreadTimes, OpenThread,
+    SetProcessAffinityMask, SetThreadGroupAffinity, SetThreadIdealProcessorEx,
+    SetThreadInformation, SetThreadPriority, SetThreadSelectedCpuSets,
+    THREAD_POWER_THROTTLING_CURRENT_VERSION, THREAD_POWER_THROTTLING_EXECUTION_SPEED,
+    THREAD_POWER_THROTTLING_STATE, THREAD_PRIORITY, THREAD_QUERY_LIMITED_INFORMATION,
+    ThreadPowerThrottling,
 };
+use windows::core::{HRESULT, PCWSTR};
 
 use super::scheduling_policy::SchedulingPolicy;
+use super::utils::{for_each_cpu_set, to_wide_null_vec};
 use crate::{
     AffinityMask, AppliedPriority, Error, Grant, Mechanism, MechanismPolicy, Result, ThreadPriority,
 };
 
 // THREADINFOCLASS::ThreadGroupInformation - reads the thread's hard
-// GROUP_AFFINITY. The Win32 surface (`Win32_System_Threading`) exposes the
-// SetThreadGroupAffinity *setter* but no symmetric getter; ntdll's
-// NtQueryInformationThread is the documented read path, declared directly here
-// rather than pulling in the Wdk crate feature for one call.
+// GROUP_AFFINITY. The calling thread is read through ntdll's
+// NtQueryInformationThread, declared directly here rather than pulling in the
+// Wdk crate feature for one call; other threads (by id) go through
+// GetThreadGroupAffinity on a handle opened for the query.
 const THREAD_GROUP_INFORMATION: i32 = 22;
 
 #[link(name = "ntdll")]
@@ -44,9 +60,28 @@ unsafe extern "system" {
 /// that is an OS rule, not a library limitation. Multi-group placement is what
 /// [`set_thread_soft_affinity`] is for.
 pub(crate) fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
+    // SAFETY: GetCurrentThread returns a pseudo-handle that is always valid.
+    set_affinity_of(unsafe { GetCurrentThread() }, mask)
+}
+
+/// Sets the whole process's affinity via `SetProcessAffinityMask`, which also
+/// moves every existing thread. Same single-group rule as
+/// [`set_thread_affinity`]; the group must be the process's own (the OS
+/// rejects others with `ERROR_INVALID_PARAMETER`).
+pub(crate) fn set_process_affinity(mask: &AffinityMask) -> Result<()> {
+    let (_, bits) = single_group_bits(mask)?;
+
+    // SAFETY: GetCurrentProcess returns a pseudo-handle that is always valid.
+    unsafe { SetProcessAffinityMask(GetCurrentProcess(), bits) }
+        .map_err(|e| Error::Affinity(format!("SetProcessAffinityMask failed: {}", e).into()))
+}
+
+/// Splits `mask` into its processor group and the group-local bits; a hard
+/// affinity may name exactly one group.
+fn single_group_bits(mask: &AffinityMask) -> Result<(u16, usize)> {
     if mask.is_empty() {
         return Err(Error::Affinity(
-            "Cannot set thread affinity with an empty mask".to_string(),
+            "Cannot set thread affinity with an empty mask".into(),
         ));
     }
 
@@ -60,11 +95,14 @@ pub(crate) fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
         match group {
             None => group = Some(g),
             Some(existing) if existing != g => {
-                return Err(Error::InvalidParameter(format!(
-                    "Hard affinity is single-group on Windows (mask spans groups {} and {}); \
+                return Err(Error::InvalidParameter(
+                    format!(
+                        "Hard affinity is single-group on Windows (mask spans groups {} and {}); \
                      use soft affinity (CPU Sets) for cross-group placement",
-                    existing, g
-                )));
+                        existing, g
+                    )
+                    .into(),
+                ));
             }
             _ => {}
         }
@@ -72,20 +110,28 @@ pub(crate) fn set_thread_affinity(mask: &AffinityMask) -> Result<()> {
         bits |= 1usize << (lp % 64);
     }
 
+    Ok((group.unwrap_or(0), bits))
+}
+
+/// [`set_thread_affinity`] for the thread behind `thread` (needs
+/// `THREAD_SET_INFORMATION` and `THREAD_QUERY_INFORMATION` access) - the
+/// [`crate::RawThread`] path.
+pub(crate) fn set_affinity_of(thread: HANDLE, mask: &AffinityMask) -> Result<()> {
+    let (group, bits) = single_group_bits(mask)?;
+
     let ga = GROUP_AFFINITY {
         Mask: bits,
-        Group: group.unwrap_or(0),
+        Group: group,
         Reserved: [0; 3],
     };
 
-    let ok = unsafe { SetThreadGroupAffinity(GetCurrentThread(), &ga, None) };
+    let ok = unsafe { SetThreadGroupAffinity(thread, &ga, None) };
 
     if !ok.as_bool() {
         let err = std::io::Error::last_os_error();
-        return Err(Error::Affinity(format!(
-            "SetThreadGroupAffinity failed: {}",
-            err
-        )));
+        return Err(Error::Affinity(
+            format!("SetThreadGroupAffinity failed: {}", err).into(),
+        ));
     }
 
     Ok(())
@@ -113,9 +159,9 @@ pub(crate) fn current_affinity() -> Result<AffinityMask> {
     };
 
     if status.is_err() {
-        return Err(Error::Affinity(format!(
-            "NtQueryInformationThread(ThreadGroupInformation) failed: {status:?}"
-        )));
+        return Err(Error::Affinity(
+            format!("NtQueryInformationThread(ThreadGroupInformation) failed: {status:?}").into(),
+        ));
     }
 
     let mut mask = AffinityMask::empty();
@@ -129,77 +175,137 @@ pub(crate) fn current_affinity() -> Result<AffinityMask> {
     Ok(mask)
 }
 
-/// Sets the current thread's SOFT affinity (CPU Sets) to `mask` (OS LP ids).
-///
-/// Cross-group capable. Never called with an empty selection - passing zero
-/// CpuSet ids to the OS would CLEAR the assignment, which v1 does not expose.
-pub(crate) fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
-    if mask.is_empty() {
+/// Reads the hard-affinity mask of thread `id` (a `GetCurrentThreadId`
+/// value, any process) via `OpenThread` + `GetThreadGroupAffinity`, the
+/// getter for a handle opened with query rights only.
+pub(crate) fn affinity_of_thread_id(id: u64) -> Result<AffinityMask> {
+    let id = u32::try_from(id).map_err(|_| {
+        Error::InvalidParameter(format!("thread id {id} is out of the Win32 DWORD range").into())
+    })?;
+
+    // SAFETY: no pointer arguments; the returned handle is closed below.
+    let thread = unsafe { OpenThread(THREAD_QUERY_LIMITED_INFORMATION, false, id) }
+        .map_err(|e| Error::NotFound(format!("OpenThread({id}) failed: {e}").into()))?;
+
+    let mut ga = GROUP_AFFINITY::default();
+    // SAFETY: `thread` is a live handle with query access and `ga` a properly
+    // sized out-buffer.
+    let ok = unsafe { GetThreadGroupAffinity(thread, &mut ga) };
+    let err = std::io::Error::last_os_error();
+    // SAFETY: `thread` came from OpenThread above and is closed exactly once.
+    let _ = unsafe { CloseHandle(thread) };
+
+    if !ok.as_bool() {
         return Err(Error::Affinity(
-            "Cannot set soft affinity with an empty mask".to_string(),
+            format!("GetThreadGroupAffinity failed: {err}").into(),
         ));
     }
 
-    // Walk SYSTEM_CPU_SET_INFORMATION records; map Group*64+LogicalProcessorIndex
-    // to OS LP ids and collect the CpuSet Ids covered by the mask.
-    let mut needed: u32 = 0;
+    let mut mask = AffinityMask::empty();
 
-    unsafe {
-        let _ = GetSystemCpuSetInformation(None, 0, &mut needed, Some(GetCurrentProcess()), None);
+    for bit in 0..64usize {
+        if (ga.Mask >> bit) & 1 != 0 {
+            mask.add(ga.Group as usize * 64 + bit);
+        }
     }
 
-    if needed == 0 {
-        return Err(Error::Unsupported(
-            "CPU Sets are not available on this system".to_string(),
-        ));
-    }
+    Ok(mask)
+}
 
-    let mut buffer: Vec<u8> = vec![0; needed as usize];
+/// The OS LP id (`group * 64 + number`) the calling thread is running on
+/// right now, via `GetCurrentProcessorNumberEx`.
+pub(crate) fn current_logical_processor() -> Result<usize> {
+    // SAFETY: no preconditions; fills a PROCESSOR_NUMBER for the calling thread.
+    let pn = unsafe { GetCurrentProcessorNumberEx() };
 
-    let ok = unsafe {
-        GetSystemCpuSetInformation(
-            Some(buffer.as_mut_ptr() as *mut _),
-            needed,
-            &mut needed,
-            Some(GetCurrentProcess()),
-            None,
-        )
-    };
+    Ok(pn.Group as usize * 64 + pn.Number as usize)
+}
 
-    if !ok.as_bool() {
-        let err = std::io::Error::last_os_error();
-        return Err(Error::SystemCall(format!(
-            "GetSystemCpuSetInformation failed: {}",
-            err
-        )));
+/// CPU time the calling thread has consumed (kernel + user), via
+/// `GetThreadTimes`. Charged per clock tick, so it advances in steps of the
+/// timer resolution (15.6 ms by default), not continuously.
+pub(crate) fn current_thread_cpu_time() -> Result<Duration> {
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+
+    // SAFETY: pseudo-handle for the calling thread; all four out-pointers are
+    // live FILETIMEs.
+    unsafe {
+        GetThreadTimes(
+            GetCurrentThread(),
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        )
     }
+    .map_err(|e| Error::SystemCall(format!("GetThreadTimes failed: {}", e).into()))?;
 
-    let mut ids: Vec<u32> = Vec::new();
-    let mut offset: usize = 0;
+    // FILETIME durations count 100 ns intervals.
+    let ticks = |ft: FILETIME| (u64::from(ft.dwHighDateTime) << 32) | u64::from(ft.dwLowDateTime);
 
-    while offset + 8 <= needed as usize {
-        // Size (u32) + Type (u32) prefix; CpuSet payload follows.
-        let size = u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap()) as usize;
+    Ok(Duration::from_nanos((ticks(kernel) + ticks(user)) * 100))
+}
 
-        // Reject malformed records: `size` in 1..8 would make the payload slice
-        // start (offset+8) past its end (offset+size) and panic; `size == 0`
-        // would loop forever. Both are caught by requiring a full 8-byte header.
-        if size < 8 || offset + size > needed as usize {
-            break;
-        }
+/// The calling thread's id (`GetCurrentThreadId`) - never fails.
+pub(crate) fn current_thread_id() -> u64 {
+    // SAFETY: no preconditions.
+    u64::from(unsafe { GetCurrentThreadId() })
+}
 
-        // `Type` is the second u32. This is an extensible tagged stream;
-        // CpuSetInformation == 0. Skip any other record type (do not parse its
-        // bytes as a CpuSet) but keep walking.
-        let rec_type = u32::from_le_bytes(buffer[offset + 4..offset + 8].try_into().unwrap());
+/// Sets the calling thread's ideal processor to OS LP `lp_id`
+/// (`group * 64 + number`) via `SetThreadIdealProcessorEx`.
+pub(crate) fn set_thread_ideal_processor(lp_id: usize) -> Result<()> {
+    let group = u16::try_from(lp_id / 64).map_err(|_| Error::InvalidCoreId(lp_id))?;
+    let pn = PROCESSOR_NUMBER {
+        Group: group,
+        Number: (lp_id % 64) as u8,
+        Reserved: 0,
+    };
 
-        if rec_type != 0 {
-            offset += size;
-            continue;
+    // SAFETY: pseudo-handle for the calling thread; `pn` outlives the call and
+    // the previous-value out-pointer is omitted.
+    unsafe { SetThreadIdealProcessorEx(GetCurrentThread(), &pn, None) }.map_err(|e| {
+        // No such processor in that group.
+        if e.code() == HRESULT::from_win32(ERROR_INVALID_PARAMETER.0) {
+            Error::InvalidCoreId(lp_id)
+        } else {
+            Error::Affinity(format!("SetThreadIdealProcessorEx failed: {}", e).into())
         }
+    })
+}
+
+/// Reads the calling thread's ideal processor as an OS LP id
+/// (`group * 64 + number`) via `GetThreadIdealProcessorEx`.
+pub(crate) fn current_ideal_processor() -> Result<usize> {
+    let mut pn = PROCESSOR_NUMBER::default();
+
+    // SAFETY: pseudo-handle for the calling thread; `pn` is a valid out-pointer.
+    unsafe { GetThreadIdealProcessorEx(GetCurrentThread(), &mut pn) }.map_err(|e| {
+        Error::SystemCall(format!("GetThreadIdealProcessorEx failed: {}", e).into())
+    })?;
+
+    Ok(pn.Group as usize * 64 + pn.Number as usize)
+}
+
+/// Sets the current thread's SOFT affinity (CPU Sets) to `mask` (OS LP ids).
+///
+/// Cross-group capable. Never called with an empty selection - passing zero
+/// CpuSet ids to the OS would CLEAR the assignment, which v1 does not expose.
+pub(crate) fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
+    if mask.is_empty() {
+        return Err(Error::Affinity(
+            "Cannot set soft affinity with an empty mask".into(),
+        ));
+    }
 
-        let payload = &buffer[offset + 8..offset + size];
+    // Map Group*64+LogicalProcessorIndex to OS LP ids and collect the CpuSet
+    // Ids covered by the mask.
+    let mut ids: Vec<u32> = Vec::new();
 
+    for_each_cpu_set(|payload| {
         // SYSTEM_CPU_SET_INFORMATION.CpuSet: Id(u32) Group(u16) LpIndex(u8) ...
         if payload.len() >= 8 {
             let id = u32::from_le_bytes(payload[0..4].try_into().unwrap());
@@ -211,9 +317,7 @@ pub(crate) fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
                 ids.push(id);
             }
         }
-
-        offset += size;
-    }
+    })?;
 
     if ids.is_empty() {
         return Err(Error::InvalidCoreId(
@@ -226,10 +330,9 @@ pub(crate) fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
     if !ok.as_bool() {
         let err = std::io::Error::last_os_error();
 
-        return Err(Error::SystemCall(format!(
-            "SetThreadSelectedCpuSets failed: {}",
-            err
-        )));
+        return Err(Error::SystemCall(
+            format!("SetThreadSelectedCpuSets failed: {}", err).into(),
+        ));
     }
 
     Ok(())
@@ -237,22 +340,188 @@ pub(crate) fn set_thread_soft_affinity(mask: &AffinityMask) -> Result<()> {
 
 /// Sets the current thread's priority (`THREAD_PRIORITY_IDLE..TIME_CRITICAL`).
 pub(crate) fn set_thread_priority(priority: ThreadPriority) -> Result<AppliedPriority> {
-    set_thread_priority_with_grant(priority, Grant::Direct)
+    set_thread_priority_with_grant(unsafe { GetCurrentThread() }, priority, Grant::Direct)
+}
+
+/// [`set_thread_priority`] for the thread behind `thread` (needs
+/// `THREAD_SET_LIMITED_INFORMATION` access) - the [`crate::RawThread`] path.
+pub(crate) fn set_priority_of(thread: HANDLE, priority: ThreadPriority) -> Result<AppliedPriority> {
+    set_thread_priority_with_grant(thread, priority, Grant::Direct)
 }
 
 /// Applies Windows time-critical priority through the explicit realtime API.
 pub(crate) fn promote_thread_to_realtime() -> Result<AppliedPriority> {
-    set_thread_priority_with_grant(ThreadPriority::TimeCritical, Grant::Realtime)
+    set_thread_priority_with_grant(
+        unsafe { GetCurrentThread() },
+        ThreadPriority::TimeCritical,
+        Grant::Realtime,
+    )
+}
+
+/// `AvSetMmThreadCharacteristicsW` for the calling thread; returns the raw
+/// registration handle for [`revert_mmcss_task`].
+pub(crate) fn register_mmcss_task(task_name: &str) -> Result<*mut core::ffi::c_void> {
+    let wide_task_name = to_wide_null_vec(task_name);
+    let mut task_index: u32 = 0;
+
+    // SAFETY: `wide_task_name` is NUL-terminated and outlives the call;
+    // valid out-pointer for the task index.
+    let handle =
+        unsafe { AvSetMmThreadCharacteristicsW(PCWSTR(wide_task_name.as_ptr()), &mut task_index) }
+            .map_err(|e| {
+                Error::SystemCall(
+                    format!(
+                        "AvSetMmThreadCharacteristicsW(\"{}\") failed: {}",
+                        task_name, e
+                    )
+                    .into(),
+                )
+            })?;
+
+    Ok(handle.0)
+}
+
+/// `AvRevertMmThreadCharacteristics` for a handle from [`register_mmcss_task`],
+/// on the thread that registered it.
+pub(crate) fn revert_mmcss_task(raw: *mut core::ffi::c_void) -> Result<()> {
+    // SAFETY: `raw` came from AvSetMmThreadCharacteristicsW on this thread
+    // (MmcssHandle is !Send) and is reverted at most once (from Drop).
+    unsafe { AvRevertMmThreadCharacteristics(HANDLE(raw)) }.map_err(|e| {
+        Error::SystemCall(format!("AvRevertMmThreadCharacteristics failed: {}", e).into())
+    })
+}
+
+/// The Windows audio recipe for the calling thread: MMCSS "Pro Audio" task,
+/// power throttling (EcoQoS) off, then `THREAD_PRIORITY_TIME_CRITICAL`.
+///
+/// The first two are best effort - the MMCSS service can be disabled and
+/// power throttling does not exist before Windows 10 1709 - and never fail
+/// the call; the priority step decides the result.
+pub(crate) fn configure_audio_thread() -> Result<AppliedPriority> {
+    // The registration is deliberately never reverted: it is meant to last
+    // for the thread's lifetime, and MMCSS drops it when the thread exits.
+    if let Err(_e) = register_mmcss_task("Pro Audio") {
+        #[cfg(feature = "log")]
+        log::warn!("{}", _e);
+    }
+
+    if let Err(_e) = set_power_throttling(&power_throttling_state(false)) {
+        #[cfg(feature = "log")]
+        log::warn!("Disabling power throttling failed: {}", _e);
+    }
+
+    promote_thread_to_realtime()
+}
+
+/// A power-throttling (EcoQoS) state that explicitly turns execution-speed
+/// throttling on or off: ControlMask selects the policy, StateMask sets it.
+pub(crate) fn power_throttling_state(enabled: bool) -> THREAD_POWER_THROTTLING_STATE {
+    THREAD_POWER_THROTTLING_STATE {
+        Version: THREAD_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: THREAD_POWER_THROTTLING_EXECUTION_SPEED,
+        StateMask: if enabled {
+            THREAD_POWER_THROTTLING_EXECUTION_SPEED
+        } else {
+            0
+        },
+    }
+}
+
+/// The calling thread's power-throttling state, for save/restore. An empty
+/// ControlMask means the system decides.
+pub(crate) fn power_throttling() -> Result<THREAD_POWER_THROTTLING_STATE> {
+    let mut state = THREAD_POWER_THROTTLING_STATE {
+        Version: THREAD_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: 0,
+        StateMask: 0,
+    };
+
+    // SAFETY: pseudo-handle for the calling thread; `state` is a live
+    // THREAD_POWER_THROTTLING_STATE of the stated size.
+    unsafe {
+        GetThreadInformation(
+            GetCurrentThread(),
+            ThreadPowerThrottling,
+            &mut state as *mut THREAD_POWER_THROTTLING_STATE as *mut core::ffi::c_void,
+            std::mem::size_of::<THREAD_POWER_THROTTLING_STATE>() as u32,
+        )
+    }
+    .map_err(|e| Error::SystemCall(format!("Reading power throttling failed: {}", e).into()))?;
+
+    Ok(state)
+}
+
+/// Sets the calling thread's power-throttling state. Fails before Windows 10
+/// 1709, which has no such policy.
+pub(crate) fn set_power_throttling(state: &THREAD_POWER_THROTTLING_STATE) -> Result<()> {
+    // SAFETY: pseudo-handle for the calling thread; `state` is a live
+    // THREAD_POWER_THROTTLING_STATE of the stated size.
+    unsafe {
+        SetThreadInformation(
+            GetCurrentThread(),
+            ThreadPowerThrottling,
+            state as *const THREAD_POWER_THROTTLING_STATE as *const core::ffi::c_void,
+            std::mem::size_of::<THREAD_POWER_THROTTLING_STATE>() as u32,
+        )
+    }
+    .map_err(|e| Error::SystemCall(format!("Setting power throttling failed: {}", e).into()))
+}
+
+/// The calling thread's raw `GetThreadPriority` value, for save/restore.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SavedPriority(i32);
+
+/// Snapshots the calling thread's priority.
+pub(crate) fn save_thread_priority() -> Result<SavedPriority> {
+    // THREAD_PRIORITY_ERROR_RETURN (MAXLONG) is the failure value.
+    // SAFETY: pseudo-handle for the calling thread.
+    match unsafe { GetThreadPriority(GetCurrentThread()) } {
+        i32::MAX => Err(Error::SystemCall(
+            format!(
+                "GetThreadPriority failed: {}",
+                std::io::Error::last_os_error()
+            )
+            .into(),
+        )),
+        value => Ok(SavedPriority(value)),
+    }
+}
+
+/// The calling thread's `GetThreadPriority` level by its Win32 name
+/// (`"THREAD_PRIORITY_NORMAL"`), for reports.
+pub(crate) fn describe_thread_priority() -> Result<String> {
+    let SavedPriority(level) = save_thread_priority()?;
+
+    Ok(match level {
+        -15 => "THREAD_PRIORITY_IDLE".to_string(),
+        -2 => "THREAD_PRIORITY_LOWEST".to_string(),
+        -1 => "THREAD_PRIORITY_BELOW_NORMAL".to_string(),
+        0 => "THREAD_PRIORITY_NORMAL".to_string(),
+        1 => "THREAD_PRIORITY_ABOVE_NORMAL".to_string(),
+        2 => "THREAD_PRIORITY_HIGHEST".to_string(),
+        15 => "THREAD_PRIORITY_TIME_CRITICAL".to_string(),
+        // Only REALTIME_PRIORITY_CLASS processes use the values in between.
+        other => format!("thread priority {}", other),
+    })
+}
+
+/// Puts the calling thread back at a [`save_thread_priority`] snapshot.
+pub(crate) fn restore_thread_priority(saved: &SavedPriority) -> Result<()> {
+    // SAFETY: pseudo-handle for the calling thread; the value came from
+    // GetThreadPriority, so it is a valid level.
+    unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY(saved.0)) }
+        .map_err(|e| Error::SystemCall(format!("SetThreadPriority failed: {}", e).into()))
 }
 
 fn set_thread_priority_with_grant(
+    thread: HANDLE,
     priority: ThreadPriority,
     grant: Grant,
 ) -> Result<AppliedPriority> {
     // Map the portable level to the Windows value (table in scheduling_policy.rs).
     let sched_policy = SchedulingPolicy::default_for(priority);
 
-    let result = unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY(sched_policy.0)) };
+    let result = unsafe { SetThreadPriority(thread, THREAD_PRIORITY(sched_policy.0)) };
 
     match result {
         Ok(_) => Ok(AppliedPriority::new(
@@ -266,9 +535,39 @@ fn set_thread_priority_with_grant(
                 value: sched_policy.0 as i8,
             },
         )),
-        Err(e) => Err(Error::SystemCall(format!(
-            "SetThreadPriority failed with error: {:?}",
-            e
-        ))),
+        Err(e) => Err(Error::SystemCall(
+            format!("SetThreadPriority failed with error: {:?}", e).into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mmcss_games_task_registers_and_reverts() {
+        std::thread::spawn(|| {
+            let handle = crate::register_mmcss_task("Games").unwrap();
+            drop(handle);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn ideal_processor_round_trips() {
+        let original = current_ideal_processor().unwrap();
+        let target = current_affinity()
+            .unwrap()
+            .iter()
+            .last()
+            .expect("the thread may run somewhere");
+
+        set_thread_ideal_processor(target).unwrap();
+        assert_eq!(current_ideal_processor().unwrap(), target);
+
+        set_thread_ideal_processor(original).unwrap();
+        assert!(set_thread_ideal_processor(63 * 64 + 63).is_err());
     }
 }
@@ -16,10 +16,11 @@ use std::ptr;
 
 use windows::Win32::Foundation::GetLastError;
 use windows::Win32::System::SystemInformation::{
-    CacheData, CacheInstruction, CacheUnified, GetLogicalProcessorInformationEx, RelationAll,
-    RelationCache, RelationNumaNode, RelationProcessorCore, RelationProcessorPackage,
-    SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX,
+    CacheData, CacheInstruction, CacheUnified, GetLogicalProcessorInformationEx, GetSystemInfo,
+    RelationAll, RelationCache, RelationNumaNode, RelationProcessorCore, RelationProcessorPackage,
+    SYSTEM_INFO, SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX,
 };
+use windows::Win32::System::Threading::{GetActiveProcessorCount, GetActiveProcessorGroupCount};
 
 #[cfg(target_arch = "aarch64")]
 use windows::Win32::System::Threading::{
@@ -30,9 +31,10 @@ use windows::Win32::System::Threading::{
     PROCESSOR_FEATURE_ID,
 };
 
+use crate::platform::timing::PhaseTimer;
 use crate::{
-    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, Error, L2Domain, L3Domain, Lp, Result,
-    Vendor,
+    AffinityMask, CacheInfo, CoreKind, CpuFeatures, CpuInfo, DetectionPhase, Error, L2Domain,
+    L3Domain, Lp, Result, Vendor,
 };
 
 #[cfg(target_arch = "aarch64")]
@@ -95,6 +97,9 @@ enum CacheType {
 
 struct RawCore {
     efficiency_class: u8,
+    /// CPU Set `SchedulingClass` - the OS preferred-core rank (Turbo Boost
+    /// Max 3.0 / AMD preferred cores), higher = faster. 0 when unreported.
+    scheduling_class: u8,
     lp_ids: Vec<u16>, // sorted ascending
 }
 
@@ -112,6 +117,9 @@ struct RawTopology {
     packages: Vec<Vec<u16>>,
     caches: Vec<RawCache>,
     numa_nodes: Vec<(u32, Vec<u16>)>,
+    /// Gaps the collector papered over, forwarded to
+    /// [`CpuInfo::detection_warnings`].
+    warnings: Vec<String>,
 }
 
 /// Expands a GROUP_AFFINITY into `group * 64 + bit` LP ids.
@@ -124,7 +132,9 @@ fn expand_group_mask(group: u16, mask: usize, out: &mut Vec<u16>) {
 }
 
 /// Detects CPU information on Windows.
-pub fn detect_cpu_info() -> Result<CpuInfo> {
+pub fn detect_cpu_info(progress: &mut dyn FnMut(DetectionPhase, f32)) -> Result<CpuInfo> {
+    let mut timer = PhaseTimer::start("windows", progress);
+
     // --- Identity ---
     let mut vendor = Vendor::Unknown;
     let mut model_name = "Unknown".to_string();
@@ -145,6 +155,45 @@ pub fn detect_cpu_info() -> Result<CpuInfo> {
         super::registry::detect_via_registry(&mut vendor, &mut model_name)?;
     }
 
+    timer.phase(DetectionPhase::Identity);
+
+    let mut raw = collect_topology(collect_glpi_topology, collect_fallback_topology)?;
+
+    read_scheduling_classes(&mut raw);
+
+    // Before assembly: finalize estimates cache latencies from it.
+    let x86_signature = crate::platform::detect_x86_signature();
+    let mut info = build_cpu_info(raw, vendor, model_name, features, x86_signature)?;
+
+    timer.phase(DetectionPhase::Topology);
+
+    // NOTE(windows): cpuid leaf 0x16 only; Windows-on-ARM reports no clocks.
+    #[cfg(target_arch = "x86_64")]
+    let (base, max) = crate::platform::common_x86_64::detect_frequency_via_cpuid();
+    #[cfg(not(target_arch = "x86_64"))]
+    let (base, max) = (None, None);
+
+    info.base_frequency_mhz = base;
+    info.max_frequency_mhz = max;
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        info.clflush_line_size = crate::platform::common_x86_64::detect_clflush_line_size();
+    }
+
+    (info.has_invariant_tsc, info.tsc_frequency_hz) = crate::platform::detect_timestamp_counter();
+    info.avx512_downclock_risk = crate::platform::detect_avx512_downclock_risk(info.vendor);
+    crate::platform::fill_cache_inclusiveness(&mut info);
+
+    info.microcode = super::registry::detect_microcode_via_registry();
+
+    timer.finish();
+    Ok(info)
+}
+
+/// Primary topology source: one `GetLogicalProcessorInformationEx(RelationAll)`
+/// buffer walked into raw relations (phase 1).
+fn collect_glpi_topology() -> Result<RawTopology> {
     // --- GLPI buffer, two-call pattern ---
     let mut buffer_size: u32 = 0;
 
@@ -155,10 +204,13 @@ pub fn detect_cpu_info() -> Result<CpuInfo> {
     if size_query.is_err() && buffer_size == 0 {
         let err = unsafe { GetLastError() };
 
-        return Err(Error::SystemCall(format!(
-            "GetLogicalProcessorInformationEx (size query) failed: {:?}",
-            err
-        )));
+        return Err(Error::SystemCall(
+            format!(
+                "GetLogicalProcessorInformationEx (size query) failed: {:?}",
+                err
+            )
+            .into(),
+        ));
     }
 
     let mut buffer: Vec<u8> = vec![0; buffer_size as usize];
@@ -170,7 +222,9 @@ pub fn detect_cpu_info() -> Result<CpuInfo> {
             &mut buffer_size,
         )
     }
-    .map_err(|e| Error::SystemCall(format!("GetLogicalProcessorInformationEx failed: {:?}", e)))?;
+    .map_err(|e| {
+        Error::SystemCall(format!("GetLogicalProcessorInformationEx failed: {:?}", e).into())
+    })?;
 
     // --- Phase 1: collect raw relations (no classification yet) ---
     let mut raw = RawTopology::default();
@@ -208,6 +262,7 @@ pub fn detect_cpu_info() -> Result<CpuInfo> {
                 if info.Relationship == RelationProcessorCore {
                     raw.cores.push(RawCore {
                         efficiency_class: rel.EfficiencyClass,
+                        scheduling_class: 0,
                         lp_ids,
                     });
                 } else {
@@ -267,7 +322,182 @@ pub fn detect_cpu_info() -> Result<CpuInfo> {
         current = unsafe { current.add(info.Size as usize) };
     }
 
-    build_cpu_info(raw, vendor, model_name, features)
+    if raw.cores.is_empty() {
+        return Err(Error::Detection(
+            "GetLogicalProcessorInformationEx reported no processor cores".into(),
+        ));
+    }
+
+    Ok(raw)
+}
+
+/// Degraded topology for sandboxes where GLPI is unavailable (some UWP / Xbox
+/// contexts): LP ids only (see [`fallback_lp_ids`]). Every LP becomes its own
+/// core on one socket with one efficiency class - i.e. homogeneous, all
+/// Performance, no caches. The LP count is exact; SMT siblings are counted as
+/// cores and the hybrid layout is lost.
+fn collect_fallback_topology() -> Result<RawTopology> {
+    let groups = unsafe { GetActiveProcessorGroupCount() };
+    let counts: Vec<usize> = (0..groups)
+        .map(|group| unsafe { GetActiveProcessorCount(group) } as usize)
+        .collect();
+
+    let mut sys_info = SYSTEM_INFO::default();
+
+    unsafe { GetSystemInfo(&mut sys_info) };
+
+    let (lp_ids, assumed_dense) = fallback_lp_ids(&counts, sys_info.dwActiveProcessorMask);
+
+    if lp_ids.is_empty() {
+        return Err(Error::SystemCall(
+            "GetActiveProcessorCount and GetSystemInfo reported no processors".into(),
+        ));
+    }
+
+    let mut warnings = vec![
+        "GetLogicalProcessorInformationEx unavailable: one core per LP assumed, \
+         SMT and hybrid layout unknown"
+            .to_string(),
+    ];
+    if assumed_dense {
+        warnings.push(
+            "processor groups assumed densely populated: LP ids after an offline LP may be wrong"
+                .to_string(),
+        );
+    }
+
+    Ok(RawTopology {
+        cores: lp_ids
+            .iter()
+            .map(|&id| RawCore {
+                efficiency_class: 0,
+                scheduling_class: 0,
+                lp_ids: vec![id],
+            })
+            .collect(),
+        packages: vec![lp_ids],
+        warnings,
+        ..RawTopology::default()
+    })
+}
+
+/// LP ids of the degraded topology from the active LP count per processor
+/// group and `GetSystemInfo`'s active mask. The mask is the real one, gaps
+/// for offline LPs included, but it describes a single group - so it is used
+/// on single-group machines. With several groups no source short of GLPI
+/// reports each group's mask, and every group is ASSUMED to fill from bit 0
+/// up to its count; the returned `bool` says the assumption was made, so the
+/// caller can warn.
+fn fallback_lp_ids(group_counts: &[usize], active_mask: usize) -> (Vec<u16>, bool) {
+    let mut lp_ids: Vec<u16> = Vec::new();
+
+    if group_counts.len() <= 1 && active_mask != 0 {
+        expand_group_mask(0, active_mask, &mut lp_ids);
+        return (lp_ids, false);
+    }
+
+    for (group, &count) in group_counts.iter().enumerate() {
+        let mask = if count >= usize::BITS as usize {
+            usize::MAX
+        } else {
+            (1usize << count) - 1
+        };
+
+        expand_group_mask(group as u16, mask, &mut lp_ids);
+    }
+
+    (lp_ids, true)
+}
+
+/// Stamps each core's CPU Set `SchedulingClass` - GLPI's `EfficiencyClass`
+/// only separates kinds, the per-core preferred-core rank lives here.
+/// Best-effort: CPU Sets missing (pre-Win10) or the field unset (0 on
+/// builds before it was populated) leave every core at 0.
+fn read_scheduling_classes(raw: &mut RawTopology) {
+    let mut classes: Vec<(u16, u8)> = Vec::new();
+
+    // SYSTEM_CPU_SET_INFORMATION.CpuSet: Group(u16) at 4, LpIndex(u8) at 6,
+    // SchedulingClass(u8) at 12.
+    let walked = super::utils::for_each_cpu_set(|payload| {
+        if payload.len() >= 13 {
+            let group = u16::from_le_bytes(payload[4..6].try_into().unwrap());
+            let os_id = group as usize * 64 + payload[6] as usize;
+
+            classes.push((os_id as u16, payload[12]));
+        }
+    });
+
+    if walked.is_err() {
+        return;
+    }
+
+    for core in raw.cores.iter_mut() {
+        core.scheduling_class = classes
+            .iter()
+            .filter(|(os_id, _)| core.lp_ids.binary_search(os_id).is_ok())
+            .map(|&(_, class)| class)
+            .max()
+            .unwrap_or(0);
+    }
+}
+
+/// Live parking state of LP `os_id` for [`Lp::is_parked`]: bit 0 (`Parked`)
+/// of the CPU Set `AllFlags` byte.
+pub(crate) fn is_lp_parked(os_id: u16) -> Result<bool> {
+    let mut parked = None;
+
+    // SYSTEM_CPU_SET_INFORMATION.CpuSet: Group(u16) at 4, LpIndex(u8) at 6,
+    // AllFlags(u8) at 11.
+    super::utils::for_each_cpu_set(|payload| {
+        if payload.len() >= 12 {
+            let group = u16::from_le_bytes(payload[4..6].try_into().unwrap());
+
+            if group as usize * 64 + payload[6] as usize == os_id as usize {
+                parked = Some(payload[11] & 0x1 != 0);
+            }
+        }
+    })?;
+
+    parked.ok_or(Error::InvalidCoreId(os_id as usize))
+}
+
+/// The OS LP id of a CPU Set payload that is allocated to some other part of
+/// the system: `Allocated` (bit 1 of `AllFlags`) without
+/// `AllocatedToTargetProcess` (bit 2).
+fn reserved_lp(payload: &[u8]) -> Option<usize> {
+    // SYSTEM_CPU_SET_INFORMATION.CpuSet: Group(u16) at 4, LpIndex(u8) at 6,
+    // AllFlags(u8) at 11.
+    if payload.len() < 12 || payload[11] & 0b110 != 0b010 {
+        return None;
+    }
+    let group = u16::from_le_bytes(payload[4..6].try_into().unwrap());
+
+    Some(group as usize * 64 + payload[6] as usize)
+}
+
+/// Live mask for [`CpuInfo::os_reserved_cores`]; empty when CPU Sets are
+/// unavailable.
+pub(crate) fn os_reserved_lps() -> AffinityMask {
+    let mut mask = AffinityMask::empty();
+    let _ = super::utils::for_each_cpu_set(|payload| {
+        if let Some(os_id) = reserved_lp(payload) {
+            mask.add(os_id);
+        }
+    });
+    mask
+}
+
+/// Runs `primary`, falling back to `fallback` when it fails. Errors only when
+/// both do - reporting the primary failure, the one worth diagnosing. The
+/// sources are parameters so tests can force the primary path to fail.
+fn collect_topology(
+    primary: impl FnOnce() -> Result<RawTopology>,
+    fallback: impl FnOnce() -> Result<RawTopology>,
+) -> Result<RawTopology> {
+    match primary() {
+        Ok(raw) => Ok(raw),
+        Err(primary_err) => fallback().map_err(|_| primary_err),
+    }
 }
 
 fn build_cpu_info(
@@ -275,10 +505,11 @@ fn build_cpu_info(
     vendor: Vendor,
     model_name: String,
     features: CpuFeatures,
+    x86_signature: Option<(u8, u8)>,
 ) -> Result<CpuInfo> {
     if raw.cores.is_empty() {
         return Err(Error::Detection(
-            "GetLogicalProcessorInformationEx reported no processor cores".to_string(),
+            "GetLogicalProcessorInformationEx reported no processor cores".into(),
         ));
     }
 
@@ -315,6 +546,14 @@ fn build_cpu_info(
 
     // --- Phase 2b: LP records (dense cores, sockets by package membership) ---
     let mut lps: Vec<Lp> = Vec::new();
+    // Capacity scales EfficiencyClass linearly onto 1..=1024 - the classes
+    // are ordinal, so this is a coarse weight, but the best one GLPI gives.
+    let max_class = raw
+        .cores
+        .iter()
+        .map(|core| core.efficiency_class)
+        .max()
+        .unwrap_or(0);
 
     for (core_idx, core) in raw.cores.iter().enumerate() {
         let kind = kind_of_class(core.efficiency_class);
@@ -336,11 +575,22 @@ fn build_cpu_info(
                 kind,
                 smt_index: sibling as u8,
                 // GLPI EfficiencyClass is RELATIVE, higher = more performant -
-                // already exactly the ordinal machine-local hint contract.
-                perf_hint: core.efficiency_class as u16,
+                // already the ordinal machine-local hint contract. The CPU Set
+                // SchedulingClass ranks preferred cores WITHIN a class, so it
+                // fills the low byte.
+                perf_hint: (core.efficiency_class as u16) << 8 | core.scheduling_class as u16,
                 // NOTE(windows): GLPI exposes no per-core MIDR part; Windows-on-ARM
                 // identifies cores by EfficiencyClass, not microarch id. Leave 0.
                 cpu_part: 0,
+                // NOTE(windows): GLPI numbers no cores; the only native id is
+                // the per-thread x86 APIC id, readable only from ON that LP.
+                native_core_id: None,
+                capacity: Some(
+                    (u32::from(Lp::MAX_CAPACITY) * (u32::from(core.efficiency_class) + 1)
+                        / (u32::from(max_class) + 1)) as u16,
+                ),
+                freq_min_mhz: None,
+                freq_max_mhz: None,
             });
         }
     }
@@ -485,6 +735,9 @@ fn build_cpu_info(
                 size_bytes: cache.size_bytes,
                 line_bytes: cache.line_bytes,
                 shared_by: cache.lp_ids.len() as u16,
+                write_policy: None,
+                inclusive: None,
+                estimated_latency_cycles: None,
             };
 
             match (cache.level, cache.cache_type) {
@@ -535,11 +788,16 @@ fn build_cpu_info(
         }
     }
 
+    // NOTE(windows): GLPI lists only enabled LPs; a firmware-disabled SMT is
+    // indistinguishable from none.
+    let smt_supported = lps.iter().any(|lp| lp.smt_index > 0);
+
     let mut info = CpuInfo {
         lps,
         core_count,
         socket_count,
         numa_node_count,
+        smt_supported,
         kind_core_counts,
         l3_domains,
         l2_domains,
@@ -549,8 +807,23 @@ fn build_cpu_info(
         vendor,
         model_name,
         features,
+        // Stamped by `detect_cpu_info` - GLPI carries no clock or cpuid data.
+        base_frequency_mhz: None,
+        max_frequency_mhz: None,
+        clflush_line_size: None,
+        has_invariant_tsc: false,
+        tsc_frequency_hz: None,
+        microcode: None,
+        // NOTE(windows): no documented user-mode API reports the package
+        // power limit (CallNtPowerInformation carries clocks only).
+        tdp_watts: None,
+        x86_signature,
+        avx512_downclock_risk: None,
+        detection_warnings: raw.warnings,
+        logical_processor_ids: Default::default(),
+        schema_version: CpuInfo::SCHEMA_VERSION,
     };
-    info.normalize_domain_order();
+    info.finalize(&crate::DetectOptions::FULL);
     Ok(info)
 }
 
@@ -630,6 +903,7 @@ mod tests {
     fn core(efficiency_class: u8, lp_ids: &[u16]) -> RawCore {
         RawCore {
             efficiency_class,
+            scheduling_class: 0,
             lp_ids: lp_ids.to_vec(),
         }
     }
@@ -655,10 +929,87 @@ mod tests {
             Vendor::Unknown,
             "test cpu".to_string(),
             CpuFeatures::default(),
+            None,
         )
         .unwrap()
     }
 
+    // A sandbox that refuses GLPI still gets a usable, invariant-respecting
+    // CpuInfo from the degraded source; only a double failure is an error.
+    #[test]
+    fn failed_glpi_falls_back_to_degraded_topology() {
+        let degraded = || {
+            Ok(RawTopology {
+                cores: vec![core(0, &[0]), core(0, &[1]), core(0, &[2]), core(0, &[3])],
+                packages: vec![vec![0, 1, 2, 3]],
+                ..RawTopology::default()
+            })
+        };
+        let glpi_denied = || Err(Error::SystemCall("GLPI denied".into()));
+
+        let info = info(collect_topology(glpi_denied, degraded).unwrap());
+        assert_eq!(info.num_logical_cores(), 4);
+        assert_eq!(info.num_performance_cores(), 4);
+        assert_eq!(info.socket_count, 1);
+        assert!(!info.is_hybrid());
+        assert!(info.l3_domains.is_empty());
+        assert!(!info.has_complete_topology());
+
+        let err = collect_topology(glpi_denied, || {
+            Err(Error::SystemCall("GetSystemInfo denied".into()))
+        })
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("GLPI denied"));
+    }
+
+    // One group: the real mask, so an offline LP leaves a gap instead of
+    // shifting every later id. Several groups: the dense assumption, flagged.
+    #[test]
+    fn fallback_lp_ids_keep_gaps_in_the_active_mask() {
+        assert_eq!(fallback_lp_ids(&[3], 0b1011), (vec![0, 1, 3], false));
+        assert_eq!(fallback_lp_ids(&[], 0b11), (vec![0, 1], false));
+        assert_eq!(fallback_lp_ids(&[2, 1], 0b11), (vec![0, 1, 64], true));
+        // No mask reported: the count, densely.
+        assert_eq!(fallback_lp_ids(&[2], 0), (vec![0, 1], true));
+    }
+
+    // The live fallback must agree with the machine's LP count.
+    #[test]
+    fn live_fallback_topology_matches_glpi_lp_count() {
+        let fallback = info(collect_fallback_topology().unwrap());
+        let glpi = info(collect_glpi_topology().unwrap());
+        assert_eq!(fallback.num_logical_cores(), glpi.num_logical_cores());
+    }
+
+    #[test]
+    fn scheduling_class_ranks_favored_cores_within_their_kind() {
+        let mut favored = core(1, &[2, 3]);
+        favored.scheduling_class = 3;
+        let raw = RawTopology {
+            cores: vec![core(0, &[0]), core(1, &[1]), favored, core(1, &[4])],
+            packages: vec![vec![0, 1, 2, 3, 4]],
+            ..RawTopology::default()
+        };
+        let info = info(raw);
+
+        // The class byte still dominates: an E-core never outranks a P-core.
+        assert!(info.lps[0].perf_hint < info.lps[1].perf_hint);
+        let ids: Vec<u16> = info.favored_cores().iter().map(|lp| lp.os_id).collect();
+        assert_eq!(ids, [2]);
+    }
+
+    // Parking is dynamic; the point is that every detected LP has a CPU Set
+    // record to read it from.
+    #[test]
+    fn live_parking_state_reads_for_every_lp() {
+        let info = CpuInfo::detect().unwrap();
+
+        for lp in &info.lps {
+            assert!(lp.is_parked().is_ok(), "LP {} parking state", lp.os_id);
+        }
+    }
+
     #[test]
     fn two_efficiency_classes_are_efficiency_and_performance() {
         let raw = RawTopology {
@@ -709,6 +1060,44 @@ mod tests {
         assert_eq!(info.lps[1].l3_domain, 0);
     }
 
+    // The signature goes in before finalize, so the per-kind caches get
+    // their latency estimates on this path too.
+    #[test]
+    fn cache_latencies_are_estimated_from_the_signature() {
+        let raw = || RawTopology {
+            cores: vec![core(1, &[0, 1]), core(0, &[2])],
+            packages: vec![vec![0, 1, 2]],
+            caches: vec![
+                cache(CacheLevel::L1, CacheType::Data, 48 << 10, &[0, 1]),
+                cache(CacheLevel::L2, CacheType::Unified, 2 << 20, &[0, 1]),
+                cache(CacheLevel::L1, CacheType::Data, 32 << 10, &[2]),
+                cache(CacheLevel::L2, CacheType::Unified, 4 << 20, &[2]),
+            ],
+            ..RawTopology::default()
+        };
+        let build = |signature| {
+            build_cpu_info(
+                raw(),
+                Vendor::Intel,
+                "test cpu".to_string(),
+                CpuFeatures::default(),
+                signature,
+            )
+            .unwrap()
+        };
+        let (p, e) = (CoreKind::Performance.index(), CoreKind::Efficiency.index());
+
+        // Raptor Lake: Raptor Cove P-cores, Gracemont E-cores.
+        let info = build(Some((6, 0xB7)));
+        assert_eq!(info.l1d[p].estimated_latency_cycles, Some(5));
+        assert_eq!(info.l2[p].estimated_latency_cycles, Some(16));
+        assert_eq!(info.l1d[e].estimated_latency_cycles, Some(3));
+        assert_eq!(info.l2[e].estimated_latency_cycles, Some(17));
+
+        let unknown = build(None);
+        assert_eq!(unknown.l1d[p].estimated_latency_cycles, None);
+    }
+
     #[test]
     fn sparse_numa_node_ids_count_distinct_nodes() {
         let raw = RawTopology {
@@ -723,4 +1112,34 @@ mod tests {
         assert_eq!(info.lps[0].numa_node, 0);
         assert_eq!(info.lps[1].numa_node, 2);
     }
+
+    #[test]
+    fn reserved_cpu_sets_are_allocated_elsewhere() {
+        // A CpuSet payload (the bytes after Size/Type): Id, Group, LpIndex,
+        // CoreIndex, LastLevelCacheIndex, NumaNodeIndex, EfficiencyClass,
+        // AllFlags, SchedulingClass, AllocationTag.
+        let payload = |group: u16, lp_index: u8, all_flags: u8| {
+            let mut bytes = vec![0u8; 24];
+            bytes[0..4].copy_from_slice(&0x100u32.to_le_bytes());
+            bytes[4..6].copy_from_slice(&group.to_le_bytes());
+            bytes[6] = lp_index;
+            bytes[11] = all_flags;
+            bytes
+        };
+
+        assert_eq!(reserved_lp(&payload(1, 3, 0b010)), Some(67));
+        // Parked or RealTime alone is not a reservation.
+        assert_eq!(reserved_lp(&payload(0, 3, 0b1001)), None);
+        // Allocated to this process: ours to use.
+        assert_eq!(reserved_lp(&payload(0, 3, 0b110)), None);
+        assert_eq!(reserved_lp(&payload(0, 3, 0b010)[..11]), None);
+
+        let mut mask = AffinityMask::empty();
+        for set in [payload(0, 0, 0), payload(0, 1, 0b010), payload(0, 2, 0b001)] {
+            if let Some(os_id) = reserved_lp(&set) {
+                mask.add(os_id);
+            }
+        }
+        assert_eq!(mask.iter().collect::<Vec<_>>(), [1]);
+    }
 }
@@ -0,0 +1,24 @@
+//! AC / battery state from `GetSystemPowerStatus`.
+
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+/// `BatteryFlag` bit: the system has no battery.
+const BATTERY_FLAG_NO_BATTERY: u8 = 128;
+
+/// `Some(true)` when the AC line is offline, `Some(false)` when it is online
+/// or there is no battery, `None` when the call fails or the line status is
+/// unknown (255).
+pub(crate) fn on_battery() -> Option<bool> {
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SAFETY: `status` is a valid, writable SYSTEM_POWER_STATUS.
+    unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+
+    if status.BatteryFlag != 255 && status.BatteryFlag & BATTERY_FLAG_NO_BATTERY != 0 {
+        return Some(false);
+    }
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
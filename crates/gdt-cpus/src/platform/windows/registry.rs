@@ -10,8 +10,8 @@
 
 use windows::Win32::Foundation::ERROR_SUCCESS;
 use windows::Win32::System::Registry::{
-    HKEY, HKEY_LOCAL_MACHINE, KEY_READ, REG_SZ, REG_VALUE_TYPE, RegCloseKey, RegOpenKeyExW,
-    RegQueryValueExW,
+    HKEY, HKEY_LOCAL_MACHINE, KEY_READ, REG_BINARY, REG_SZ, REG_VALUE_TYPE, RegCloseKey,
+    RegOpenKeyExW, RegQueryValueExW,
 };
 use windows::core::{Error as WinError, HRESULT, PCWSTR, Result as WinResult, w};
 
@@ -158,6 +158,42 @@ fn read_registry_string_value(hkey: HKEY, value_name: &str) -> WinResult<String>
     })
 }
 
+/// Opens `HKLM\\HARDWARE\\DESCRIPTION\\System\\CentralProcessor\\0` for reading.
+///
+/// # Errors
+///
+/// `Error::Detection` if the key cannot be opened.
+fn open_central_processor_key() -> Result<RegistryKeyGuard> {
+    let pcwstr_subkey: PCWSTR = w!(r"HARDWARE\DESCRIPTION\System\CentralProcessor\0");
+
+    let mut hkey_opened = HKEY::default();
+
+    // SAFETY: Calling Windows API to open a registry key.
+    // `pcwstr_subkey` is a valid pointer to a constant string.
+    let win_err_code_open = unsafe {
+        RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            pcwstr_subkey,
+            Some(0),  // ulOptions, must be 0
+            KEY_READ, // u32 value of the KEY_READ flag
+            &mut hkey_opened,
+        )
+    };
+
+    if win_err_code_open != ERROR_SUCCESS {
+        return Err(Error::Detection(
+            format!(
+                "Failed to open registry key '{}': {}",
+                r"HARDWARE\DESCRIPTION\System\CentralProcessor\0",
+                WinError::from_hresult(HRESULT::from_win32(win_err_code_open.0))
+            )
+            .into(),
+        ));
+    }
+
+    Ok(RegistryKeyGuard::new(hkey_opened))
+}
+
 /// Attempts to detect CPU vendor and model name by querying the Windows Registry.
 ///
 /// This function serves as a fallback mechanism. It queries the registry key
@@ -186,33 +222,8 @@ fn read_registry_string_value(hkey: HKEY, value_name: &str) -> WinResult<String>
 /// determine the vendor from "Identifier" or "VendorIdentifier" if `*vendor`
 /// is still `Vendor::Unknown`.
 pub(crate) fn detect_via_registry(vendor: &mut Vendor, model_name: &mut String) -> Result<()> {
-    // crate::Result
-    let pcwstr_subkey: PCWSTR = w!(r"HARDWARE\DESCRIPTION\System\CentralProcessor\0");
-
-    let mut hkey_opened = HKEY::default();
-
-    // SAFETY: Calling Windows API to open a registry key.
-    // `pcwstr_subkey` is a valid pointer to a constant string.
-    let win_err_code_open = unsafe {
-        RegOpenKeyExW(
-            HKEY_LOCAL_MACHINE,
-            pcwstr_subkey,
-            Some(0),  // ulOptions, must be 0
-            KEY_READ, // u32 value of the KEY_READ flag
-            &mut hkey_opened,
-        )
-    };
-
-    if win_err_code_open != ERROR_SUCCESS {
-        return Err(Error::Detection(format!(
-            "Failed to open registry key '{}': {}",
-            r"HARDWARE\DESCRIPTION\System\CentralProcessor\0",
-            WinError::from_hresult(HRESULT::from_win32(win_err_code_open.0))
-        )));
-    }
-
     // Create an RAII guard for the opened key to ensure it's closed.
-    let hkey_guard = RegistryKeyGuard::new(hkey_opened);
+    let hkey_guard = open_central_processor_key()?;
     let hkey = hkey_guard.0; // Use the HKEY from the guard for subsequent operations
 
     // 1) Read ProcessorNameString. For a fallback mechanism, failure to read a
@@ -261,3 +272,101 @@ pub(crate) fn detect_via_registry(vendor: &mut Vendor, model_name: &mut String)
     // when `hkey_guard` goes out of scope.
     Ok(())
 }
+
+/// Reads a `REG_BINARY` value's bytes.
+fn read_registry_binary_value(hkey: HKEY, value_name: &str) -> WinResult<Vec<u8>> {
+    let wide_value_name_vec = to_wide_null_vec(value_name);
+    let pcwstr_value_name = PCWSTR(wide_value_name_vec.as_ptr());
+
+    let mut data_type: REG_VALUE_TYPE = REG_VALUE_TYPE(0);
+    let mut size: u32 = 0;
+
+    // SAFETY: size query - no data buffer; `pcwstr_value_name` outlives the call.
+    let win_err_code = unsafe {
+        RegQueryValueExW(
+            hkey,
+            pcwstr_value_name,
+            None,
+            Some(&mut data_type),
+            None,
+            Some(&mut size),
+        )
+    };
+
+    if win_err_code != ERROR_SUCCESS {
+        return Err(WinError::from_hresult(HRESULT::from_win32(win_err_code.0)));
+    }
+
+    if data_type != REG_BINARY {
+        return Err(WinError::new(
+            windows::Win32::Foundation::E_UNEXPECTED,
+            "Registry value is not of type REG_BINARY",
+        ));
+    }
+
+    let mut buffer = vec![0u8; size as usize];
+
+    // SAFETY: `buffer` holds exactly `size` bytes, the size the query reported.
+    let win_err_code = unsafe {
+        RegQueryValueExW(
+            hkey,
+            pcwstr_value_name,
+            None,
+            None,
+            Some(buffer.as_mut_ptr()),
+            Some(&mut size),
+        )
+    };
+
+    if win_err_code != ERROR_SUCCESS {
+        return Err(WinError::from_hresult(HRESULT::from_win32(win_err_code.0)));
+    }
+
+    buffer.truncate(size as usize);
+    Ok(buffer)
+}
+
+/// Reads the loaded microcode revision from the `Update Revision` value of
+/// `CentralProcessor\\0`. `None` if the key or value is missing (Windows on
+/// ARM, some VMs) or reports revision 0.
+pub(crate) fn detect_microcode_via_registry() -> Option<String> {
+    let hkey_guard = open_central_processor_key().ok()?;
+
+    read_registry_binary_value(hkey_guard.0, "Update Revision")
+        .ok()
+        .and_then(|bytes| format_update_revision(&bytes))
+}
+
+/// Formats the 8-byte `Update Revision` blob like Linux's `microcode` field.
+/// Intel keeps the revision in the HIGH dword (the `IA32_BIOS_SIGN_ID` MSR
+/// layout), AMD in the low one; whichever is non-zero wins.
+fn format_update_revision(bytes: &[u8]) -> Option<String> {
+    let raw = u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?);
+    let revision = match raw >> 32 {
+        0 => raw & 0xFFFF_FFFF,
+        high => high,
+    };
+
+    (revision != 0).then(|| format!("0x{:x}", revision))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_revision_takes_the_non_zero_dword() {
+        // Intel: revision 0xf4 in the high dword.
+        assert_eq!(
+            format_update_revision(&[0, 0, 0, 0, 0xf4, 0, 0, 0]).as_deref(),
+            Some("0xf4")
+        );
+        // AMD: revision 0x0a201016 in the low dword.
+        assert_eq!(
+            format_update_revision(&[0x16, 0x10, 0x20, 0x0a, 0, 0, 0, 0]).as_deref(),
+            Some("0xa201016")
+        );
+        assert_eq!(format_update_revision(&[0; 8]), None);
+        assert_eq!(format_update_revision(&[1, 2]), None);
+    }
+}
@@ -5,6 +5,11 @@
 
 use std::{ffi::OsStr, os::windows::ffi::OsStrExt};
 
+use windows::Win32::System::SystemInformation::GetSystemCpuSetInformation;
+use windows::Win32::System::Threading::GetCurrentProcess;
+
+use crate::{Error, Result};
+
 /// Converts a Rust string slice (`&str`) into a null-terminated UTF-16 encoded
 /// vector of `u16` values, suitable for use with Windows API functions that
 /// expect wide strings (PCWSTR).
@@ -53,3 +58,66 @@ pub(crate) fn to_wide_null_vec(s: &str) -> Vec<u16> {
         .chain(std::iter::once(0))
         .collect()
 }
+
+/// Walks the `SYSTEM_CPU_SET_INFORMATION` records visible to this process,
+/// calling `f` with each CpuSet payload (the bytes after the Size/Type
+/// header). Shared by soft affinity and the preferred-core read in detection.
+pub(crate) fn for_each_cpu_set(mut f: impl FnMut(&[u8])) -> Result<()> {
+    let mut needed: u32 = 0;
+
+    unsafe {
+        let _ = GetSystemCpuSetInformation(None, 0, &mut needed, Some(GetCurrentProcess()), None);
+    }
+
+    if needed == 0 {
+        return Err(Error::Unsupported(
+            "CPU Sets are not available on this system".into(),
+        ));
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed as usize];
+
+    let ok = unsafe {
+        GetSystemCpuSetInformation(
+            Some(buffer.as_mut_ptr() as *mut _),
+            needed,
+            &mut needed,
+            Some(GetCurrentProcess()),
+            None,
+        )
+    };
+
+    if !ok.as_bool() {
+        let err = std::io::Error::last_os_error();
+        return Err(Error::SystemCall(
+            format!("GetSystemCpuSetInformation failed: {}", err).into(),
+        ));
+    }
+
+    let mut offset: usize = 0;
+
+    while offset + 8 <= needed as usize {
+        // Size (u32) + Type (u32) prefix; CpuSet payload follows.
+        let size = u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap()) as usize;
+
+        // Reject malformed records: `size` in 1..8 would make the payload slice
+        // start (offset+8) past its end (offset+size) and panic; `size == 0`
+        // would loop forever. Both are caught by requiring a full 8-byte header.
+        if size < 8 || offset + size > needed as usize {
+            break;
+        }
+
+        // `Type` is the second u32. This is an extensible tagged stream;
+        // CpuSetInformation == 0. Skip any other record type (do not parse its
+        // bytes as a CpuSet) but keep walking.
+        let rec_type = u32::from_le_bytes(buffer[offset + 4..offset + 8].try_into().unwrap());
+
+        if rec_type == 0 {
+            f(&buffer[offset + 8..offset + size]);
+        }
+
+        offset += size;
+    }
+
+    Ok(())
+}
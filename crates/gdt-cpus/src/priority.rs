@@ -343,6 +343,13 @@ pub enum MechanismPolicy {
     Qos,
     /// Windows `SetThreadPriority` -- `value` is the `THREAD_PRIORITY_*` constant (-15..=15).
     WinPriority,
+    /// Linux `SCHED_DEADLINE` -- `value` is unused (0); the runtime / deadline /
+    /// period are the ones passed to [`crate::set_thread_deadline`].
+    SchedDeadline,
+    /// macOS Mach `THREAD_TIME_CONSTRAINT_POLICY` (the Core Audio I/O thread
+    /// policy) -- `value` is unused (0); the period / computation are the ones
+    /// passed to [`crate::configure_audio_thread`].
+    TimeConstraint,
 }
 
 impl std::fmt::Display for MechanismPolicy {
@@ -353,6 +360,8 @@ impl std::fmt::Display for MechanismPolicy {
             MechanismPolicy::SchedOther => write!(f, "SCHED_OTHER"),
             MechanismPolicy::Qos => write!(f, "QoS"),
             MechanismPolicy::WinPriority => write!(f, "THREAD_PRIORITY"),
+            MechanismPolicy::SchedDeadline => write!(f, "SCHED_DEADLINE"),
+            MechanismPolicy::TimeConstraint => write!(f, "THREAD_TIME_CONSTRAINT_POLICY"),
         }
     }
 }
@@ -425,6 +434,9 @@ impl std::fmt::Display for Mechanism {
                 Some(class) => write!(f, "QoS {class}"),
                 None => write!(f, "QoS {}", self.value),
             },
+            MechanismPolicy::SchedDeadline | MechanismPolicy::TimeConstraint => {
+                write!(f, "{}", self.policy)
+            }
             other => write!(f, "{} {}", other, self.value),
         }
     }
@@ -0,0 +1,268 @@
+//! Scoped per-thread placement: priority, hard affinity, MMCSS and power
+//! throttling applied as one [`ThreadProfile`] and undone together when the
+//! returned [`ProfileGuard`] drops.
+//!
+//! The recipe for a render, audio or worker thread is the same handful of
+//! calls on every platform; bundling them means the undo happens in one
+//! place, in reverse order, even when a later step fails.
+
+use std::marker::PhantomData;
+
+use crate::{AffinityMask, AppliedPriority, MmcssHandle, Result, ThreadPriority};
+
+#[cfg(target_os = "linux")]
+use crate::platform::linux::affinity::{
+    SavedPriority, restore_thread_priority, save_thread_priority,
+};
+#[cfg(target_os = "macos")]
+use crate::platform::macos::affinity::{
+    SavedPriority, restore_thread_priority, save_thread_priority,
+};
+#[cfg(target_os = "windows")]
+use crate::platform::windows::affinity::{
+    SavedPriority, restore_thread_priority, save_thread_priority,
+};
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+#[derive(Debug)]
+struct SavedPriority;
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn save_thread_priority() -> Result<SavedPriority> {
+    Err(crate::Error::Unsupported(
+        "Thread priority is not supported on this platform.".into(),
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn restore_thread_priority(_saved: &SavedPriority) -> Result<()> {
+    Ok(())
+}
+
+/// Everything [`apply_thread_profile`] sets on the calling thread.
+///
+/// `None` fields are left untouched. `mmcss_task` and `power_throttling` are
+/// Windows concepts: applied best-effort there (a disabled MMCSS service or a
+/// pre-1709 system logs and carries on) and ignored elsewhere.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadProfile<'a> {
+    /// Priority level, set through [`crate::set_thread_priority`].
+    pub priority: ThreadPriority,
+    /// Hard affinity, set through [`crate::set_thread_affinity`].
+    pub affinity: Option<AffinityMask>,
+    /// MMCSS task to register under (`"Games"`, `"Audio"`, `"Pro Audio"`),
+    /// see [`crate::register_mmcss_task`].
+    pub mmcss_task: Option<&'a str>,
+    /// `true` opts the thread into EcoQoS execution-speed throttling,
+    /// `false` opts it out (what latency-sensitive threads want).
+    pub power_throttling: Option<bool>,
+}
+
+impl ThreadProfile<'_> {
+    /// A profile that only sets `priority`.
+    pub fn new(priority: ThreadPriority) -> Self {
+        Self {
+            priority,
+            affinity: None,
+            mmcss_task: None,
+            power_throttling: None,
+        }
+    }
+}
+
+/// The thread state [`apply_thread_profile`] replaced, put back on drop.
+///
+/// Filled in step by step, so a failed apply restores exactly what it had
+/// already changed.
+struct SavedThreadState {
+    priority: Option<SavedPriority>,
+    affinity: Option<AffinityMask>,
+    mmcss: Option<MmcssHandle>,
+    #[cfg(target_os = "windows")]
+    power_throttling: Option<windows::Win32::System::Threading::THREAD_POWER_THROTTLING_STATE>,
+    // Thread state belongs to the thread that changed it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for SavedThreadState {
+    fn drop(&mut self) {
+        if let Some(saved) = self.priority.take()
+            && let Err(_e) = restore_thread_priority(&saved)
+        {
+            #[cfg(feature = "log")]
+            log::warn!("Restoring thread priority failed: {}", _e);
+        }
+
+        #[cfg(target_os = "windows")]
+        if let Some(saved) = self.power_throttling.take()
+            && let Err(_e) = crate::platform::windows::affinity::set_power_throttling(&saved)
+        {
+            #[cfg(feature = "log")]
+            log::warn!("Restoring power throttling failed: {}", _e);
+        }
+
+        // Reverts the registration (MmcssHandle's own Drop).
+        self.mmcss.take();
+
+        if let Some(saved) = self.affinity.take()
+            && let Err(_e) = crate::set_thread_affinity(&saved)
+        {
+            #[cfg(feature = "log")]
+            log::warn!("Restoring thread affinity failed: {}", _e);
+        }
+    }
+}
+
+/// Restores the calling thread's previous placement when dropped - returned
+/// by [`apply_thread_profile`].
+///
+/// Not `Send`: it must drop on the thread the profile was applied to.
+/// Restore failures are logged (feature `log`), never panicked on.
+#[must_use = "dropping the guard immediately undoes the profile"]
+pub struct ProfileGuard {
+    applied: AppliedPriority,
+    _saved: SavedThreadState,
+}
+
+impl ProfileGuard {
+    /// What [`crate::set_thread_priority`] actually granted for the
+    /// profile's priority.
+    pub fn applied_priority(&self) -> &AppliedPriority {
+        &self.applied
+    }
+}
+
+impl std::fmt::Debug for ProfileGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfileGuard")
+            .field("applied", &self.applied)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Applies `profile` to the calling thread: affinity, then MMCSS and power
+/// throttling (Windows), then priority. The original priority is saved
+/// before any of them runs. Dropping the returned guard restores each in
+/// reverse order.
+///
+/// # Errors
+///
+/// Whatever [`crate::current_affinity`], [`crate::set_thread_affinity`] or
+/// [`crate::set_thread_priority`] return - e.g. [`crate::Error::Unsupported`]
+/// for an affinity on macOS. Anything already changed is restored before the
+/// error is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use gdt_cpus::{ThreadPriority, ThreadProfile, apply_thread_profile};
+///
+/// std::thread::spawn(|| {
+///     let _profile = apply_thread_profile(&ThreadProfile {
+///         mmcss_task: Some("Games"),
+///         power_throttling: Some(false),
+///         ..ThreadProfile::new(ThreadPriority::AboveNormal)
+///     })
+///     .unwrap();
+///     // ... render loop; the old placement returns when `_profile` drops ...
+/// });
+/// ```
+pub fn apply_thread_profile(profile: &ThreadProfile<'_>) -> Result<ProfileGuard> {
+    let mut saved = SavedThreadState {
+        priority: None,
+        affinity: None,
+        mmcss: None,
+        #[cfg(target_os = "windows")]
+        power_throttling: None,
+        _not_send: PhantomData,
+    };
+
+    // Snapshot first: MMCSS registration already raises the priority, and
+    // restoring that raised value would leave the thread boosted.
+    saved.priority = Some(save_thread_priority()?);
+
+    if let Some(mask) = &profile.affinity {
+        let previous = crate::current_affinity()?;
+        crate::set_thread_affinity(mask)?;
+        saved.affinity = Some(previous);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use crate::platform::windows::affinity::{
+            power_throttling, power_throttling_state, set_power_throttling,
+        };
+
+        if let Some(task) = profile.mmcss_task {
+            match crate::register_mmcss_task(task) {
+                Ok(handle) => saved.mmcss = Some(handle),
+                Err(_e) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("MMCSS registration failed: {}", _e);
+                }
+            }
+        }
+
+        if let Some(enabled) = profile.power_throttling {
+            match power_throttling().and_then(|previous| {
+                set_power_throttling(&power_throttling_state(enabled)).map(|()| previous)
+            }) {
+                Ok(previous) => saved.power_throttling = Some(previous),
+                Err(_e) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("Setting power throttling failed: {}", _e);
+                }
+            }
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = (profile.mmcss_task, profile.power_throttling);
+
+    let applied = crate::set_thread_priority(profile.priority)?;
+
+    Ok(ProfileGuard {
+        applied,
+        _saved: saved,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dropping_profile_restores_priority_and_affinity() {
+        use crate::platform::linux::affinity::current_nice;
+
+        std::thread::spawn(|| {
+            // Start above nice 0 so the restore has something to undo; raising
+            // nice never needs privileges, and a denied boost leaves it as is.
+            let _ = crate::set_thread_priority(ThreadPriority::BelowNormal).unwrap();
+            let nice = current_nice().unwrap();
+            let affinity = crate::current_affinity().unwrap();
+            let target = affinity.iter().next().unwrap();
+
+            let guard = apply_thread_profile(&ThreadProfile {
+                affinity: Some(AffinityMask::single(target)),
+                mmcss_task: Some("Games"),
+                ..ThreadProfile::new(ThreadPriority::Highest)
+            })
+            .unwrap();
+            assert_eq!(
+                crate::current_affinity().unwrap(),
+                AffinityMask::single(target)
+            );
+            assert_eq!(
+                guard.applied_priority().requested(),
+                ThreadPriority::Highest
+            );
+            drop(guard);
+
+            assert_eq!(current_nice().unwrap(), nice);
+            assert_eq!(crate::current_affinity().unwrap(), affinity);
+        })
+        .join()
+        .unwrap();
+    }
+}
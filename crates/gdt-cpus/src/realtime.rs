@@ -67,7 +67,157 @@ pub fn promote_thread_to_realtime(budget: Duration) -> Result<AppliedPriority> {
     {
         let _ = budget;
         Err(crate::Error::Unsupported(
-            "Real-time promotion is not supported on this platform.".to_string(),
+            "Real-time promotion is not supported on this platform.".into(),
+        ))
+    }
+}
+
+/// Whether this process may put threads on the real-time tier itself - for
+/// asking the user to grant privileges up front instead of discovering a
+/// degraded [`promote_thread_to_realtime`] later.
+///
+/// * **Linux** - `true` when direct `SCHED_RR` is permitted: the thread holds
+///   `CAP_SYS_NICE` (root, `setcap cap_sys_nice`) or `RLIMIT_RTPRIO` is at
+///   least 85. `false` does not rule out promotion - the realtime portal and
+///   rtkit may still grant it, leashed - and cgroup v1 RT throttling with no
+///   runtime budget can refuse even a privileged thread.
+/// * **macOS** - `true`: `SCHED_RR` and Mach time-constraint scheduling need
+///   no privileges.
+/// * **Windows** - `true`: the tier is `THREAD_PRIORITY_TIME_CRITICAL`,
+///   which any thread may set. `SeIncreaseBasePriorityPrivilege` only gates
+///   `REALTIME_PRIORITY_CLASS`, which this crate never uses.
+/// * Other platforms: `false`.
+pub fn can_set_realtime_priority() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::realtime::can_promote_directly()
+    }
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    {
+        true
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        false
+    }
+}
+
+/// Puts the current thread on Linux `SCHED_DEADLINE`: every `period` it is
+/// guaranteed `runtime` of CPU time, finished by `deadline` after the period
+/// starts - the fit for fixed-rate work such as a 120 Hz simulation tick
+/// (`runtime` 2 ms, `deadline` = `period` = 8.33 ms).
+///
+/// Requires `0 < runtime <= deadline <= period` and `runtime` of at least
+/// 1024 ns (the kernel's resolution floor), else
+/// [`crate::Error::InvalidParameter`]. The kernel also runs admission control:
+/// a reservation that would overcommit the CPUs' real-time bandwidth is
+/// refused with [`crate::Error::SystemCall`].
+///
+/// # Privileges
+///
+/// `SCHED_DEADLINE` needs `CAP_SYS_NICE` (or root) - unlike `SCHED_RR` there is
+/// no `RLIMIT_RTPRIO` route and neither rtkit nor the realtime portal broker
+/// it. A denied request follows the [`promote_thread_to_realtime`] philosophy:
+/// the thread keeps the timeshare level it had and the returned
+/// [`AppliedPriority`] says so ([`AppliedPriority::degraded`] with
+/// [`crate::FallbackReason::NoBroker`]). Callers that must not run without the
+/// guarantee check `degraded()` and bail.
+///
+/// A deadline thread overrunning its `runtime` is throttled until the next
+/// period (no signal, no kill). It cannot be given a hard affinity narrower
+/// than its root domain - [`crate::set_thread_affinity`] on it fails.
+/// [`demote_thread_from_realtime`] returns it to `SCHED_OTHER`. Other
+/// platforms: [`crate::Error::Unsupported`].
+pub fn set_thread_deadline(
+    runtime: Duration,
+    deadline: Duration,
+    period: Duration,
+) -> Result<AppliedPriority> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::realtime::set_deadline(runtime, deadline, period)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (runtime, deadline, period);
+        Err(crate::Error::Unsupported(
+            "SCHED_DEADLINE is a Linux scheduling class; there is no equivalent here.".into(),
+        ))
+    }
+}
+
+/// Applies each platform's recommended recipe for a real-time AUDIO thread
+/// to the current thread - the incantation audio engineers otherwise look up
+/// per OS.
+///
+/// `period` is the buffer duration the thread services (256 frames at
+/// 48 kHz = 5.33 ms) and `budget` the CPU time it needs per buffer; both are
+/// promises in the [`promote_thread_to_realtime`] sense. Requires
+/// `0 < budget <= period`, else [`crate::Error::InvalidParameter`].
+///
+/// # Platform behavior
+///
+/// * **Linux** - [`promote_thread_to_realtime`] with `budget`: direct
+///   `SCHED_RR`, then the realtime portal, then rtkit, with the same
+///   `RLIMIT_RTTIME` consent. `SCHED_RR` rather than `SCHED_FIFO`: the same
+///   priority band, but two audio threads at one priority take turns instead
+///   of one starving the other. A denied promotion degrades (see
+///   [`AppliedPriority::degraded`]) instead of failing.
+/// * **macOS** - Mach `THREAD_TIME_CONSTRAINT_POLICY` (period = constraint =
+///   `period`, computation = `budget`, preemptible), the policy Core Audio's
+///   own I/O threads use; no privileges needed. The thread stays in the QoS
+///   system, unlike the `SCHED_RR` path of `TimeCritical`.
+/// * **Windows** - registers the thread with MMCSS as `"Pro Audio"` for the
+///   rest of its life (use [`crate::register_mmcss_task`] for a scoped
+///   registration), opts it out of power throttling (EcoQoS), then
+///   `THREAD_PRIORITY_TIME_CRITICAL`.
+///   The first two are best effort (logged at `warn` with the `log`
+///   feature); `period` and `budget` are only validated.
+///
+/// [`demote_thread_from_realtime`] undoes the priority part.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// std::thread::spawn(|| {
+///     let period = Duration::from_micros(5333); // 256 frames @ 48 kHz
+///     match gdt_cpus::configure_audio_thread(period, Duration::from_millis(1)) {
+///         Ok(applied) if applied.degraded() => eprintln!("audio thread not real-time: {applied}"),
+///         Ok(_) => {}
+///         Err(e) => eprintln!("audio thread setup failed: {e}"),
+///     }
+///     // ... render loop ...
+/// });
+/// ```
+pub fn configure_audio_thread(period: Duration, budget: Duration) -> Result<AppliedPriority> {
+    if budget.is_zero() || budget > period {
+        return Err(crate::Error::InvalidParameter(
+            format!(
+                "audio thread budget {:?} must be non-zero and fit the period {:?}",
+                budget, period
+            )
+            .into(),
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::realtime::promote(budget)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::affinity::set_time_constraint(period, budget)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::configure_audio_thread()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        Err(crate::Error::Unsupported(
+            "Real-time audio threads are not supported on this platform.".into(),
         ))
     }
 }
@@ -100,7 +250,90 @@ pub fn demote_thread_from_realtime() -> Result<()> {
     #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
     {
         Err(crate::Error::Unsupported(
-            "Real-time promotion is not supported on this platform.".to_string(),
+            "Real-time promotion is not supported on this platform.".into(),
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_thread_rejects_impossible_budget() {
+        let ms = Duration::from_millis;
+
+        assert!(matches!(
+            configure_audio_thread(ms(5), Duration::ZERO),
+            Err(crate::Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            configure_audio_thread(ms(5), ms(6)),
+            Err(crate::Error::InvalidParameter(_))
+        ));
+    }
+
+    // Promotion lowers the process-wide hard RLIMIT_RTTIME for good, so the
+    // real call runs in a child process. Unprivileged runs must degrade, not
+    // error.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn audio_thread_is_realtime_or_degrades() {
+        const CHILD: &str = "GDT_CPUS_AUDIO_THREAD_CHILD";
+
+        if std::env::var_os(CHILD).is_none() {
+            let child = std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "--exact",
+                    "realtime::tests::audio_thread_is_realtime_or_degrades",
+                    "--test-threads=1",
+                ])
+                .env(CHILD, "1")
+                .output()
+                .unwrap();
+            assert!(
+                child.status.success(),
+                "{}",
+                String::from_utf8_lossy(&child.stdout)
+            );
+            return;
+        }
+
+        std::thread::spawn(|| {
+            let applied =
+                configure_audio_thread(Duration::from_micros(5333), Duration::from_millis(1))
+                    .unwrap();
+
+            assert!(
+                applied.degraded() || applied.mechanism().policy == crate::MechanismPolicy::SchedRr,
+                "{applied}"
+            );
+            demote_thread_from_realtime().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[test]
+    fn realtime_is_grantable_without_privileges() {
+        std::thread::spawn(|| {
+            assert!(can_set_realtime_priority());
+            promote_thread_to_realtime(Duration::from_millis(1)).unwrap();
+            demote_thread_from_realtime().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[test]
+    fn audio_thread_is_configured() {
+        std::thread::spawn(|| {
+            configure_audio_thread(Duration::from_micros(5333), Duration::from_millis(1)).unwrap();
+            demote_thread_from_realtime().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+}
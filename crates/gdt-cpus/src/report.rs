@@ -0,0 +1,266 @@
+//! One-call system description for bug reports.
+//!
+//! [`system_report`] bundles what a support thread usually asks for in three
+//! follow-ups - the detected topology, what the calling thread may run on and
+//! at which priority, how much of the priority ladder works, any container
+//! CPU limit, and what detection had to guess - into one value whose
+//! `Display` is meant to be pasted into an issue as-is.
+
+use crate::{AffinityMask, CoreKind, CpuInfo, PriorityCaps, Result, ThreadPriority};
+
+/// Snapshot of the CPU and the calling thread's scheduling context.
+///
+/// Built by [`system_report`]. The `Display` impl renders a short plain-text
+/// block; with the `serde` feature the whole value serializes for tooling.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemReport {
+    /// Version of this crate that produced the report.
+    pub crate_version: String,
+    /// Target OS (`std::env::consts::OS`).
+    pub os: String,
+    /// Target architecture (`std::env::consts::ARCH`).
+    pub arch: String,
+    /// The detected topology, including its
+    /// [`detection_warnings`](CpuInfo::detection_warnings).
+    pub cpu: CpuInfo,
+    /// The calling thread's hard affinity, or `None` where it cannot be read
+    /// (macOS).
+    pub affinity: Option<AffinityMask>,
+    /// Detected logical processors the calling thread may run on - all of
+    /// them when [`affinity`](Self::affinity) is `None`. Below
+    /// `cpu.num_logical_cores()` under `taskset`, cpusets and container CPU
+    /// pinning.
+    pub usable_logical_cores: usize,
+    /// What each [`ThreadPriority`] level resolves to for this process.
+    pub priority: PriorityCaps,
+    /// The calling thread's current priority in the platform's own terms -
+    /// Linux policy + `nice` or RT priority, Windows `GetThreadPriority`
+    /// level, macOS QoS class + POSIX policy. `None` where it cannot be read.
+    /// Free text for humans; the wording is not a stable API.
+    pub thread_priority: Option<String>,
+    /// [`crate::can_set_realtime_priority`] for this process.
+    pub realtime_grantable: bool,
+    /// CPU bandwidth limit in CPUs from the Linux cgroup v2 `cpu.max` of the
+    /// process's cgroup and its ancestors (`1.5` = one and a half CPUs' worth
+    /// of time), the tightest one applying. `None` when unlimited, unreadable
+    /// (cgroup v1) or not Linux.
+    pub cgroup_cpu_limit: Option<f64>,
+}
+
+/// Gathers a [`SystemReport`]: a fresh [`CpuInfo::detect()`], the calling
+/// thread's affinity and priority, [`crate::priority_capabilities`],
+/// [`crate::can_set_realtime_priority`] and the cgroup CPU limit.
+///
+/// Touches no thread state. Failing to read the affinity, priority or cgroup
+/// limit is not an error - the report just carries `None`.
+///
+/// # Errors
+///
+/// Whatever [`CpuInfo::detect()`] returns.
+///
+/// # Example
+///
+/// ```
+/// if let Ok(report) = gdt_cpus::system_report() {
+///     eprintln!("{report}");
+/// }
+/// ```
+pub fn system_report() -> Result<SystemReport> {
+    let cpu = CpuInfo::detect()?;
+    let affinity = crate::current_affinity().ok();
+
+    let usable_logical_cores = match &affinity {
+        Some(mask) => cpu
+            .lps
+            .iter()
+            .filter(|lp| mask.contains(lp.os_id as usize))
+            .count(),
+        None => cpu.num_logical_cores(),
+    };
+
+    Ok(SystemReport {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu,
+        affinity,
+        usable_logical_cores,
+        priority: crate::priority_capabilities(),
+        thread_priority: current_thread_priority(),
+        realtime_grantable: crate::can_set_realtime_priority(),
+        cgroup_cpu_limit: cgroup_cpu_limit(),
+    })
+}
+
+fn current_thread_priority() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::affinity::describe_thread_priority().ok()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        crate::platform::macos::affinity::describe_thread_priority().ok()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::affinity::describe_thread_priority().ok()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+fn cgroup_cpu_limit() -> Option<f64> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::linux::cgroup::cpu_limit()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+impl std::fmt::Display for SystemReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cpu = &self.cpu;
+        let kinds = |kind: CoreKind| cpu.kind_core_counts[kind.index()];
+
+        writeln!(
+            f,
+            "gdt-cpus {} ({}/{})",
+            self.crate_version, self.os, self.arch
+        )?;
+        writeln!(
+            f,
+            "CPU: {} ({}), microcode {}",
+            cpu.model_name,
+            cpu.vendor,
+            cpu.microcode.as_deref().unwrap_or("?")
+        )?;
+        writeln!(
+            f,
+            "Topology: {} socket(s), {} core(s), {} LP(s), {} NUMA node(s); \
+             P {} / E {} / LP-E {}; SMT {}",
+            cpu.socket_count,
+            cpu.core_count,
+            cpu.num_logical_cores(),
+            cpu.numa_node_count,
+            kinds(CoreKind::Performance),
+            kinds(CoreKind::Efficiency),
+            kinds(CoreKind::LpEfficiency),
+            match (cpu.smt_supported, cpu.is_smt_enabled()) {
+                (_, true) => "on",
+                (true, false) => "off",
+                (false, false) => "n/a",
+            }
+        )?;
+
+        let p = CoreKind::Performance.index();
+        writeln!(
+            f,
+            "Caches: L1d {} KiB, L2 {} KiB, {} L3 domain(s){}",
+            cpu.l1d[p].size_bytes / 1024,
+            cpu.l2[p].size_bytes / 1024,
+            cpu.l3_domains.len(),
+            cpu.l3_domains
+                .first()
+                .map(|d| format!(" of {} MiB", d.size_bytes / (1024 * 1024)))
+                .unwrap_or_default()
+        )?;
+
+        let or_unknown = |v: Option<u32>| v.map_or_else(|| "?".to_string(), |v| v.to_string());
+        writeln!(
+            f,
+            "Clocks: base {} MHz, max {} MHz, package limit {} W",
+            or_unknown(cpu.base_frequency_mhz),
+            or_unknown(cpu.max_frequency_mhz),
+            or_unknown(cpu.tdp_watts)
+        )?;
+
+        match &self.affinity {
+            Some(mask) => writeln!(
+                f,
+                "Affinity: {} ({} usable)",
+                mask, self.usable_logical_cores
+            )?,
+            None => writeln!(
+                f,
+                "Affinity: unavailable ({} usable)",
+                self.usable_logical_cores
+            )?,
+        }
+
+        writeln!(
+            f,
+            "Priority: {} of 7 levels distinct, Highest {} Normal",
+            self.priority.distinct_levels(),
+            if self
+                .priority
+                .distinct(ThreadPriority::Highest, ThreadPriority::Normal)
+            {
+                "above"
+            } else {
+                "same as"
+            }
+        )?;
+        writeln!(
+            f,
+            "Thread priority: {}; real-time {}",
+            self.thread_priority.as_deref().unwrap_or("unavailable"),
+            if self.realtime_grantable {
+                "grantable"
+            } else {
+                "not grantable"
+            }
+        )?;
+        match self.cgroup_cpu_limit {
+            Some(cpus) => writeln!(f, "CPU limit: {:.2} CPUs (cgroup cpu.max)", cpus)?,
+            None => writeln!(f, "CPU limit: none")?,
+        }
+
+        if cpu.detection_warnings.is_empty() {
+            write!(f, "Detection warnings: none")
+        } else {
+            write!(f, "Detection warnings:")?;
+            for warning in &cpu.detection_warnings {
+                write!(f, "\n  - {}", warning)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_matches_detection() {
+        let Ok(report) = system_report() else {
+            return; // unsupported platform - detection itself fails
+        };
+        let info = CpuInfo::detect().unwrap();
+
+        assert_eq!(report.cpu.num_logical_cores(), info.num_logical_cores());
+        assert_eq!(report.cpu.core_count, info.core_count);
+        assert!(report.usable_logical_cores >= 1);
+        assert!(report.usable_logical_cores <= info.num_logical_cores());
+
+        let text = report.to_string();
+        assert!(text.starts_with("gdt-cpus "));
+        assert!(text.contains(&info.model_name));
+        assert!(text.contains("Thread priority: "));
+        assert!(text.contains("CPU limit: "));
+        assert!(text.contains("Detection warnings:"));
+
+        assert_eq!(
+            report.realtime_grantable,
+            crate::can_set_realtime_priority()
+        );
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        assert!(report.thread_priority.is_some());
+    }
+}
@@ -0,0 +1,238 @@
+//! Busy-wait helpers for lock-free code: a CPU relax hint and short,
+//! time-bounded spins.
+//!
+//! Everything here BURNS the core it runs on - no syscall, no yield to the
+//! scheduler - except [`Backoff`], which falls back to yielding once its
+//! spin budget is spent. Use it for waits expected to end within a few
+//! microseconds (a lock holder about to release, a producer about to
+//! publish); anything longer belongs in a parking primitive.
+
+use std::time::{Duration, Instant};
+
+use crate::CoreKind;
+
+/// One CPU relax hint: `PAUSE` on x86, `YIELD`/`ISB` on ARM (whatever
+/// [`std::hint::spin_loop`] lowers to). Call it in the body of every spin
+/// loop - it saves power, frees execution resources for an SMT sibling and
+/// avoids the memory-order mis-speculation penalty on loop exit.
+#[inline]
+pub fn spin_hint() {
+    std::hint::spin_loop();
+}
+
+/// Busy-waits for at least `duration`, issuing [`spin_hint`] between clock
+/// reads.
+///
+/// Returns as soon as the deadline has passed, so the overshoot is one
+/// hint plus one clock read (unless the thread is preempted mid-spin, which
+/// no busy-wait can prevent). This is a busy-wait: the core stays 100% busy
+/// for the whole duration.
+pub fn spin_for(duration: Duration) {
+    let start = Instant::now();
+
+    while start.elapsed() < duration {
+        spin_hint();
+    }
+}
+
+/// Measured cost of one [`spin_hint`] on the current machine, for backoff
+/// loops that count hints instead of reading the clock.
+///
+/// Measured rather than derived from [`CpuInfo::base_frequency_mhz`](crate::CpuInfo::base_frequency_mhz):
+/// the hint's latency in cycles is a microarchitecture choice, not a clock
+/// property (`PAUSE` went from ~10 to ~140 cycles with Skylake, and AMD and
+/// ARM differ again), so a frequency alone cannot predict it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpinCalibration {
+    /// Average nanoseconds per [`spin_hint`] call.
+    pub ns_per_hint: f64,
+}
+
+impl SpinCalibration {
+    /// Times a batch of hints on the calling thread (well under a
+    /// millisecond). Run it once at startup, ideally on the core type the
+    /// spinning threads will use - hint latency differs between P and E cores.
+    pub fn measure() -> Self {
+        const HINTS: u32 = 10_000;
+
+        let start = Instant::now();
+        for _ in 0..HINTS {
+            spin_hint();
+        }
+        let elapsed = start.elapsed();
+
+        Self {
+            ns_per_hint: (elapsed.as_nanos() as f64 / HINTS as f64).max(0.1),
+        }
+    }
+
+    /// Number of [`spin_hint`] calls that take roughly `duration` (at least 1).
+    pub fn hints_for(&self, duration: Duration) -> u32 {
+        let hints = duration.as_nanos() as f64 / self.ns_per_hint;
+
+        hints.clamp(1.0, u32::MAX as f64) as u32
+    }
+
+    /// Spins for roughly `duration` by counting hints - no clock reads, so
+    /// cheaper and more uniform than [`spin_for`] but only as accurate as the
+    /// calibration (frequency scaling and core migration skew it).
+    pub fn spin_for(&self, duration: Duration) {
+        for _ in 0..self.hints_for(duration) {
+            spin_hint();
+        }
+    }
+}
+
+/// Spin-then-yield backoff for a retry loop, from
+/// [`backoff_for_core_kind`].
+///
+/// Each [`snooze`](Self::snooze) spins twice as many [`spin_hint`]s as the
+/// previous one until the spin budget is spent; from then on it yields the
+/// rest of the time slice instead (`std::thread::yield_now` - never sleeps).
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    step: u32,
+    spin_rounds: u32,
+}
+
+impl Backoff {
+    /// Number of [`snooze`](Self::snooze) calls that spin before the backoff
+    /// starts yielding. Round `n` issues `2^n` hints.
+    pub fn spin_rounds(&self) -> u32 {
+        self.spin_rounds
+    }
+
+    /// Waits one round: spins while the budget lasts, then yields.
+    pub fn snooze(&mut self) {
+        if self.is_yielding() {
+            std::thread::yield_now();
+            return;
+        }
+
+        for _ in 0..1u32 << self.step {
+            spin_hint();
+        }
+        self.step += 1;
+    }
+
+    /// `true` once the spin budget is spent and [`snooze`](Self::snooze)
+    /// yields instead of spinning.
+    pub fn is_yielding(&self) -> bool {
+        self.step >= self.spin_rounds
+    }
+
+    /// Starts over from the shortest spin - call after the awaited condition
+    /// was observed, before reusing the backoff for the next wait.
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+}
+
+/// A [`Backoff`] whose spin budget matches the relative performance of the
+/// core kind the waiting thread runs on.
+///
+/// A Performance core's spin round ends quickly and the awaited thread is
+/// usually a peer about to finish, so spinning longer pays off. An Efficiency
+/// core burns the same number of hints slower, and the thread it waits on
+/// most likely runs on a faster core - it should hand the core back to the
+/// scheduler sooner, and an LP-E island core sooner still. `Unknown` is
+/// treated as Performance.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use gdt_cpus::{CoreKind, backoff_for_core_kind};
+///
+/// let ready = AtomicBool::new(true);
+/// let mut backoff = backoff_for_core_kind(CoreKind::Efficiency);
+///
+/// while !ready.load(Ordering::Acquire) {
+///     backoff.snooze();
+/// }
+/// ```
+pub fn backoff_for_core_kind(kind: CoreKind) -> Backoff {
+    // Rounds double the hint count: 7 rounds = 127 hints before yielding,
+    // 5 = 31, 3 = 7.
+    let spin_rounds = match kind {
+        CoreKind::Performance | CoreKind::Unknown => 7,
+        CoreKind::Efficiency => 5,
+        CoreKind::LpEfficiency => 3,
+    };
+
+    Backoff {
+        step: 0,
+        spin_rounds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spin_for_waits_at_least_the_duration() {
+        let wanted = Duration::from_millis(5);
+        let start = Instant::now();
+
+        spin_for(wanted);
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= wanted);
+        // Generous slack for a loaded CI runner; the point is "returns", not
+        // "returns within N µs".
+        assert!(elapsed < wanted + Duration::from_millis(250));
+    }
+
+    #[test]
+    fn spin_for_zero_returns_immediately() {
+        let start = Instant::now();
+
+        spin_for(Duration::ZERO);
+
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn calibration_scales_hint_count_with_duration() {
+        let cal = SpinCalibration { ns_per_hint: 40.0 };
+
+        assert_eq!(cal.hints_for(Duration::from_micros(1)), 25);
+        assert_eq!(cal.hints_for(Duration::from_micros(10)), 250);
+        assert_eq!(cal.hints_for(Duration::ZERO), 1);
+
+        let live = SpinCalibration::measure();
+        assert!(live.ns_per_hint > 0.0);
+    }
+
+    #[test]
+    fn efficiency_backoff_yields_sooner() {
+        let snoozes_until_yield = |kind| {
+            let mut backoff = backoff_for_core_kind(kind);
+            let mut snoozes = 0;
+            while !backoff.is_yielding() {
+                backoff.snooze();
+                snoozes += 1;
+            }
+            snoozes
+        };
+
+        let p = snoozes_until_yield(CoreKind::Performance);
+        let e = snoozes_until_yield(CoreKind::Efficiency);
+        let lp_e = snoozes_until_yield(CoreKind::LpEfficiency);
+        assert!(lp_e < e && e < p, "LP-E {lp_e}, E {e}, P {p}");
+        assert_eq!(
+            p,
+            backoff_for_core_kind(CoreKind::Performance).spin_rounds()
+        );
+        assert_eq!(snoozes_until_yield(CoreKind::Unknown), p);
+
+        let mut backoff = backoff_for_core_kind(CoreKind::Efficiency);
+        for _ in 0..e + 2 {
+            backoff.snooze();
+        }
+        assert!(backoff.is_yielding());
+        backoff.reset();
+        assert!(!backoff.is_yielding());
+    }
+}
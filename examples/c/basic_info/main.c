@@ -12,7 +12,8 @@ int main(void)
 
   if (res != GDT_CPUS_ERROR_CODE_SUCCESS)
   {
-    printf("Error retrieving CPU info: %s\n", gdt_cpus_error_code_description(res));
+    printf("Error retrieving CPU info: %s (%s)\n", gdt_cpus_error_code_description(res),
+           gdt_cpus_last_error_message());
     return 1;
   }
 
@@ -35,6 +36,16 @@ int main(void)
   printf("Efficiency cores: %" PRIu64 "\n", info.efficiency_cores);
   printf("LP-Efficiency cores: %" PRIu64 "\n", info.lp_efficiency_cores);
   printf("NUMA nodes: %" PRIu64 "\n", info.numa_node_count);
+
+  uint32_t mhz = 0;
+  if (gdt_cpus_base_frequency_mhz(&mhz) == GDT_CPUS_ERROR_CODE_SUCCESS)
+    printf("Base clock: %u MHz\n", mhz);
+  else
+    printf("Base clock: unknown\n");
+  if (gdt_cpus_max_frequency_mhz(&mhz) == GDT_CPUS_ERROR_CODE_SUCCESS)
+    printf("Max clock: %u MHz\n", mhz);
+  else
+    printf("Max clock: unknown\n");
   printf("Hybrid architecture: %s\n", is_hybrid ? "Yes" : "No");
 
   printf("\nL3 domains: %" PRIu64 "\n", info.l3_domain_count);